@@ -0,0 +1,23 @@
+// Packs an asset folder into a single pak archive
+//
+//     cargo run --example pack_assets -- assets assets.pak
+
+use std::{env, process};
+
+use quad::asset::pack_asset_folder;
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [folder, output] = args.as_slice() else {
+        eprintln!("Usage: pack_assets <asset folder> <output archive>");
+        process::exit(2);
+    };
+
+    match pack_asset_folder(folder, output) {
+        Ok(count) => println!("Packed {} files into {}", count, output),
+        Err(error) => {
+            eprintln!("Failed to pack {}: {}", folder, error);
+            process::exit(1);
+        }
+    }
+}
@@ -1,10 +1,16 @@
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, DeriveInput};
+use syn::{parse_macro_input, parse_quote, DeriveInput, Error, LitStr};
 
 pub fn derive_component(input: TokenStream) -> TokenStream {
     let mut ast = parse_macro_input!(input as DeriveInput);    
 
+    let storage = match get_storage_type(&ast) {
+        Ok(storage) => storage,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
     ast.generics
         .make_where_clause()
         .predicates
@@ -14,6 +20,36 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
     let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
 
     quote! {
-        impl #impl_generics ::quad::ecs::Component for #struct_name #type_generics #where_clause {}
+        impl #impl_generics ::quad::ecs::Component for #struct_name #type_generics #where_clause {
+            #storage
+        }
     }.into()
 }
+
+// Parses `#[component(storage = "SparseSet")]`
+fn get_storage_type(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut storage = TokenStream2::new();
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("storage") {
+                return Err(meta.error("Unsupported component attribute"));
+            }
+
+            let value = meta.value()?.parse::<LitStr>()?;
+            storage = match value.value().as_str() {
+                "Table" => quote! {},
+                "SparseSet" => quote! {
+                    const STORAGE_TYPE: ::quad::ecs::StorageType = ::quad::ecs::StorageType::SparseSet;
+                },
+                _ => {
+                    return Err(Error::new(
+                        value.span(),
+                        "Expected storage to be \"Table\" or \"SparseSet\"",
+                    ))
+                }
+            };
+            Ok(())
+        })?;
+    }
+    Ok(storage)
+}
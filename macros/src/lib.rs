@@ -2,11 +2,13 @@ use proc_macro::TokenStream;
 
 mod component;
 mod event;
+mod label;
 mod resource;
 mod bundle;
 mod param_set;
+mod reflect;
 
-#[proc_macro_derive(Component)]
+#[proc_macro_derive(Component, attributes(component))]
 pub fn derive_component(input: TokenStream) -> TokenStream {
     component::derive_component(input)
 }
@@ -26,6 +28,21 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
     bundle::derive_bundle(input)
 }
 
+#[proc_macro_derive(Reflect, attributes(reflect))]
+pub fn derive_reflect(input: TokenStream) -> TokenStream {
+    reflect::derive_reflect(input)
+}
+
+#[proc_macro_derive(SystemLabel)]
+pub fn derive_system_label(input: TokenStream) -> TokenStream {
+    label::derive_system_label(input)
+}
+
+#[proc_macro_derive(StageLabel)]
+pub fn derive_stage_label(input: TokenStream) -> TokenStream {
+    label::derive_stage_label(input)
+}
+
 #[proc_macro]
 pub fn impl_param_set(input: TokenStream) -> TokenStream {
     param_set::impl_param_set(input)
@@ -0,0 +1,184 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, Index, LitStr,
+};
+
+pub fn derive_reflect(input: TokenStream) -> TokenStream {
+    let mut ast = parse_macro_input!(input as DeriveInput);
+
+    let is_value = match parse_reflect_attrs(&ast.attrs, "value") {
+        Ok(is_value) => is_value,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    let fields = if is_value {
+        None
+    } else {
+        match get_reflected_fields(&ast.data) {
+            Ok(fields) => Some(fields),
+            Err(e) => return e.into_compile_error().into(),
+        }
+    };
+
+    let where_clause = ast.generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote! { Self: Send + Sync + 'static });
+    if is_value {
+        where_clause.predicates.push(parse_quote! { Self: Clone });
+    }
+    if let Some((_, _, field_types)) = &fields {
+        for field_type in field_types.iter() {
+            where_clause
+                .predicates
+                .push(parse_quote! { #field_type: ::quad::reflect::Reflect });
+        }
+    }
+
+    let struct_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
+
+    let body = match fields {
+        None => quote! {
+            fn type_info() -> ::quad::reflect::TypeInfo {
+                ::quad::reflect::TypeInfo::new::<Self>(::quad::reflect::TypeKind::Value)
+            }
+
+            fn apply(
+                &mut self,
+                value: &dyn ::quad::reflect::Reflect,
+            ) -> Result<(), ::quad::reflect::ReflectError> {
+                let value = value.downcast_ref::<Self>().ok_or(
+                    ::quad::reflect::ReflectError::TypeMismatch {
+                        expected: ::std::any::type_name::<Self>(),
+                        found: value.type_name(),
+                    },
+                )?;
+                *self = value.clone();
+                Ok(())
+            }
+        },
+        Some((kind, field_names, field_types)) => {
+            let field_accessors = field_names.iter().map(|(name, _)| name).collect::<Vec<_>>();
+            let field_strings = field_names.iter().map(|(_, name)| name).collect::<Vec<_>>();
+            quote! {
+                fn type_info() -> ::quad::reflect::TypeInfo {
+                    ::quad::reflect::TypeInfo::new::<Self>(::quad::reflect::TypeKind::#kind(vec![
+                        #(::quad::reflect::FieldInfo::new::<#field_types>(#field_strings)),*
+                    ]))
+                }
+
+                fn field_names(&self) -> &'static [&'static str] {
+                    &[#(#field_strings),*]
+                }
+
+                fn field(&self, name: &str) -> Option<&dyn ::quad::reflect::Reflect> {
+                    match name {
+                        #(#field_strings => Some(&self.#field_accessors),)*
+                        _ => None,
+                    }
+                }
+
+                fn field_mut(&mut self, name: &str) -> Option<&mut dyn ::quad::reflect::Reflect> {
+                    match name {
+                        #(#field_strings => Some(&mut self.#field_accessors),)*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::quad::reflect::Reflect for #struct_name #type_generics #where_clause {
+            #body
+
+            #[inline]
+            fn type_name(&self) -> &'static str {
+                ::std::any::type_name::<Self>()
+            }
+
+            #[inline]
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            #[inline]
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+
+            #[inline]
+            fn as_reflect(&self) -> &dyn ::quad::reflect::Reflect {
+                self
+            }
+
+            #[inline]
+            fn as_reflect_mut(&mut self) -> &mut dyn ::quad::reflect::Reflect {
+                self
+            }
+        }
+    }
+    .into()
+}
+
+type ReflectedFields<'a> = (
+    TokenStream2,
+    Vec<(TokenStream2, LitStr)>,
+    Vec<&'a syn::Type>,
+);
+
+// Fields marked with `#[reflect(ignore)]` are not accessible through reflection
+fn get_reflected_fields(data: &Data) -> syn::Result<ReflectedFields<'_>> {
+    let (kind, fields) =
+        match data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => (quote! { Struct }, &fields.named),
+                Fields::Unnamed(fields) => (quote! { TupleStruct }, &fields.unnamed),
+                Fields::Unit => return Ok((quote! { Struct }, Vec::new(), Vec::new())),
+            },
+            _ => return Err(Error::new(
+                Span::call_site(),
+                "Reflect can only be derived for structs, use #[reflect(value)] for other types",
+            )),
+        };
+
+    let mut names = Vec::new();
+    let mut types = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if parse_reflect_attrs(&field.attrs, "ignore")? {
+            continue;
+        }
+        let name = match &field.ident {
+            Some(ident) => (
+                quote! { #ident },
+                LitStr::new(&ident.to_string(), ident.span()),
+            ),
+            None => {
+                let index = Index::from(index);
+                let name = LitStr::new(&index.index.to_string(), Span::call_site());
+                (quote! { #index }, name)
+            }
+        };
+        names.push(name);
+        types.push(&field.ty);
+    }
+    Ok((kind, names, types))
+}
+
+// Parses `#[reflect(value)]` on the type or `#[reflect(ignore)]` on a field
+fn parse_reflect_attrs(attrs: &[Attribute], expected: &str) -> syn::Result<bool> {
+    let mut found = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("reflect")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(expected) {
+                return Err(meta.error("Unsupported reflect attribute"));
+            }
+            found = true;
+            Ok(())
+        })?;
+    }
+    Ok(found)
+}
@@ -1,43 +1,58 @@
+mod descriptor;
+mod plugin;
 mod stage;
+mod state;
 mod systems;
 mod task_pool_options;
 
-pub use stage::{MainStage, RenderStage, StageLabel};
+pub use descriptor::{
+    IntoSystemDescriptor, SystemDescriptor, SystemLabel, SystemLabelId, SystemSet,
+};
+pub use plugin::{Plugin, PluginGroup, PluginGroupBuilder};
+pub use quad_macros::{StageLabel, SystemLabel};
+pub use stage::{
+    CommandFlush, MainStage, RenderStage, StageExecutor, StageId, StageLabel, StageSettings,
+};
+pub use state::{State, StateScoped, StateValue, in_state};
+pub use systems::ScheduleError;
 pub use task_pool_options::TaskPoolOptions;
 
+use std::{any::TypeId, collections::HashSet};
+
 use crate::{
-    asset::{
-        Asset, AssetEvent, AssetLoader, AssetServer, AssetServerSettings, Assets, asset_plugin,
-        update_asset_storage_system,
-    },
-    audio::{AudioDevice, audio_plugin},
+    asset::{Asset, AssetEvent, AssetLoader, AssetServer, Assets, update_asset_storage_system},
     ecs::{
-        Event, Events, FromWorld, IntoSystem, ReadOnlySystemParamFetch, Res, ResMut, Resource,
-        SystemParam, World,
+        Component, EcsDiagnostics, Event, EventRetention, Events, FromWorld, MapEntities,
+        ReadOnlySystemParamFetch, Res, ResMut, Resource, SystemParam, World,
     },
-    input::input_plugin,
-    pipeline::core_pipeline_plugin,
+    reflect::{Reflect, TypeRegistry},
     render::{
         render_phase::{DrawFunctions, PhaseItem, RenderCommand, RenderCommandState},
-        render_plugin, update_render_app,
+        update_render_app,
     },
     run::{Scene, SceneResult, SceneStage},
-    sprite::sprite_plugin,
-    text::text_plugin,
-    timing::{Time, timing_plugin},
-    transform::transform_plugin,
-    ui::ui_plugin,
-    windowing::{Window, Windows, windowing_plugin},
+    scene::AppSceneRegistry,
+    timing::{FixedTime, FixedTimeAlpha, Time},
+    windowing::{Window, Windows},
 };
 
+use serde::{Serialize, de::DeserializeOwned};
+
 use self::systems::Systems;
 
-pub mod prelude {}
+pub mod prelude {
+    pub use crate::app::{
+        IntoSystemDescriptor, Plugin, PluginGroup, StageLabel, StageSettings, State, StateScoped,
+        SystemLabel, SystemSet, in_state,
+    };
+}
 
 #[derive(Default)]
 pub struct App {
     pub(crate) world: World,     // TODO: Private?
     pub(crate) systems: Systems, // TODO: Private?
+    // Event types whose update system is scheduled
+    events: HashSet<TypeId>,
 }
 
 impl App {
@@ -46,49 +61,35 @@ impl App {
         Default::default()
     }
 
-    pub(crate) fn create_pools(&mut self, options: &TaskPoolOptions) {
-        options.create_pools(&mut self.world);
-    }
-
-    pub fn add_timing_plugin(&mut self) -> &mut Self {
-        timing_plugin(self);
-        self
-    }
-
-    pub fn add_windowing_plugin(&mut self) -> &mut Self {
-        windowing_plugin(self);
-        self
+    #[inline]
+    pub fn world(&self) -> &World {
+        &self.world
     }
 
-    pub fn add_input_plugin(&mut self) -> &mut Self {
-        input_plugin(self);
-        self
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
     }
 
-    pub fn add_asset_plugin(&mut self, settings: &AssetServerSettings) -> &mut Self {
-        self.world.insert_resource(settings.clone());
-        asset_plugin(self);
-        self
+    // Runs all main stages once without a scene or the render app, e.g. to step a headless app
+    // from a test or a server loop. Time is only advanced if the timing plugin was added.
+    pub fn update(&mut self) {
+        self.update_before_scene();
+        self.update_after_scene();
     }
 
-    // TODO Move to MainApp trait, should not be public
-    pub fn add_audio_plugin(&mut self, audio_device: &AudioDevice) -> &mut Self {
-        audio_plugin(self, audio_device);
-        self
+    // Unknown labels and ordering cycles otherwise only panic once the stage first runs
+    pub fn validate_schedule(&self) -> Result<(), ScheduleError> {
+        self.systems.validate()
     }
 
-    pub fn add_transform_plugin(&mut self) -> &mut Self {
-        transform_plugin(self);
-        self
+    pub(crate) fn create_pools(&mut self, options: &TaskPoolOptions) {
+        options.create_pools(&mut self.world);
     }
 
-    // TODO Move to MainApp trait
-    pub(crate) fn add_render_plugin(&mut self, render_app: &mut App) -> &mut Self {
-        render_plugin(self, render_app);
-        core_pipeline_plugin(self, render_app);
-        sprite_plugin(self, render_app);
-        text_plugin(self, render_app);
-        ui_plugin(self, render_app);
+    // Per-frame archetype, table and system statistics in the EcsDiagnostics resource
+    pub fn add_ecs_diagnostics(&mut self) -> &mut Self {
+        self.world.init_resource::<EcsDiagnostics>();
         self
     }
 
@@ -117,20 +118,127 @@ impl App {
         self.world.resource_mut()
     }
 
+    pub fn add_stage_before<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.systems.add_stage(target, label, settings, true);
+        self
+    }
+
+    pub fn add_stage_after<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.systems.add_stage(target, label, settings, false);
+        self
+    }
+
     pub fn add_system_to_stage<L, S, Params>(&mut self, stage: L, system: S) -> &mut Self
     where
         L: StageLabel,
-        S: IntoSystem<(), (), Params>,
+        S: IntoSystemDescriptor<Params>,
     {
-        self.systems.add(stage, system.system());
+        self.systems.add(stage, system.into_descriptor());
+        self
+    }
+
+    pub fn add_system_set_to_stage<L>(&mut self, stage: L, system_set: SystemSet) -> &mut Self
+    where
+        L: StageLabel + Copy,
+    {
+        for descriptor in system_set.into_descriptors() {
+            self.systems.add(stage, descriptor);
+        }
+        self
+    }
+
+    // The enter systems of the initial value run before the first frame's fixed update
+    pub fn add_state<S: StateValue>(&mut self, initial: S) -> &mut Self {
+        self.insert_resource(State::new(initial));
+        self.systems.add_state::<S>();
+        self
+    }
+
+    pub fn add_system_set_on_enter<S: StateValue>(
+        &mut self,
+        value: S,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        let state = self
+            .systems
+            .state_mut::<S>()
+            .expect("State not added to the app");
+        for descriptor in system_set.into_descriptors() {
+            state.add_on_enter(value.clone(), descriptor);
+        }
+        self
+    }
+
+    pub fn add_system_set_on_exit<S: StateValue>(
+        &mut self,
+        value: S,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        let state = self
+            .systems
+            .state_mut::<S>()
+            .expect("State not added to the app");
+        for descriptor in system_set.into_descriptors() {
+            state.add_on_exit(value.clone(), descriptor);
+        }
         self
     }
 
+    pub fn add_system_set_on_update<L, S>(
+        &mut self,
+        stage: L,
+        value: S,
+        system_set: SystemSet,
+    ) -> &mut Self
+    where
+        L: StageLabel + Copy,
+        S: StateValue,
+    {
+        self.add_system_set_to_stage(stage, system_set.run_if(in_state(value)))
+    }
+
     // TODO: AddEvent trait ?
     pub fn add_event<T: Event>(&mut self) -> &mut Self {
         self.init_resource::<Events<T>>()
-            .add_system_to_stage(MainStage::PreUpdate, Events::<T>::update_system);
+            .add_event_update_system::<T>()
+    }
 
+    // Keeps events that were already sent if the channel exists
+    pub fn add_event_with_retention<T: Event>(&mut self, retention: EventRetention) -> &mut Self {
+        match self.world.get_resource_mut::<Events<T>>() {
+            Some(mut events) => events.set_retention(retention),
+            None => {
+                self.world
+                    .insert_resource(Events::<T>::with_retention(retention));
+            }
+        }
+        self.add_event_update_system::<T>()
+    }
+
+    // The channel may have been inserted as a plain resource, so its existence does not tell
+    // whether the update system is scheduled
+    fn add_event_update_system<T: Event>(&mut self) -> &mut Self {
+        if self.events.insert(TypeId::of::<T>()) {
+            self.add_system_to_stage(MainStage::PreUpdate, Events::<T>::update_system);
+        }
         self
     }
 
@@ -162,6 +270,101 @@ impl App {
         self
     }
 
+    pub fn register_type<T: Reflect>(&mut self) -> &mut Self {
+        self.type_registry().register::<T>();
+        self
+    }
+
+    pub fn register_component_type<T: Reflect + Component>(&mut self) -> &mut Self {
+        self.type_registry().register_component::<T>();
+        self
+    }
+
+    pub fn register_resource_type<T: Reflect + Resource>(&mut self) -> &mut Self {
+        self.type_registry().register_resource::<T>();
+        self
+    }
+
+    fn type_registry(&mut self) -> ResMut<TypeRegistry> {
+        self.world
+            .get_resource_mut::<TypeRegistry>()
+            .expect("Reflect plugin not initialized")
+    }
+
+    // The name identifies the component in scene files and must not change once scenes are saved
+    pub fn register_scene_component<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        self.world
+            .get_resource::<AppSceneRegistry>()
+            .expect("Scene plugin not initialized")
+            .write()
+            .register::<T>(name);
+        self
+    }
+
+    pub fn register_scene_component_mapped<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+    {
+        self.world
+            .get_resource::<AppSceneRegistry>()
+            .expect("Scene plugin not initialized")
+            .write()
+            .register_mapped::<T>(name);
+        self
+    }
+
+    pub fn register_cloneable<T: Component + Clone>(&mut self) -> &mut Self {
+        self.world.register_cloneable::<T>();
+        self
+    }
+
+    pub fn register_cloneable_mapped<T: Component + Clone + MapEntities>(&mut self) -> &mut Self {
+        self.world.register_cloneable_mapped::<T>();
+        self
+    }
+
+    fn update_before_scene(&mut self) {
+        if let Some(mut time) = self.world.get_resource_mut::<Time>() {
+            time.update();
+        }
+        self.systems.run(MainStage::LoadAssets, &mut self.world);
+        self.systems.run(MainStage::PreUpdate, &mut self.world);
+        self.systems.apply_state_transitions(&mut self.world);
+        self.run_fixed_update();
+    }
+
+    fn update_after_scene(&mut self) {
+        self.systems
+            .run(MainStage::PreTransformUpdate, &mut self.world);
+        self.systems
+            .run(MainStage::TransformUpdate, &mut self.world);
+        self.systems
+            .run(MainStage::PostTransformUpdate, &mut self.world);
+        self.systems.run(MainStage::AssetEvents, &mut self.world);
+        self.systems.run(MainStage::Flush, &mut self.world);
+        self.world.update_ecs_diagnostics();
+        self.world.clear_trackers();
+    }
+
+    // Systems of the fixed update stage see the fixed step as the time delta
+    fn run_fixed_update(&mut self) {
+        let Some(delta) = self.world.get_resource::<Time>().map(|time| time.delta()) else {
+            return;
+        };
+        self.world.resource_mut::<FixedTime>().accumulate(delta);
+        while self.world.resource_mut::<FixedTime>().expend() {
+            let step = self.world.resource::<FixedTime>().step();
+            self.world.resource_mut::<Time>().set_delta(step);
+            self.systems.run(MainStage::FixedUpdate, &mut self.world);
+        }
+        self.world.resource_mut::<Time>().set_delta(delta);
+        let alpha = self.world.resource::<FixedTime>().alpha();
+        self.world.resource_mut::<FixedTimeAlpha>().0 = alpha;
+    }
+
     // TODO AddRenderCommand trait?
     pub(crate) fn add_render_command<P: PhaseItem, C: RenderCommand<P> + Send + Sync + 'static>(
         &mut self,
@@ -186,7 +389,7 @@ impl App {
 pub trait MainApp {
     fn update_main_app(
         &mut self,
-        render_app: &mut App,
+        render_app: Option<&mut App>,
         scene: &mut dyn Scene,
         stage: SceneStage,
     ) -> SceneResult;
@@ -195,31 +398,89 @@ pub trait MainApp {
 impl MainApp for App {
     fn update_main_app(
         &mut self,
-        render_app: &mut App,
+        render_app: Option<&mut App>,
         scene: &mut dyn Scene,
         stage: SceneStage,
     ) -> SceneResult {
-        self.world.resource_mut::<Time>().update();
-        self.systems.run(MainStage::LoadAssets, &mut self.world);
-        self.systems.run(MainStage::PreUpdate, &mut self.world);
+        self.update_before_scene();
 
         let result = scene.update(stage, &mut self.world);
         if matches!(result, SceneResult::Quit) {
             return result;
         }
 
-        self.systems
-            .run(MainStage::PreTransformUpdate, &mut self.world);
-        self.systems
-            .run(MainStage::TransformUpdate, &mut self.world);
-        self.systems
-            .run(MainStage::PostTransformUpdate, &mut self.world);
-        self.systems.run(MainStage::AssetEvents, &mut self.world);
-        self.systems.run(MainStage::Flush, &mut self.world);
-        self.world.clear_trackers();
-
-        update_render_app(&mut self.world, render_app);
+        self.update_after_scene();
+        if let Some(render_app) = render_app {
+            update_render_app(&mut self.world, render_app);
+        }
 
         result
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        app::{App, MainStage},
+        ecs::{Event, EventRetention, Events, Res, ResMut, Resource},
+        timing::{FixedTime, FixedTimeAlpha, Time, timing_plugin},
+    };
+
+    #[derive(Resource, Default)]
+    struct Steps(Vec<Duration>);
+
+    fn record_step(time: Res<Time>, mut steps: ResMut<Steps>) {
+        steps.0.push(time.delta());
+    }
+
+    #[test]
+    fn fixed_update_steps() {
+        let mut app = App::new();
+        timing_plugin(&mut app);
+        app.insert_resource(FixedTime::new(Duration::from_millis(10)))
+            .init_resource::<Steps>()
+            .add_system_to_stage(MainStage::FixedUpdate, record_step);
+
+        let start = Instant::now();
+        let frame = |app: &mut App, ms: u64| {
+            app.world
+                .resource_mut::<Time>()
+                .update_with_instant(start + Duration::from_millis(ms));
+            app.run_fixed_update();
+            std::mem::take(&mut app.world.resource_mut::<Steps>().0)
+        };
+
+        assert!(frame(&mut app, 0).is_empty());
+        let step = Duration::from_millis(10);
+        assert_eq!(frame(&mut app, 25), vec![step, step]);
+        assert_eq!(
+            app.world.resource::<Time>().delta(),
+            Duration::from_millis(25)
+        );
+        assert_eq!(app.world.resource::<FixedTimeAlpha>().0, 0.5);
+        assert_eq!(frame(&mut app, 30), vec![step]);
+        assert!(frame(&mut app, 35).is_empty());
+        assert_eq!(app.world.resource::<FixedTime>().elapsed(), step * 3);
+    }
+
+    #[derive(Event)]
+    struct Ping;
+
+    #[test]
+    fn event_update_system_is_added_once() {
+        let mut app = App::new();
+        app.init_resource::<Events<Ping>>()
+            .add_event_with_retention::<Ping>(EventRetention::Frames(2))
+            .add_event::<Ping>();
+        app.world.resource_mut::<Events<Ping>>().send(Ping);
+
+        let update = |app: &mut App| {
+            app.systems.run(MainStage::PreUpdate, &mut app.world);
+            app.world.resource::<Events<Ping>>().len()
+        };
+        assert_eq!(update(&mut app), 1);
+        assert_eq!(update(&mut app), 0);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    any::TypeId,
+    collections::hash_map::DefaultHasher,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use crate::ecs::{BoxedCondition, BoxedSystem, IntoSystem};
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct SystemLabelId {
+    type_id: TypeId,
+    hash: u64,
+}
+
+pub trait SystemLabel: Debug + Hash + Send + Sync + 'static {
+    fn id(&self) -> SystemLabelId {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        SystemLabelId {
+            type_id: TypeId::of::<Self>(),
+            hash: hasher.finish(),
+        }
+    }
+
+    // Shown in schedule errors
+    fn name(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl SystemLabel for &'static str {
+    fn name(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct LabelEntry {
+    pub id: SystemLabelId,
+    pub name: String,
+}
+
+impl LabelEntry {
+    fn new(label: impl SystemLabel) -> Self {
+        Self {
+            id: label.id(),
+            name: label.name(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SystemOrdering {
+    pub labels: Vec<LabelEntry>,
+    pub before: Vec<LabelEntry>,
+    pub after: Vec<LabelEntry>,
+}
+
+impl SystemOrdering {
+    fn extend(&mut self, other: &SystemOrdering) {
+        self.labels.extend(other.labels.iter().cloned());
+        self.before.extend(other.before.iter().cloned());
+        self.after.extend(other.after.iter().cloned());
+    }
+}
+
+pub struct SystemDescriptor {
+    pub(crate) system: BoxedSystem,
+    pub(crate) ordering: SystemOrdering,
+    pub(crate) conditions: Vec<BoxedCondition>,
+}
+
+impl SystemDescriptor {
+    pub fn run_if<C, Params>(mut self, condition: C) -> Self
+    where
+        C: IntoSystem<(), bool, Params>,
+    {
+        self.conditions.push(Box::new(condition.system()));
+        self
+    }
+
+    pub fn label(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.labels.push(LabelEntry::new(label));
+        self
+    }
+
+    pub fn before(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.before.push(LabelEntry::new(label));
+        self
+    }
+
+    pub fn after(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.after.push(LabelEntry::new(label));
+        self
+    }
+}
+
+pub struct DescriptorMarker;
+
+pub trait IntoSystemDescriptor<Params> {
+    fn into_descriptor(self) -> SystemDescriptor;
+
+    fn run_if<C, ConditionParams>(self, condition: C) -> SystemDescriptor
+    where
+        Self: Sized,
+        C: IntoSystem<(), bool, ConditionParams>,
+    {
+        self.into_descriptor().run_if(condition)
+    }
+
+    fn label(self, label: impl SystemLabel) -> SystemDescriptor
+    where
+        Self: Sized,
+    {
+        self.into_descriptor().label(label)
+    }
+
+    fn before(self, label: impl SystemLabel) -> SystemDescriptor
+    where
+        Self: Sized,
+    {
+        self.into_descriptor().before(label)
+    }
+
+    fn after(self, label: impl SystemLabel) -> SystemDescriptor
+    where
+        Self: Sized,
+    {
+        self.into_descriptor().after(label)
+    }
+}
+
+impl IntoSystemDescriptor<DescriptorMarker> for SystemDescriptor {
+    fn into_descriptor(self) -> SystemDescriptor {
+        self
+    }
+}
+
+impl<S, Params> IntoSystemDescriptor<Params> for S
+where
+    S: IntoSystem<(), (), Params>,
+{
+    fn into_descriptor(self) -> SystemDescriptor {
+        SystemDescriptor {
+            system: Box::new(self.system()),
+            ordering: Default::default(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+type ConditionFactory = Box<dyn Fn() -> BoxedCondition>;
+
+// A group of systems sharing the set labels, ordering constraints and run conditions, e.g. all
+// physics systems labeled as one set that runs before everything labeled as rendering preparation.
+#[derive(Default)]
+pub struct SystemSet {
+    systems: Vec<SystemDescriptor>,
+    ordering: SystemOrdering,
+    conditions: Vec<ConditionFactory>,
+}
+
+impl SystemSet {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_system<S, Params>(mut self, system: S) -> Self
+    where
+        S: IntoSystemDescriptor<Params>,
+    {
+        self.systems.push(system.into_descriptor());
+        self
+    }
+
+    pub fn label(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.labels.push(LabelEntry::new(label));
+        self
+    }
+
+    pub fn before(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.before.push(LabelEntry::new(label));
+        self
+    }
+
+    pub fn after(mut self, label: impl SystemLabel) -> Self {
+        self.ordering.after.push(LabelEntry::new(label));
+        self
+    }
+
+    // Every system of the set gets its own copy of the condition
+    pub fn run_if<C, Params>(mut self, condition: C) -> Self
+    where
+        C: IntoSystem<(), bool, Params> + Clone + 'static,
+    {
+        self.conditions
+            .push(Box::new(move || Box::new(condition.clone().system())));
+        self
+    }
+
+    pub(crate) fn into_descriptors(self) -> impl Iterator<Item = SystemDescriptor> {
+        let ordering = self.ordering;
+        let conditions = self.conditions;
+        self.systems.into_iter().map(move |mut descriptor| {
+            descriptor.ordering.extend(&ordering);
+            descriptor
+                .conditions
+                .extend(conditions.iter().map(|condition| condition()));
+            descriptor
+        })
+    }
+}
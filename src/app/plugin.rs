@@ -0,0 +1,253 @@
+use std::any::{Any, TypeId};
+
+use super::App;
+
+// Plugins configure the main app and the render app, e.g. by adding resources, assets and systems
+pub trait Plugin: Any {
+    fn build(&self, app: &mut App, render_app: &mut App);
+
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+pub trait PluginGroup {
+    fn build(self) -> PluginGroupBuilder;
+}
+
+struct PluginEntry {
+    type_id: TypeId,
+    plugin: Box<dyn Plugin>,
+    enabled: bool,
+}
+
+// Ordered list of plugins, built-in plugins can be replaced by custom ones in place without changing
+// the order in which they are built
+#[derive(Default)]
+pub struct PluginGroupBuilder {
+    plugins: Vec<PluginEntry>,
+}
+
+impl PluginGroupBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<P: Plugin>(self, plugin: P) -> Self {
+        let index = self.plugins.len();
+        self.insert(index, plugin)
+    }
+
+    pub fn add_before<Target: Plugin, P: Plugin>(self, plugin: P) -> Self {
+        let index = self.expect_index_of::<Target>();
+        self.insert(index, plugin)
+    }
+
+    pub fn add_after<Target: Plugin, P: Plugin>(self, plugin: P) -> Self {
+        let index = self.expect_index_of::<Target>();
+        self.insert(index + 1, plugin)
+    }
+
+    // Replaces a plugin that is part of the group with another instance of the same type, keeping
+    // its position
+    pub fn set<P: Plugin>(mut self, plugin: P) -> Self {
+        let index = self.expect_index_of::<P>();
+        self.plugins[index].plugin = Box::new(plugin);
+        self
+    }
+
+    // Replaces a plugin that is part of the group with a plugin of another type, keeping its
+    // position. The new plugin is enabled even if the old one was disabled.
+    pub fn replace<Old: Plugin, New: Plugin>(mut self, plugin: New) -> Self {
+        let index = self.expect_index_of::<Old>();
+        assert!(
+            TypeId::of::<Old>() == TypeId::of::<New>() || !self.contains::<New>(),
+            "Plugin {} is already part of the group",
+            std::any::type_name::<New>()
+        );
+        self.plugins[index] = PluginEntry {
+            type_id: TypeId::of::<New>(),
+            plugin: Box::new(plugin),
+            enabled: true,
+        };
+        self
+    }
+
+    pub fn disable<P: Plugin>(mut self) -> Self {
+        let index = self.expect_index_of::<P>();
+        self.plugins[index].enabled = false;
+        self
+    }
+
+    pub fn enable<P: Plugin>(mut self) -> Self {
+        let index = self.expect_index_of::<P>();
+        self.plugins[index].enabled = true;
+        self
+    }
+
+    pub fn contains<P: Plugin>(&self) -> bool {
+        self.index_of::<P>().is_some()
+    }
+
+    pub fn is_enabled<P: Plugin>(&self) -> bool {
+        self.index_of::<P>()
+            .is_some_and(|index| self.plugins[index].enabled)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|entry| entry.plugin.name())
+    }
+
+    pub(crate) fn into_plugins(self) -> impl Iterator<Item = (TypeId, Box<dyn Plugin>)> {
+        self.plugins
+            .into_iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| (entry.type_id, entry.plugin))
+    }
+
+    fn insert<P: Plugin>(mut self, index: usize, plugin: P) -> Self {
+        assert!(
+            !self.contains::<P>(),
+            "Plugin {} is already part of the group",
+            std::any::type_name::<P>()
+        );
+        self.plugins.insert(
+            index,
+            PluginEntry {
+                type_id: TypeId::of::<P>(),
+                plugin: Box::new(plugin),
+                enabled: true,
+            },
+        );
+        self
+    }
+
+    fn index_of<P: Plugin>(&self) -> Option<usize> {
+        self.plugins
+            .iter()
+            .position(|entry| entry.type_id == TypeId::of::<P>())
+    }
+
+    fn expect_index_of<P: Plugin>(&self) -> usize {
+        self.index_of::<P>().unwrap_or_else(|| {
+            panic!(
+                "Plugin {} is not part of the group",
+                std::any::type_name::<P>()
+            )
+        })
+    }
+}
+
+impl PluginGroup for PluginGroupBuilder {
+    fn build(self) -> PluginGroupBuilder {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{app::App, ecs::World, test_util::Log};
+
+    use super::{Plugin, PluginGroupBuilder};
+
+    struct First;
+
+    impl Plugin for First {
+        fn build(&self, app: &mut App, _render_app: &mut App) {
+            Log::push(&mut app.world, "first");
+        }
+    }
+
+    struct Second(&'static str);
+
+    impl Plugin for Second {
+        fn build(&self, app: &mut App, _render_app: &mut App) {
+            Log::push(&mut app.world, self.0);
+        }
+    }
+
+    struct Third;
+
+    impl Plugin for Third {
+        fn build(&self, _app: &mut App, render_app: &mut App) {
+            Log::push(&mut render_app.world, "third");
+        }
+    }
+
+    struct Replacement;
+
+    impl Plugin for Replacement {
+        fn build(&self, app: &mut App, _render_app: &mut App) {
+            Log::push(&mut app.world, "replacement");
+        }
+    }
+
+    fn build(group: PluginGroupBuilder) -> (World, World) {
+        let mut app = App::new();
+        let mut render_app = App::new();
+        app.world.init_resource::<Log>();
+        render_app.world.init_resource::<Log>();
+        for (_, plugin) in group.into_plugins() {
+            plugin.build(&mut app, &mut render_app);
+        }
+        (app.world, render_app.world)
+    }
+
+    #[test]
+    fn plugins_are_built_in_order() {
+        let group = PluginGroupBuilder::new()
+            .add(Second("second"))
+            .add(Third)
+            .add_before::<Second, _>(First);
+        let (app, render_app) = build(group);
+        assert_eq!(app.resource::<Log>().0, ["first", "second"]);
+        assert_eq!(render_app.resource::<Log>().0, ["third"]);
+    }
+
+    #[test]
+    fn plugins_can_be_replaced_and_disabled() {
+        let group = PluginGroupBuilder::new()
+            .add(First)
+            .add(Second("second"))
+            .add(Third)
+            .set(Second("replaced"))
+            .disable::<First>();
+        assert!(group.contains::<First>());
+        assert!(!group.is_enabled::<First>());
+
+        let (app, render_app) = build(group);
+        assert_eq!(app.resource::<Log>().0, ["replaced"]);
+        assert_eq!(render_app.resource::<Log>().0, ["third"]);
+    }
+
+    #[test]
+    fn plugins_can_be_replaced_by_other_types() {
+        let group = PluginGroupBuilder::new()
+            .add(First)
+            .add(Second("second"))
+            .add(Third)
+            .disable::<Second>()
+            .replace::<Second, _>(Replacement);
+        assert!(!group.contains::<Second>());
+        assert!(group.is_enabled::<Replacement>());
+
+        let (app, _) = build(group);
+        assert_eq!(app.resource::<Log>().0, ["first", "replacement"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is already part of the group")]
+    fn plugins_cannot_be_added_twice() {
+        let _ = PluginGroupBuilder::new()
+            .add(Second("first"))
+            .add(Second("second"));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not part of the group")]
+    fn missing_plugin_cannot_be_disabled() {
+        let _ = PluginGroupBuilder::new().add(First).disable::<Third>();
+    }
+}
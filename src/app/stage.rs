@@ -1,41 +1,78 @@
-use std::hash::Hash;
+use std::{
+    any::TypeId,
+    collections::hash_map::DefaultHasher,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
-pub struct StageId(u32);
-
-pub trait StageLabel: Eq + PartialEq + Hash {
-    fn id(&self) -> StageId;
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct StageId {
+    type_id: TypeId,
+    hash: u64,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
-pub enum MainStage {
-    LoadAssets = 0,
-    PreUpdate = 1,
-    PreTransformUpdate = 2,
-    TransformUpdate = 3,
-    PostTransformUpdate = 4,
-    AssetEvents = 5,
-    Flush = 6,
+impl StageId {
+    // Stages run by the main and render app loops
+    pub(crate) fn is_builtin(&self) -> bool {
+        self.type_id == TypeId::of::<MainStage>() || self.type_id == TypeId::of::<RenderStage>()
+    }
 }
 
-impl StageLabel for MainStage {
+pub trait StageLabel: Debug + Hash + Send + Sync + 'static {
     fn id(&self) -> StageId {
-        StageId(*self as u32)
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        StageId {
+            type_id: TypeId::of::<Self>(),
+            hash: hasher.finish(),
+        }
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum MainStage {
+    LoadAssets,
+    PreUpdate,
+    // Runs zero or more times per frame, once for every elapsed FixedTime step
+    FixedUpdate,
+    PreTransformUpdate,
+    TransformUpdate,
+    PostTransformUpdate,
+    AssetEvents,
+    Flush,
+}
+
+impl StageLabel for MainStage {}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum RenderStage {
-    Extract = 10,
-    Prepare = 11,
-    Queue = 12,
-    PhaseSort = 13,
-    Render = 14,
-    Cleanup = 15,
+    Extract,
+    Prepare,
+    Queue,
+    PhaseSort,
+    Render,
+    Cleanup,
 }
 
-impl StageLabel for RenderStage {
-    fn id(&self) -> StageId {
-        StageId(*self as u32)
-    }
+impl StageLabel for RenderStage {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StageExecutor {
+    #[default]
+    Parallel,
+    SingleThreaded,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CommandFlush {
+    #[default]
+    EndOfStage,
+    // Commands are applied at the end of the next stage that flushes
+    Deferred,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StageSettings {
+    pub executor: StageExecutor,
+    pub flush: CommandFlush,
 }
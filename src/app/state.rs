@@ -0,0 +1,256 @@
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use crate::ecs::{Component, Entity, IntoSystem, Res, Resource, System, World};
+
+use super::{
+    descriptor::SystemDescriptor,
+    systems::{ScheduleError, StageSystems},
+};
+
+pub trait StateValue: Clone + Eq + Hash + Debug + Send + Sync + 'static {}
+
+impl<T> StateValue for T where T: Clone + Eq + Hash + Debug + Send + Sync + 'static {}
+
+// Transitions are queued and applied after the PreUpdate stage. Every transition runs the exit
+// systems of the old value, despawns entities scoped to it and then runs the enter systems of the
+// new value.
+#[derive(Resource, Debug)]
+pub struct State<S: StateValue> {
+    current: S,
+    previous: Option<S>,
+    queue: VecDeque<S>,
+    entered: bool,
+}
+
+impl<S: StateValue> State<S> {
+    pub fn new(initial: S) -> Self {
+        Self {
+            current: initial,
+            previous: None,
+            queue: VecDeque::new(),
+            entered: false,
+        }
+    }
+
+    #[inline]
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    #[inline]
+    pub fn previous(&self) -> Option<&S> {
+        self.previous.as_ref()
+    }
+
+    // Transitions to the current value are skipped when the queue is applied
+    pub fn set(&mut self, next: S) {
+        self.queue.push_back(next);
+    }
+
+    pub fn queued(&self) -> impl Iterator<Item = &S> {
+        self.queue.iter()
+    }
+
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        !self.entered || !self.queue.is_empty()
+    }
+
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
+    }
+
+    // Returns the exited and entered values, the initial value is entered without exiting any
+    fn next_transition(&mut self) -> Option<(Option<S>, S)> {
+        if !self.entered {
+            self.entered = true;
+            return Some((None, self.current.clone()));
+        }
+
+        while let Some(next) = self.queue.pop_front() {
+            if next == self.current {
+                continue;
+            }
+            let exited = std::mem::replace(&mut self.current, next);
+            self.previous = Some(exited.clone());
+            return Some((Some(exited), self.current.clone()));
+        }
+        None
+    }
+}
+
+// Entities with this component are despawned together with their children when the state exits
+// the given value
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct StateScoped<S: StateValue>(pub S);
+
+pub fn in_state<S: StateValue>(value: S) -> impl System<In = (), Out = bool> + Clone {
+    (move |state: Option<Res<State<S>>>| state.is_some_and(|state| *state.current() == value))
+        .system()
+}
+
+pub(crate) trait StateDriver: Send + Sync {
+    fn apply_transitions(&mut self, world: &mut World);
+    fn validate(&self) -> Result<(), ScheduleError>;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct StateSystems<S: StateValue> {
+    enter: HashMap<S, StageSystems>,
+    exit: HashMap<S, StageSystems>,
+}
+
+impl<S: StateValue> Default for StateSystems<S> {
+    fn default() -> Self {
+        Self {
+            enter: HashMap::new(),
+            exit: HashMap::new(),
+        }
+    }
+}
+
+impl<S: StateValue> StateSystems<S> {
+    pub fn add_on_enter(&mut self, value: S, descriptor: SystemDescriptor) {
+        self.enter.entry(value).or_default().add(descriptor);
+    }
+
+    pub fn add_on_exit(&mut self, value: S, descriptor: SystemDescriptor) {
+        self.exit.entry(value).or_default().add(descriptor);
+    }
+
+    fn run(systems: &mut HashMap<S, StageSystems>, value: &S, world: &mut World) {
+        if let Some(systems) = systems.get_mut(value) {
+            systems.run(world);
+            systems.apply_buffers(world);
+        }
+    }
+
+    fn despawn_scoped(value: &S, world: &mut World) {
+        let entities = world
+            .query::<(Entity, &StateScoped<S>)>()
+            .iter(world)
+            .filter(|(_, scoped)| scoped.0 == *value)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in entities {
+            world.despawn_recursive(entity);
+        }
+    }
+}
+
+impl<S: StateValue> StateDriver for StateSystems<S> {
+    fn apply_transitions(&mut self, world: &mut World) {
+        // Checked first so that the resource is not marked as changed every frame
+        while world.resource::<State<S>>().is_pending() {
+            let Some((exited, entered)) = world.resource_mut::<State<S>>().next_transition() else {
+                break;
+            };
+
+            if let Some(exited) = exited {
+                Self::run(&mut self.exit, &exited, world);
+                Self::despawn_scoped(&exited, world);
+            }
+            Self::run(&mut self.enter, &entered, world);
+        }
+    }
+
+    fn validate(&self) -> Result<(), ScheduleError> {
+        for systems in self.enter.values().chain(self.exit.values()) {
+            systems.validate()?;
+        }
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        app::{IntoSystemDescriptor, MainStage, systems::Systems},
+        ecs::{Entity, World},
+        test_util::{Log, log},
+    };
+
+    use super::{State, StateScoped, in_state};
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum Screen {
+        Menu,
+        Game,
+        Pause,
+    }
+
+    fn setup() -> (World, Systems) {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.insert_resource(State::new(Screen::Menu));
+
+        let mut systems = Systems::default();
+        let state = systems.add_state::<Screen>();
+        state.add_on_enter(Screen::Menu, log("enter menu").into_descriptor());
+        state.add_on_exit(Screen::Menu, log("exit menu").into_descriptor());
+        state.add_on_enter(Screen::Game, log("enter game").into_descriptor());
+        systems.add(
+            MainStage::PreUpdate,
+            log("update game").run_if(in_state(Screen::Game)),
+        );
+        (world, systems)
+    }
+
+    fn frame(world: &mut World, systems: &mut Systems) -> Vec<&'static str> {
+        systems.apply_state_transitions(world);
+        systems.run(MainStage::PreUpdate, world);
+        Log::take(world)
+    }
+
+    #[test]
+    fn transitions_run_enter_and_exit_systems() {
+        let (mut world, mut systems) = setup();
+        assert_eq!(frame(&mut world, &mut systems), ["enter menu"]);
+        assert_eq!(frame(&mut world, &mut systems), Vec::<&str>::new());
+
+        world.resource_mut::<State<Screen>>().set(Screen::Menu);
+        world.resource_mut::<State<Screen>>().set(Screen::Game);
+        assert_eq!(
+            frame(&mut world, &mut systems),
+            ["exit menu", "enter game", "update game"]
+        );
+
+        {
+            let state = world.resource::<State<Screen>>();
+            assert_eq!(state.current(), &Screen::Game);
+            assert_eq!(state.previous(), Some(&Screen::Menu));
+            assert!(!state.is_pending());
+        }
+
+        world.resource_mut::<State<Screen>>().set(Screen::Pause);
+        assert_eq!(frame(&mut world, &mut systems), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn scoped_entities_are_despawned_on_exit() {
+        let (mut world, mut systems) = setup();
+        frame(&mut world, &mut systems);
+
+        let menu = world.spawn().insert(StateScoped(Screen::Menu)).id();
+        let game = world.spawn().insert(StateScoped(Screen::Game)).id();
+        let child = world.spawn().id();
+        world.entity_mut(menu).push_children(&[child]);
+        let other: Entity = world.spawn().id();
+
+        world.resource_mut::<State<Screen>>().set(Screen::Game);
+        frame(&mut world, &mut systems);
+        assert!(world.get_entity(menu).is_none());
+        assert!(world.get_entity(child).is_none());
+        assert!(world.get_entity(game).is_some());
+        assert!(world.get_entity(other).is_some());
+    }
+}
@@ -1,59 +1,312 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
-use crate::ecs::{System, World};
+use thiserror::Error;
 
-use super::stage::{StageId, StageLabel};
+use crate::{
+    ecs::{
+        BoxedSystem, ConditionalSystem, EcsDiagnostics, ParallelExecutor, StageDiagnostics,
+        SystemDiagnostics, World,
+    },
+    tasks::ComputeTaskPool,
+};
 
-struct SystemEntry {
-    pub initialized: bool,
-    pub system: Box<dyn System<In = (), Out = ()>>,
+use super::{
+    descriptor::{SystemDescriptor, SystemLabelId, SystemOrdering},
+    stage::{CommandFlush, StageExecutor, StageId, StageLabel, StageSettings},
+    state::{StateDriver, StateSystems, StateValue},
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    #[error("system `{system}` is ordered relative to unknown label `{label}`")]
+    UnknownLabel { system: String, label: String },
+    #[error("system ordering contains a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
 }
 
 #[derive(Default)]
-pub struct StageSystems(Vec<SystemEntry>);
+pub struct StageSystems {
+    name: String,
+    systems: Vec<BoxedSystem>,
+    orderings: Vec<SystemOrdering>,
+    initialized: usize,
+    executor: ParallelExecutor,
+    settings: StageSettings,
+    // Only measured while the EcsDiagnostics resource exists
+    run_times: Vec<Duration>,
+    run_time: Duration,
+}
 
 impl StageSystems {
-    pub fn add<S>(&mut self, system: S)
-    where
-        S: System<In = (), Out = ()>,
-    {
-        self.0.push(SystemEntry {
-            initialized: false,
-            system: Box::new(system),
-        });
+    pub(crate) fn add(&mut self, descriptor: SystemDescriptor) {
+        if descriptor.conditions.is_empty() {
+            self.systems.push(descriptor.system);
+        } else {
+            let system = ConditionalSystem::new(descriptor.system, descriptor.conditions);
+            self.systems.push(Box::new(system));
+        }
+        self.orderings.push(descriptor.ordering);
     }
 
     pub fn run(&mut self, world: &mut World) {
-        for entry in &mut self.0 {
-            if !entry.initialized {
-                entry.initialized = true;
-                entry.system.initialize(world);
+        if self.initialized < self.systems.len()
+            && let Err(error) = self.rebuild(world)
+        {
+            panic!("Unable to schedule stage systems: {error}");
+        }
+
+        // The pool is cloned since exclusive systems borrow the world mutably
+        let task_pool = match self.settings.executor {
+            StageExecutor::Parallel => world
+                .get_resource::<ComputeTaskPool>()
+                .map(|pool| pool.0.clone()),
+            StageExecutor::SingleThreaded => None,
+        };
+        let run_times = if world.get_resource::<EcsDiagnostics>().is_some() {
+            self.run_times.resize(self.systems.len(), Duration::ZERO);
+            Some(self.run_times.as_mut_slice())
+        } else {
+            None
+        };
+        let measured = run_times.is_some();
+        let start = Instant::now();
+        unsafe {
+            self.executor
+                .run(&mut self.systems, world, task_pool.as_ref(), run_times);
+        }
+        if measured {
+            self.run_time = start.elapsed();
+        }
+    }
+
+    // Reports unknown labels and ordering cycles without running or initializing the systems
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        let dependencies = self.dependencies()?;
+        self.sort(&dependencies).map(drop)
+    }
+
+    // Commands are counted before they are applied, so this has to be called right after run
+    fn diagnostics(&self, id: StageId) -> StageDiagnostics {
+        StageDiagnostics {
+            id,
+            name: self.name.clone(),
+            run_time: self.run_time,
+            systems: self
+                .systems
+                .iter()
+                .zip(&self.run_times)
+                .map(|(system, run_time)| SystemDiagnostics {
+                    name: system.name().to_owned(),
+                    run_time: *run_time,
+                    pending_commands: system.pending_commands(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn apply_buffers(&mut self, world: &mut World) {
+        for system in &mut self.systems {
+            system.apply_buffers(world);
+        }
+    }
+
+    fn rebuild(&mut self, world: &mut World) -> Result<(), ScheduleError> {
+        for system in &mut self.systems[self.initialized..] {
+            system.initialize(world);
+        }
+        self.initialized = self.systems.len();
+
+        let dependencies = self.dependencies()?;
+        let order = self.sort(&dependencies)?;
+        let mut positions = vec![0; order.len()];
+        for (position, &index) in order.iter().enumerate() {
+            positions[index] = position;
+        }
+
+        let mut sorted_dependencies = vec![Vec::new(); order.len()];
+        for (index, predecessors) in dependencies.into_iter().enumerate() {
+            sorted_dependencies[positions[index]] = predecessors
+                .into_iter()
+                .map(|predecessor| positions[predecessor])
+                .collect();
+        }
+
+        let mut systems = std::mem::take(&mut self.systems)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut orderings = std::mem::take(&mut self.orderings)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        for index in order {
+            self.systems.push(systems[index].take().unwrap());
+            self.orderings.push(orderings[index].take().unwrap());
+        }
+
+        self.executor.rebuild(&self.systems, &sorted_dependencies);
+        Ok(())
+    }
+
+    // For every system, the indices of the systems that have to run before it
+    fn dependencies(&self) -> Result<Vec<Vec<usize>>, ScheduleError> {
+        let mut labeled: HashMap<SystemLabelId, Vec<usize>> = HashMap::new();
+        for (index, ordering) in self.orderings.iter().enumerate() {
+            for label in &ordering.labels {
+                labeled.entry(label.id).or_default().push(index);
             }
-            unsafe {
-                entry.system.run((), world);
+        }
+
+        let mut dependencies = vec![Vec::new(); self.systems.len()];
+        for (index, ordering) in self.orderings.iter().enumerate() {
+            let constraints = ordering
+                .before
+                .iter()
+                .map(|label| (label, true))
+                .chain(ordering.after.iter().map(|label| (label, false)));
+            for (label, before) in constraints {
+                let others = labeled
+                    .get(&label.id)
+                    .ok_or_else(|| ScheduleError::UnknownLabel {
+                        system: self.systems[index].name().to_owned(),
+                        label: label.name.clone(),
+                    })?;
+                for &other in others.iter().filter(|&&other| other != index) {
+                    if before {
+                        dependencies[other].push(index);
+                    } else {
+                        dependencies[index].push(other);
+                    }
+                }
             }
         }
+
+        for predecessors in &mut dependencies {
+            predecessors.sort_unstable();
+            predecessors.dedup();
+        }
+        Ok(dependencies)
     }
 
-    pub fn apply_buffers(&mut self, world: &mut World) {
-        for entry in &mut self.0 {
-            entry.system.apply_buffers(world);
+    // Stable topological sort, systems without constraints between them keep their insertion order
+    fn sort(&self, dependencies: &[Vec<usize>]) -> Result<Vec<usize>, ScheduleError> {
+        let mut successors = vec![Vec::new(); dependencies.len()];
+        let mut remaining = vec![0; dependencies.len()];
+        for (index, predecessors) in dependencies.iter().enumerate() {
+            remaining[index] = predecessors.len();
+            for &predecessor in predecessors {
+                successors[predecessor].push(index);
+            }
+        }
+
+        let mut ready = remaining
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count == 0)
+            .map(|(index, _)| Reverse(index))
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(dependencies.len());
+        while let Some(Reverse(index)) = ready.pop() {
+            order.push(index);
+            for &successor in &successors[index] {
+                remaining[successor] -= 1;
+                if remaining[successor] == 0 {
+                    ready.push(Reverse(successor));
+                }
+            }
+        }
+
+        if order.len() < dependencies.len() {
+            return Err(ScheduleError::Cycle(
+                self.find_cycle(dependencies, &remaining),
+            ));
         }
+        Ok(order)
+    }
+
+    fn find_cycle(&self, dependencies: &[Vec<usize>], remaining: &[usize]) -> Vec<String> {
+        // Every unsorted system has an unsorted predecessor, walking them must revisit a system
+        let mut current = remaining.iter().position(|count| *count > 0).unwrap();
+        let mut path = Vec::new();
+        while !path.contains(&current) {
+            path.push(current);
+            current = *dependencies[current]
+                .iter()
+                .find(|&&predecessor| remaining[predecessor] > 0)
+                .unwrap();
+        }
+
+        let start = path.iter().position(|index| *index == current).unwrap();
+        std::iter::once(current)
+            .chain(path[start..].iter().rev().copied())
+            .map(|index| self.systems[index].name().to_owned())
+            .collect()
     }
 }
 
+#[derive(Default)]
+struct AnchoredStages {
+    before: Vec<StageId>,
+    after: Vec<StageId>,
+}
+
+// Custom stages are anchored before or after another stage and run whenever that stage runs, so
+// they need no changes to the loops driving the main and render apps
 #[derive(Default)]
 pub struct Systems {
     systems: HashMap<StageId, StageSystems>,
+    anchored: HashMap<StageId, AnchoredStages>,
+    custom: HashSet<StageId>,
+    // Stages created since the last run, checked for being scheduled
+    unchecked: Vec<StageId>,
+    deferred: Vec<StageId>,
+    states: Vec<Box<dyn StateDriver>>,
 }
 
 impl Systems {
-    pub fn add<L, S>(&mut self, stage: L, system: S)
+    pub(crate) fn add<L>(&mut self, stage: L, descriptor: SystemDescriptor)
     where
         L: StageLabel,
-        S: System<In = (), Out = ()>,
     {
-        self.systems.entry(stage.id()).or_default().add(system);
+        self.stage_mut(stage).add(descriptor);
+    }
+
+    fn stage_mut<L: StageLabel>(&mut self, stage: L) -> &mut StageSystems {
+        self.systems.entry(stage.id()).or_insert_with(|| {
+            self.unchecked.push(stage.id());
+            StageSystems {
+                name: format!("{stage:?}"),
+                ..Default::default()
+            }
+        })
+    }
+
+    // Names of the stages created since the last call that are neither built-in nor added with
+    // add_stage, their systems never run
+    fn unscheduled_stages(&mut self) -> Vec<String> {
+        self.unchecked
+            .drain(..)
+            .filter(|id| !id.is_builtin() && !self.custom.contains(id))
+            .map(|id| self.systems[&id].name.clone())
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        for systems in self.systems.values() {
+            systems.validate()?;
+        }
+        for state in &self.states {
+            state.validate()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.systems.values().all(|stage| stage.systems.is_empty())
     }
 
     pub fn get<L>(&mut self, stage: L) -> Option<&mut StageSystems>
@@ -63,13 +316,410 @@ impl Systems {
         self.systems.get_mut(&stage.id())
     }
 
+    // Stages added before the same stage run in the order they were added, as do those added after
+    pub(crate) fn add_stage<A, L>(
+        &mut self,
+        anchor: A,
+        label: L,
+        settings: StageSettings,
+        before: bool,
+    ) where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        let id = label.id();
+        assert!(
+            id != anchor.id(),
+            "Stage {label:?} cannot be added next to itself"
+        );
+        assert!(self.custom.insert(id), "Stage {label:?} already added");
+        // A new stage with nothing anchored to it cannot be part of a cycle
+        assert!(
+            !self.anchored.contains_key(&id),
+            "Stage {label:?} must be added before other stages are added next to it"
+        );
+
+        self.stage_mut(label).settings = settings;
+        let anchored = self.anchored.entry(anchor.id()).or_default();
+        if before {
+            anchored.before.push(id);
+        } else {
+            anchored.after.push(id);
+        }
+    }
+
     pub fn run<L>(&mut self, stage: L, world: &mut World)
     where
         L: StageLabel,
     {
-        if let Some(systems) = self.systems.get_mut(&stage.id()) {
-            systems.run(world);
-            systems.apply_buffers(world);
+        for name in self.unscheduled_stages() {
+            log::warn!("Stage {name} was not added to the schedule, its systems never run");
+        }
+        self.run_id(stage.id(), world);
+    }
+
+    pub(crate) fn run_before<L: StageLabel>(&mut self, stage: L, world: &mut World) {
+        self.run_anchored(stage.id(), true, world);
+    }
+
+    pub(crate) fn run_after<L: StageLabel>(&mut self, stage: L, world: &mut World) {
+        self.run_anchored(stage.id(), false, world);
+    }
+
+    fn run_id(&mut self, id: StageId, world: &mut World) {
+        self.run_anchored(id, true, world);
+        self.run_stage(id, world);
+        self.run_anchored(id, false, world);
+    }
+
+    fn run_anchored(&mut self, id: StageId, before: bool, world: &mut World) {
+        let mut index = 0;
+        while let Some(stage) = self.anchored.get(&id).and_then(|anchored| {
+            let stages = if before {
+                &anchored.before
+            } else {
+                &anchored.after
+            };
+            stages.get(index).copied()
+        }) {
+            self.run_id(stage, world);
+            index += 1;
+        }
+    }
+
+    // Runs only the systems of the stage, neither its anchored stages nor its commands
+    pub(crate) fn run_systems<L: StageLabel>(&mut self, stage: L, world: &mut World) {
+        self.run_stage_systems(stage.id(), world);
+    }
+
+    fn run_stage_systems(&mut self, id: StageId, world: &mut World) -> Option<&StageSystems> {
+        let systems = self.systems.get_mut(&id)?;
+        systems.run(world);
+        if let Some(mut diagnostics) = world.get_resource_mut::<EcsDiagnostics>() {
+            diagnostics.record_stage(systems.diagnostics(id));
+        }
+        Some(systems)
+    }
+
+    fn run_stage(&mut self, id: StageId, world: &mut World) {
+        let flush = self
+            .run_stage_systems(id, world)
+            .map_or(CommandFlush::EndOfStage, |systems| systems.settings.flush);
+
+        if flush == CommandFlush::Deferred {
+            if !self.deferred.contains(&id) {
+                self.deferred.push(id);
+            }
+            return;
+        }
+
+        for stage in self.deferred.drain(..).chain(std::iter::once(id)) {
+            if let Some(systems) = self.systems.get_mut(&stage) {
+                systems.apply_buffers(world);
+            }
+        }
+    }
+
+    pub(crate) fn add_state<S: StateValue>(&mut self) -> &mut StateSystems<S> {
+        if self.state_mut::<S>().is_none() {
+            self.states.push(Box::<StateSystems<S>>::default());
+        }
+        self.state_mut::<S>().unwrap()
+    }
+
+    pub(crate) fn state_mut<S: StateValue>(&mut self) -> Option<&mut StateSystems<S>> {
+        self.states
+            .iter_mut()
+            .find_map(|state| state.as_any_mut().downcast_mut::<StateSystems<S>>())
+    }
+
+    pub fn apply_state_transitions(&mut self, world: &mut World) {
+        for state in &mut self.states {
+            state.apply_transitions(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        app::{
+            CommandFlush, IntoSystemDescriptor, MainStage, StageExecutor, StageLabel,
+            StageSettings, SystemSet,
+        },
+        ecs::{Commands, EcsDiagnostics, Entity, Query, ResMut, World},
+        tasks::{ComputeTaskPool, TaskPoolBuilder},
+        test_util::Log,
+    };
+
+    use super::{ScheduleError, StageSystems, Systems};
+
+    fn first(mut log: ResMut<Log>) {
+        log.0.push("first");
+    }
+
+    fn second(mut log: ResMut<Log>) {
+        log.0.push("second");
+    }
+
+    fn third(mut log: ResMut<Log>) {
+        log.0.push("third");
+    }
+
+    #[test]
+    fn labels_order_systems() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+
+        let mut systems = StageSystems::default();
+        systems.add(third.label("third").after("second").into_descriptor());
+        systems.add(second.label("second").into_descriptor());
+        systems.add(first.before("second").into_descriptor());
+        systems.run(&mut world);
+
+        assert_eq!(world.resource::<Log>().0, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn system_set_shares_ordering() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+
+        let mut systems = StageSystems::default();
+        let set = SystemSet::new()
+            .after("first")
+            .with_system(second)
+            .with_system(third);
+        for descriptor in set.into_descriptors() {
+            systems.add(descriptor);
+        }
+        systems.add(first.label("first").into_descriptor());
+        systems.run(&mut world);
+
+        assert_eq!(world.resource::<Log>().0, vec!["first", "second", "third"]);
+    }
+
+    fn exclusive(world: &mut World) {
+        world.resource_mut::<Log>().0.push("exclusive");
+        world.spawn();
+    }
+
+    fn log_entities(query: Query<Entity>, mut log: ResMut<Log>) {
+        if query.iter().count() == 1 {
+            log.0.push("third");
+        }
+    }
+
+    #[test]
+    fn exclusive_systems_are_ordered() {
+        for threads in [0, 2] {
+            let mut world = World::new();
+            world.init_resource::<Log>();
+            if threads > 0 {
+                let task_pool = TaskPoolBuilder::new().num_threads(threads).build();
+                world.insert_resource(ComputeTaskPool(task_pool));
+            }
+
+            let mut systems = StageSystems::default();
+            systems.add(log_entities.after("exclusive").into_descriptor());
+            systems.add(
+                exclusive
+                    .label("exclusive")
+                    .after("first")
+                    .into_descriptor(),
+            );
+            systems.add(first.label("first").into_descriptor());
+            systems.run(&mut world);
+
+            assert_eq!(
+                world.resource::<Log>().0,
+                vec!["first", "exclusive", "third"]
+            );
         }
     }
+
+    #[test]
+    fn unknown_label_is_reported() {
+        let mut systems = StageSystems::default();
+        systems.add(first.after("missing").into_descriptor());
+
+        assert!(matches!(
+            systems.dependencies(),
+            Err(ScheduleError::UnknownLabel { label, .. }) if label == "missing"
+        ));
+    }
+
+    #[test]
+    fn cycle_is_reported() {
+        let mut systems = StageSystems::default();
+        systems.add(first.label("first").after("second").into_descriptor());
+        systems.add(second.label("second").after("first").into_descriptor());
+
+        let dependencies = systems.dependencies().unwrap();
+        let Err(ScheduleError::Cycle(cycle)) = systems.sort(&dependencies) else {
+            panic!("Cycle not detected");
+        };
+        assert_eq!(cycle.len(), 3);
+        assert_eq!(cycle.first(), cycle.last());
+    }
+
+    #[test]
+    fn schedule_is_validated() {
+        let mut systems = Systems::default();
+        systems.add(MainStage::PreUpdate, first.label("first").into_descriptor());
+        assert_eq!(systems.validate(), Ok(()));
+
+        systems.add(MainStage::Flush, second.after("first").into_descriptor());
+        let Err(ScheduleError::UnknownLabel { system, label }) = systems.validate() else {
+            panic!("Unknown label not detected");
+        };
+        assert!(system.ends_with("second"));
+        assert_eq!(label, "first");
+    }
+
+    #[derive(StageLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum CustomStage {
+        Early,
+        Late,
+        Deferred,
+    }
+
+    // Stages are told apart by their label type, not only by their name
+    #[derive(StageLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum SameName {
+        PreUpdate,
+    }
+
+    fn spawn(mut commands: Commands) {
+        commands.spawn();
+    }
+
+    fn count_entities(world: &mut World) -> usize {
+        world.query::<Entity>().iter(world).count()
+    }
+
+    #[test]
+    fn custom_stages_run_next_to_their_anchor() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+
+        let mut systems = Systems::default();
+        let single_threaded = StageSettings {
+            executor: StageExecutor::SingleThreaded,
+            ..Default::default()
+        };
+        systems.add_stage(
+            MainStage::PreUpdate,
+            CustomStage::Late,
+            single_threaded,
+            false,
+        );
+        systems.add_stage(
+            CustomStage::Late,
+            CustomStage::Early,
+            Default::default(),
+            true,
+        );
+        systems.add(CustomStage::Late, third.into_descriptor());
+        systems.add(CustomStage::Early, second.into_descriptor());
+        systems.add(MainStage::PreUpdate, first.into_descriptor());
+
+        systems.run(MainStage::PreUpdate, &mut world);
+        assert_eq!(world.resource::<Log>().0, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn deferred_stage_flushes_with_next_stage() {
+        let mut world = World::new();
+
+        let mut systems = Systems::default();
+        let deferred = StageSettings {
+            flush: CommandFlush::Deferred,
+            ..Default::default()
+        };
+        systems.add_stage(MainStage::Flush, CustomStage::Deferred, deferred, true);
+        systems.add(CustomStage::Deferred, spawn.into_descriptor());
+
+        systems.run(CustomStage::Deferred, &mut world);
+        assert_eq!(count_entities(&mut world), 0);
+        // The deferred stage runs again before Flush, which then applies the commands of both runs
+        systems.run(MainStage::Flush, &mut world);
+        assert_eq!(count_entities(&mut world), 2);
+    }
+
+    fn reset_log(mut commands: Commands) {
+        commands.init_resource::<Log>();
+    }
+
+    #[test]
+    fn stage_diagnostics() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.init_resource::<EcsDiagnostics>();
+
+        let mut systems = Systems::default();
+        systems.add_stage(
+            MainStage::PreUpdate,
+            SameName::PreUpdate,
+            Default::default(),
+            false,
+        );
+        systems.add(MainStage::PreUpdate, first.into_descriptor());
+        systems.add(MainStage::PreUpdate, reset_log.into_descriptor());
+        systems.add(SameName::PreUpdate, second.into_descriptor());
+        systems.run(MainStage::PreUpdate, &mut world);
+
+        let diagnostics = world.resource::<EcsDiagnostics>();
+        assert_eq!(diagnostics.stages.len(), 2);
+        let stage = diagnostics.stage(MainStage::PreUpdate).unwrap();
+        assert_eq!(stage.systems.len(), 2);
+        assert!(stage.systems[0].name.ends_with("first"));
+        assert_eq!(stage.systems[0].pending_commands, 0);
+        assert_eq!(stage.systems[1].pending_commands, 1);
+        assert!(stage.run_time >= stage.systems[0].run_time);
+        let stage = diagnostics.stage(SameName::PreUpdate).unwrap();
+        assert!(stage.systems[0].name.ends_with("second"));
+    }
+
+    #[test]
+    fn unscheduled_stages() {
+        let mut systems = Systems::default();
+        systems.add_stage(
+            MainStage::PreUpdate,
+            CustomStage::Early,
+            Default::default(),
+            true,
+        );
+        systems.add(CustomStage::Early, first.into_descriptor());
+        systems.add(CustomStage::Late, second.into_descriptor());
+        systems.add(CustomStage::Deferred, third.into_descriptor());
+        systems.add_stage(
+            MainStage::Flush,
+            CustomStage::Deferred,
+            Default::default(),
+            true,
+        );
+        systems.add(MainStage::PreUpdate, third.into_descriptor());
+
+        assert_eq!(systems.unscheduled_stages(), vec!["Late".to_owned()]);
+        assert!(systems.unscheduled_stages().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "already added")]
+    fn stage_cannot_be_added_twice() {
+        let mut systems = Systems::default();
+        systems.add_stage(
+            MainStage::PreUpdate,
+            CustomStage::Early,
+            Default::default(),
+            true,
+        );
+        systems.add_stage(
+            MainStage::Flush,
+            CustomStage::Early,
+            Default::default(),
+            true,
+        );
+    }
 }
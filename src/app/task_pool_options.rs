@@ -2,7 +2,7 @@ use std::thread::available_parallelism;
 
 use crate::{
     ecs::World,
-    tasks::{IoTaskPool, TaskPoolBuilder},
+    tasks::{ComputeTaskPool, IoTaskPool, TaskPoolBuilder},
 };
 
 #[derive(Clone)]
@@ -27,7 +27,7 @@ pub struct TaskPoolOptions {
     pub max_total_threads: usize,
     pub io: TaskPoolThreadAssignmentPolicy,
     // pub async_compute: TaskPoolThreadAssignmentPolicy,
-    // pub compute: TaskPoolThreadAssignmentPolicy,
+    pub compute: TaskPoolThreadAssignmentPolicy,
 }
 
 impl Default for TaskPoolOptions {
@@ -45,11 +45,11 @@ impl Default for TaskPoolOptions {
             //     max_threads: 4,
             //     percent: 0.25,
             // },
-            // compute: TaskPoolThreadAssignmentPolicy {
-            //     min_threads: 1,
-            //     max_threads: std::usize::MAX,
-            //     percent: 1.0,
-            // },
+            compute: TaskPoolThreadAssignmentPolicy {
+                min_threads: 1,
+                max_threads: usize::MAX,
+                percent: 1.0,
+            },
         }
     }
 }
@@ -70,14 +70,14 @@ impl TaskPoolOptions {
             .clamp(self.min_total_threads, self.max_total_threads);
         log::trace!("Assigning {} cores to task pools", total_threads);
 
-        let remaining_threads = total_threads;
+        let mut remaining_threads = total_threads;
 
         let io_threads = self
             .io
             .get_number_of_threads(remaining_threads, total_threads);
 
         log::trace!("IO Threads: {}", io_threads);
-        // remaining_threads = remaining_threads.saturating_sub(io_threads);
+        remaining_threads = remaining_threads.saturating_sub(io_threads);
 
         world.insert_resource(IoTaskPool(
             TaskPoolBuilder::default()
@@ -100,16 +100,16 @@ impl TaskPoolOptions {
         //         .build(),
         // ));
 
-        // let compute_threads = self
-        //     .compute
-        //     .get_number_of_threads(remaining_threads, total_threads);
+        let compute_threads = self
+            .compute
+            .get_number_of_threads(remaining_threads, total_threads);
 
-        // log::trace!("Compute Threads: {}", compute_threads);
-        // world.insert_resource(ComputeTaskPool(
-        //     TaskPoolBuilder::default()
-        //         .num_threads(compute_threads)
-        //         .thread_name("Compute Task Pool".to_string())
-        //         .build(),
-        // ));
+        log::trace!("Compute Threads: {}", compute_threads);
+        world.insert_resource(ComputeTaskPool(
+            TaskPoolBuilder::default()
+                .num_threads(compute_threads)
+                .thread_name("Compute Task Pool".to_string())
+                .build(),
+        ));
     }
 }
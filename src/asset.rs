@@ -6,16 +6,24 @@ mod io;
 mod loader;
 mod path;
 
-pub use asset_server::{AssetServer, free_unused_assets_system};
+pub use asset_server::{AssetServer, free_unused_assets_system, reload_changed_assets_system};
 pub use assets::{AssetEvent, Assets};
 pub use handle::{Handle, HandleId, HandleUntyped};
-pub use io::FileAssetIo;
+pub use info::LoadState;
+pub use io::{
+    AssetIo, AssetIoError, AssetWithMeta, EmbeddedAssetIo, FileAssetIo, LayeredAssetIo,
+    MemoryAssetIo, PakAssetIo, PakWriter, pack_asset_folder,
+};
 pub use loader::{
-    Asset, AssetDynamic, AssetLoader, LoadContext, LoadedAsset, update_asset_storage_system,
+    Asset, AssetDynamic, AssetLoader, LoadContext, LoadedAsset, LoaderSettings,
+    LoaderSettingsError, update_asset_storage_system,
 };
+pub use path::AssetPath;
+
+use std::path::PathBuf;
 
 use crate::{
-    app::{App, MainStage},
+    app::{App, MainStage, Plugin},
     ecs::Resource,
     tasks::IoTaskPool,
 };
@@ -24,24 +32,143 @@ pub mod prelude {
     pub use crate::asset::{AssetEvent, AssetServer, Assets, Handle, HandleUntyped};
 }
 
+#[derive(Clone)]
+pub enum AssetIoBackend {
+    // The asset folder of the settings
+    Folder,
+    // Folder relative to the executable, such as a mod folder
+    Directory(PathBuf),
+    Memory(MemoryAssetIo),
+    Embedded(EmbeddedAssetIo),
+    // Pak archives relative to the executable, later archives override the earlier ones
+    Archives(Vec<PathBuf>),
+}
+
 #[derive(Clone, Resource)]
 pub struct AssetServerSettings {
     pub asset_folder: String,
+    // Reloads the changed files of the asset folder and the assets depending on them
+    pub watch_for_changes: bool,
+    // A path is resolved by the source of the highest priority that has it, sources of the same
+    // priority in the order they are listed. A `name://` path selects a source directly.
+    pub sources: Vec<AssetSourceSettings>,
+}
+
+#[derive(Clone)]
+pub struct AssetSourceSettings {
+    pub name: String,
+    pub priority: i32,
+    pub backend: AssetIoBackend,
 }
 
 impl Default for AssetServerSettings {
     fn default() -> Self {
         Self {
             asset_folder: "assets".to_string(),
+            watch_for_changes: false,
+            sources: vec![AssetSourceSettings {
+                name: "assets".to_string(),
+                priority: 0,
+                backend: AssetIoBackend::Folder,
+            }],
+        }
+    }
+}
+
+impl AssetServerSettings {
+    fn create_asset_io(&self) -> Box<dyn AssetIo> {
+        assert!(!self.sources.is_empty(), "No asset source configured");
+        let mut io = LayeredAssetIo::default();
+        for source in &self.sources {
+            io.add_boxed_source(
+                &source.name,
+                source.priority,
+                self.create_backend(&source.backend),
+            );
+        }
+        Box::new(io)
+    }
+
+    fn create_backend(&self, backend: &AssetIoBackend) -> Box<dyn AssetIo> {
+        match backend {
+            AssetIoBackend::Folder => Box::new(FileAssetIo::new(&self.asset_folder)),
+            AssetIoBackend::Directory(path) => Box::new(FileAssetIo::new(path)),
+            AssetIoBackend::Memory(io) => Box::new(io.clone()),
+            AssetIoBackend::Embedded(io) => Box::new(io.clone()),
+            AssetIoBackend::Archives(paths) => {
+                let root_path = FileAssetIo::get_root_path();
+                let paths = paths
+                    .iter()
+                    .map(|path| root_path.join(path))
+                    .collect::<Vec<_>>();
+                let io = PakAssetIo::open(&paths)
+                    .unwrap_or_else(|error| panic!("Failed to open asset archives: {}", error));
+                Box::new(io)
+            }
         }
     }
 }
 
+#[derive(Default)]
+pub struct AssetPlugin {
+    pub settings: AssetServerSettings,
+}
+
+impl Plugin for AssetPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        app.insert_resource(self.settings.clone());
+        asset_plugin(app);
+    }
+}
+
 pub fn asset_plugin(app: &mut App) {
     let task_pool = app.resource::<IoTaskPool>().0.clone();
     let settings = app.resource::<AssetServerSettings>();
-    let source = Box::new(FileAssetIo::new(&settings.asset_folder));
-    let asset_server = AssetServer::with_boxed_io(source, task_pool);
+    let asset_server = AssetServer::with_boxed_io(settings.create_asset_io(), task_pool);
+    if settings.watch_for_changes
+        && let Err(error) = asset_server.watch_for_changes()
+    {
+        log::error!("Failed to watch assets for changes: {}", error);
+    }
     app.insert_resource(asset_server);
-    app.add_system_to_stage(MainStage::PreUpdate, free_unused_assets_system);
+    app.add_system_to_stage(MainStage::LoadAssets, reload_changed_assets_system)
+        .add_system_to_stage(MainStage::PreUpdate, free_unused_assets_system);
+}
+
+#[cfg(test)]
+mod test {
+    use futures_lite::future;
+    use std::path::Path;
+
+    use super::{AssetIoBackend, AssetServerSettings, AssetSourceSettings, MemoryAssetIo};
+
+    #[test]
+    fn sources_resolve_by_priority() {
+        let base = MemoryAssetIo::new();
+        base.insert("player.png", vec![1u8]);
+        base.insert("enemy.png", vec![1u8]);
+        let modded = MemoryAssetIo::new();
+        modded.insert("player.png", vec![2u8]);
+        let patch = MemoryAssetIo::new();
+        patch.insert("player.png", vec![3u8]);
+
+        let source = |name: &str, priority, io: &MemoryAssetIo| AssetSourceSettings {
+            name: name.to_string(),
+            priority,
+            backend: AssetIoBackend::Memory(io.clone()),
+        };
+        let settings = AssetServerSettings {
+            sources: vec![
+                source("base", 0, &base),
+                source("patch", 10, &patch),
+                source("mod", 10, &modded),
+            ],
+            ..Default::default()
+        };
+        let io = settings.create_asset_io();
+        let load = |path: &str| future::block_on(io.load_path(Path::new(path))).ok();
+        assert_eq!(load("player.png"), Some(vec![3]));
+        assert_eq!(load("enemy.png"), Some(vec![1]));
+        assert_eq!(load("mod://player.png"), Some(vec![2]));
+    }
 }
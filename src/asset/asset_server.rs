@@ -3,8 +3,8 @@ use crossbeam_channel::TryRecvError;
 use parking_lot::{Mutex, RwLock};
 use std::{
     any::TypeId,
-    collections::{HashMap, hash_map::Entry},
-    path::Path,
+    collections::{HashMap, HashSet, hash_map::Entry},
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use thiserror::Error;
@@ -20,9 +20,10 @@ use super::{
     info::{LoadState, SourceInfo, SourceMeta},
     io::{AssetIo, AssetIoError},
     loader::{
-        AssetLifecycle, AssetLifecycleChannel, AssetLifecycleEvent, AssetLoader, LoadContext,
+        AssetLifecycle, AssetLifecycleChannel, AssetLifecycleEvent, AssetLoader,
+        BoxedLoaderSettings, LoadContext, LoaderSettings,
     },
-    path::{AssetPath, AssetPathId, LabelId, SourcePathId},
+    path::{AssetPath, AssetPathId, LabelId, SourcePathId, meta_file_path, strip_meta_extension},
 };
 
 #[derive(Error, Debug)]
@@ -66,6 +67,8 @@ pub struct AssetServerInternal {
     loaders: RwLock<Vec<Arc<dyn AssetLoader>>>,
     extension_to_loader_index: RwLock<HashMap<String, usize>>,
     handle_to_path: Arc<RwLock<HashMap<HandleId, AssetPath<'static>>>>,
+    // Settings passed to load_with_settings, kept for reloads
+    loader_settings: RwLock<HashMap<SourcePathId, BoxedLoaderSettings>>,
     task_pool: TaskPool,
 }
 
@@ -97,6 +100,7 @@ impl AssetServer {
                 asset_ref_counter: Default::default(),
                 handle_to_path: Default::default(),
                 asset_lifecycles: Default::default(),
+                loader_settings: Default::default(),
                 task_pool,
                 asset_io,
             }),
@@ -126,11 +130,65 @@ impl AssetServer {
         loaders.push(Arc::new(loader));
     }
 
+    // Changed files are reloaded together with the assets depending on them
     pub fn watch_for_changes(&self) -> Result<(), AssetServerError> {
         self.server.asset_io.watch_for_changes()?;
+        for source_info in self.server.asset_sources.read().values() {
+            self.server
+                .asset_io
+                .watch_path_for_changes(&source_info.path)?;
+        }
         Ok(())
     }
 
+    // Reloads the whole source of the path, even if only a labeled asset was given
+    pub fn reload_asset<'a, P: Into<AssetPath<'a>>>(&self, path: P) {
+        let path: AssetPath = path.into();
+        self.reload_sources(vec![path.path().to_owned()]);
+    }
+
+    // A changed meta file reloads the asset it belongs to
+    pub fn reload_changed_assets(&self) {
+        let changed = self.server.asset_io.changed_paths();
+        if !changed.is_empty() {
+            self.reload_sources(
+                changed
+                    .into_iter()
+                    .map(|path| strip_meta_extension(&path).unwrap_or(path))
+                    .collect(),
+            );
+        }
+    }
+
+    // Reloads every source at most once together with the sources depending on it, paths that
+    // were never loaded are skipped. Loaders only record the paths of their dependencies, so the
+    // reloads are independent of each other and may finish in any order.
+    fn reload_sources(&self, paths: Vec<PathBuf>) {
+        let mut reloaded = HashSet::new();
+        let mut pending = paths;
+        while let Some(path) = pending.pop() {
+            if !reloaded.insert(path.clone()) {
+                continue;
+            }
+            {
+                let asset_sources = self.server.asset_sources.read();
+                if !asset_sources
+                    .values()
+                    .any(|source_info| source_info.path == path)
+                {
+                    continue;
+                }
+                pending.extend(
+                    asset_sources
+                        .values()
+                        .filter(|source_info| source_info.depends_on(&path))
+                        .map(|source_info| source_info.path.clone()),
+                );
+            }
+            self.load_untracked(AssetPath::new(path, None), true);
+        }
+    }
+
     pub fn get_handle<T: Asset, I: Into<HandleId>>(&self, id: I) -> Handle<T> {
         let sender = self.server.asset_ref_counter.channel.sender.clone();
         Handle::strong(id.into(), sender)
@@ -229,6 +287,52 @@ impl AssetServer {
         self.load_untyped(path).typed()
     }
 
+    // The settings replace the meta file of the asset until they are removed, an already loaded
+    // asset is reloaded with them. The first settings of a path win, later calls with different
+    // settings only log a warning so call sites do not reload each other's assets.
+    pub fn load_with_settings<'a, T: Asset, S: LoaderSettings, P: Into<AssetPath<'a>>>(
+        &self,
+        path: P,
+        settings: S,
+    ) -> Handle<T> {
+        let path: AssetPath = path.into();
+        let force = {
+            let mut loader_settings = self.server.loader_settings.write();
+            match loader_settings.get(&path.get_id().source_path_id()) {
+                Some(current) => {
+                    if current.downcast_ref::<S>() != Some(&settings) {
+                        log::warn!(
+                            "Asset {} already has other loader settings, they are kept",
+                            path.path().display()
+                        );
+                    }
+                    false
+                }
+                None => {
+                    loader_settings.insert(path.get_id().source_path_id(), Arc::new(settings));
+                    true
+                }
+            }
+        };
+        let handle_id = self.load_untracked(path, force);
+        self.get_handle(handle_id)
+    }
+
+    // The meta file applies again, an already loaded asset is reloaded
+    pub fn remove_loader_settings<'a, P: Into<AssetPath<'a>>>(&self, path: P) -> bool {
+        let path: AssetPath = path.into();
+        let removed = self
+            .server
+            .loader_settings
+            .write()
+            .remove(&path.get_id().source_path_id())
+            .is_some();
+        if removed {
+            self.reload_sources(vec![path.path().to_owned()]);
+        }
+        removed
+    }
+
     async fn load_async(
         &self,
         asset_path: AssetPath<'_>,
@@ -247,7 +351,7 @@ impl AssetServer {
                     committed_assets: Default::default(),
                     load_state: LoadState::NotLoaded,
                     meta: None,
-                    // path: asset_path.path().to_owned(),
+                    path: asset_path.path().to_owned(),
                     version: 0,
                 }),
             };
@@ -286,9 +390,25 @@ impl AssetServer {
             }
         };
 
+        // explicit loader settings take precedence over the meta file
+        let settings = self
+            .server
+            .loader_settings
+            .read()
+            .get(&asset_path_id.source_path_id())
+            .cloned();
+
         // load the asset bytes
-        let bytes = match self.server.asset_io.load_path(asset_path.path()).await {
-            Ok(bytes) => bytes,
+        let asset_io = &self.server.asset_io;
+        let loaded = match settings {
+            Some(_) => asset_io
+                .load_path(asset_path.path())
+                .await
+                .map(|bytes| (bytes, None)),
+            None => asset_io.load_path_with_meta(asset_path.path()).await,
+        };
+        let (bytes, meta_file) = match loaded {
+            Ok(loaded) => loaded,
             Err(err) => {
                 set_asset_failed();
                 return Err(AssetServerError::AssetIoError(err));
@@ -303,6 +423,8 @@ impl AssetServer {
             version,
             &self.server.task_pool,
         );
+        load_context.settings = settings;
+        load_context.meta_file = meta_file;
 
         if let Err(err) = asset_loader
             .load(&bytes, &mut load_context)
@@ -351,6 +473,10 @@ impl AssetServer {
             .asset_io
             .watch_path_for_changes(asset_path.path())
             .unwrap();
+        self.server
+            .asset_io
+            .watch_path_for_changes(&meta_file_path(asset_path.path()))
+            .unwrap();
         self.create_assets_in_load_context(&mut load_context);
         Ok(asset_path_id)
     }
@@ -541,3 +667,181 @@ pub fn free_unused_assets_system(asset_server: Res<AssetServer>) {
     asset_server.free_unused_assets();
     asset_server.mark_unused_assets();
 }
+
+pub fn reload_changed_assets_system(asset_server: Res<AssetServer>) {
+    asset_server.reload_changed_assets();
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use serde::Deserialize;
+    use std::{
+        any::TypeId,
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        asset::{
+            AssetEvent, AssetLoader, AssetPath, AssetServer, Assets, LoadContext, LoadedAsset,
+            MemoryAssetIo,
+            loader::{AssetLifecycleChannel, AssetLifecycleEvent},
+            update_asset_storage_system,
+        },
+        ecs::{Events, IntoSystem, System, World},
+        tasks::TaskPool,
+        ty::BoxedFuture,
+    };
+
+    struct Text(String);
+
+    #[derive(Clone, Default, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct TextSettings {
+        uppercase: bool,
+    }
+
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            load_context: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<()>> {
+            Box::pin(async move {
+                let settings = load_context.settings::<TextSettings>()?;
+                let mut text = String::from_utf8(bytes.to_vec())?;
+                if settings.uppercase {
+                    text = text.to_uppercase();
+                }
+                load_context.set_default_asset(LoadedAsset::new(Text(text)));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
+    // The content is the path of the included text
+    struct IncludeLoader;
+
+    impl AssetLoader for IncludeLoader {
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            load_context: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<()>> {
+            Box::pin(async move {
+                let path = String::from_utf8(bytes.to_vec())?;
+                let asset = LoadedAsset::new(Text(format!("include {}", path)))
+                    .with_dependency(AssetPath::from(path.as_str()));
+                load_context.set_default_asset(asset);
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["inc"]
+        }
+    }
+
+    fn next_text(asset_server: &AssetServer) -> String {
+        let asset_lifecycles = asset_server.server.asset_lifecycles.read();
+        let channel = asset_lifecycles[&TypeId::of::<Text>()]
+            .downcast_ref::<AssetLifecycleChannel<Text>>()
+            .unwrap();
+        match channel.receiver.recv_timeout(Duration::from_secs(5)) {
+            Ok(AssetLifecycleEvent::Create(result)) => result.asset.0,
+            _ => panic!("Asset was not loaded"),
+        }
+    }
+
+    #[test]
+    fn loader_settings() {
+        let io = MemoryAssetIo::new();
+        io.insert("plain.txt", b"hello".to_vec());
+        io.insert("meta.txt", b"hello".to_vec());
+        io.insert("meta.txt.meta", b"(uppercase: true)".to_vec());
+
+        let asset_server = AssetServer::new(io, TaskPool::new());
+        let _assets = asset_server.register_asset_type::<Text>();
+        asset_server.add_loader(TextLoader);
+
+        let _plain = asset_server.load::<Text, _>("plain.txt");
+        assert_eq!(next_text(&asset_server), "hello");
+        let _meta = asset_server.load::<Text, _>("meta.txt");
+        assert_eq!(next_text(&asset_server), "HELLO");
+
+        let _plain = asset_server
+            .load_with_settings::<Text, _, _>("plain.txt", TextSettings { uppercase: true });
+        assert_eq!(next_text(&asset_server), "HELLO");
+        let _meta = asset_server
+            .load_with_settings::<Text, _, _>("meta.txt", TextSettings { uppercase: false });
+        assert_eq!(next_text(&asset_server), "hello");
+
+        // The first settings are kept
+        let _plain = asset_server
+            .load_with_settings::<Text, _, _>("plain.txt", TextSettings { uppercase: false });
+        assert!(asset_server.remove_loader_settings("meta.txt"));
+        assert_eq!(next_text(&asset_server), "HELLO");
+        assert!(asset_server.remove_loader_settings("plain.txt"));
+        assert_eq!(next_text(&asset_server), "hello");
+        assert!(!asset_server.remove_loader_settings("plain.txt"));
+    }
+
+    #[test]
+    fn reload_dependents() {
+        let io = MemoryAssetIo::new();
+        io.insert("text.txt", b"hello".to_vec());
+        io.insert("main.inc", b"text.txt".to_vec());
+
+        let asset_server = AssetServer::new(io.clone(), TaskPool::new());
+        asset_server.watch_for_changes().unwrap();
+        asset_server.add_loader(TextLoader);
+        asset_server.add_loader(IncludeLoader);
+        let mut world = World::new();
+        world.insert_resource(asset_server.register_asset_type::<Text>());
+        world.insert_resource(asset_server.clone());
+        world.init_resource::<Events<AssetEvent<Text>>>();
+
+        let mut update = update_asset_storage_system::<Text>.system();
+        let mut send_events = Assets::<Text>::asset_event_system.system();
+        update.initialize(&mut world);
+        send_events.initialize(&mut world);
+        let mut wait_for = |world: &mut World, count: usize| {
+            let mut events = Vec::new();
+            let start = Instant::now();
+            while events.len() < count && start.elapsed() < Duration::from_secs(5) {
+                unsafe {
+                    update.run((), world);
+                    send_events.run((), world);
+                }
+                events.extend(world.resource_mut::<Events<AssetEvent<Text>>>().drain());
+            }
+            events
+        };
+
+        let text = asset_server.load::<Text, _>("text.txt");
+        let main = asset_server.load::<Text, _>("main.inc");
+        assert_eq!(wait_for(&mut world, 2).len(), 2);
+
+        io.insert("text.txt", b"world".to_vec());
+        asset_server.reload_changed_assets();
+        let modified = wait_for(&mut world, 2)
+            .into_iter()
+            .filter_map(|event| match event {
+                AssetEvent::Modified { handle } => Some(handle.id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(modified.len(), 2);
+        assert!(modified.contains(&text.id) && modified.contains(&main.id));
+        assert_eq!(
+            world.resource::<Assets<Text>>().get(&text).unwrap().0,
+            "world"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     any::TypeId,
     collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
 };
 
 use super::path::{AssetPath, LabelId};
@@ -20,7 +21,7 @@ pub struct AssetMeta {
 #[derive(Clone, Debug)]
 pub struct SourceInfo {
     pub meta: Option<SourceMeta>,
-    // pub path: PathBuf,
+    pub path: PathBuf,
     pub asset_types: HashMap<LabelId, TypeId>,
     pub load_state: LoadState,
     pub committed_assets: HashSet<LabelId>,
@@ -37,6 +38,17 @@ impl SourceInfo {
     pub fn get_asset_type(&self, label_id: LabelId) -> Option<TypeId> {
         self.asset_types.get(&label_id).cloned()
     }
+
+    pub fn depends_on(&self, path: &Path) -> bool {
+        self.meta.as_ref().is_some_and(|meta| {
+            meta.assets.iter().any(|asset| {
+                asset
+                    .dependencies
+                    .iter()
+                    .any(|dependency| dependency.path() == path)
+            })
+        })
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
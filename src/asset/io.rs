@@ -1,4 +1,9 @@
+mod embedded_asset_io;
 mod file_asset_io;
+mod filesystem_watcher;
+mod layered_asset_io;
+mod memory_asset_io;
+mod pak_asset_io;
 
 use std::{
     io,
@@ -10,7 +15,13 @@ use thiserror::Error;
 
 use crate::ty::BoxedFuture;
 
+use super::path::meta_file_path;
+
+pub use embedded_asset_io::*;
 pub use file_asset_io::*;
+pub use layered_asset_io::*;
+pub use memory_asset_io::*;
+pub use pak_asset_io::*;
 
 #[derive(Error, Debug)]
 pub enum AssetIoError {
@@ -18,12 +29,35 @@ pub enum AssetIoError {
     NotFound(PathBuf),
     #[error("encountered an io error while loading asset: {0}")]
     Io(#[from] io::Error),
-    // #[error("failed to watch path: {0}")]
-    // PathWatchError(PathBuf),
+    #[error("failed to watch path: {0}")]
+    PathWatchError(PathBuf),
+    #[error("invalid asset archive {0}: {1}")]
+    InvalidArchive(PathBuf, String),
 }
 
+// Bytes of an asset and of its meta file
+pub type AssetWithMeta = (Vec<u8>, Option<Vec<u8>>);
+
 pub trait AssetIo: Downcast + Send + Sync + 'static {
     fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>>;
+
+    // Loads the asset together with its meta file from the same source, the meta file is None if
+    // it does not exist
+    fn load_path_with_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<AssetWithMeta, AssetIoError>> {
+        Box::pin(async move {
+            let bytes = self.load_path(path).await?;
+            let meta_file = match self.load_path(&meta_file_path(path)).await {
+                Ok(meta_file) => Some(meta_file),
+                Err(AssetIoError::NotFound(_)) => None,
+                Err(error) => return Err(error),
+            };
+            Ok((bytes, meta_file))
+        })
+    }
+
     fn read_directory(
         &self,
         path: &Path,
@@ -31,4 +65,32 @@ pub trait AssetIo: Downcast + Send + Sync + 'static {
     fn is_directory(&self, path: &Path) -> bool;
     fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError>;
     fn watch_for_changes(&self) -> Result<(), AssetIoError>;
+
+    // Watched paths that changed since the last call, the asset server reloads them
+    fn changed_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+// Direct children of a directory in a flat list of file paths, nested files yield their folder
+fn child_paths<'a>(files: impl Iterator<Item = &'a PathBuf>, directory: &Path) -> Vec<PathBuf> {
+    let mut children = Vec::new();
+    for file in files {
+        let Some(child) = file
+            .strip_prefix(directory)
+            .ok()
+            .and_then(|relative| relative.components().next())
+        else {
+            continue;
+        };
+        let child = directory.join(child);
+        if !children.contains(&child) {
+            children.push(child);
+        }
+    }
+    children
+}
+
+fn has_children<'a>(mut files: impl Iterator<Item = &'a PathBuf>, directory: &Path) -> bool {
+    files.any(|file| file != directory && file.starts_with(directory))
 }
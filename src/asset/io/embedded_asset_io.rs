@@ -0,0 +1,96 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::ty::BoxedFuture;
+
+use super::{AssetIo, AssetIoError, child_paths, has_children};
+
+// Assets compiled into the binary, usually filled with the embedded_asset macro
+#[derive(Clone, Default)]
+pub struct EmbeddedAssetIo {
+    files: HashMap<PathBuf, &'static [u8]>,
+}
+
+impl EmbeddedAssetIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<P: Into<PathBuf>>(&mut self, path: P, bytes: &'static [u8]) -> &mut Self {
+        self.files.insert(path.into(), bytes);
+        self
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.files.contains_key(path.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl AssetIo for EmbeddedAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            self.files
+                .get(path)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        if !has_children(self.files.keys(), path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(child_paths(self.files.keys(), path).into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        has_children(self.files.keys(), path)
+    }
+
+    // Embedded assets never change
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+// Embeds a file of the crate's asset folder, the path is relative to the folder and is also the
+// asset path the file is loaded with
+//
+//     let mut io = EmbeddedAssetIo::new();
+//     embedded_asset!(io, "textures/player.png");
+//     embedded_asset!(io, "resources", "shaders/sprite.wgsl");
+#[macro_export]
+macro_rules! embedded_asset {
+    ($io:expr, $path:literal) => {
+        $crate::embedded_asset!($io, "assets", $path)
+    };
+    ($io:expr, $folder:literal, $path:literal) => {
+        $io.insert(
+            $path,
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/",
+                $folder,
+                "/",
+                $path
+            )),
+        )
+    };
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use parking_lot::RwLock;
 use std::{
     env,
     fs::{self, File},
@@ -8,16 +9,18 @@ use std::{
 
 use crate::ty::BoxedFuture;
 
-use super::{AssetIo, AssetIoError};
+use super::{AssetIo, AssetIoError, filesystem_watcher::FilesystemWatcher};
 
 pub struct FileAssetIo {
     root_path: PathBuf,
+    filesystem_watcher: RwLock<Option<FilesystemWatcher>>,
 }
 
 impl FileAssetIo {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
         FileAssetIo {
             root_path: Self::get_root_path().join(path.as_ref()),
+            filesystem_watcher: Default::default(),
         }
     }
 
@@ -70,14 +73,31 @@ impl AssetIo for FileAssetIo {
         )))
     }
 
-    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        if let Some(watcher) = self.filesystem_watcher.write().as_mut() {
+            watcher.watch(path);
+        }
         Ok(())
     }
 
     fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        let mut filesystem_watcher = self.filesystem_watcher.write();
+        if filesystem_watcher.is_none() {
+            let watcher = FilesystemWatcher::new(&self.root_path)
+                .map_err(|_| AssetIoError::PathWatchError(self.root_path.clone()))?;
+            *filesystem_watcher = Some(watcher);
+        }
         Ok(())
     }
 
+    fn changed_paths(&self) -> Vec<PathBuf> {
+        self.filesystem_watcher
+            .read()
+            .as_ref()
+            .map(FilesystemWatcher::changed_paths)
+            .unwrap_or_default()
+    }
+
     fn is_directory(&self, path: &Path) -> bool {
         self.root_path.join(path).is_dir()
     }
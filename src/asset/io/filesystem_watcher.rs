@@ -0,0 +1,69 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crossbeam_channel::Receiver;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+// Watches the whole asset folder, only changes of the files registered with watch are reported
+pub struct FilesystemWatcher {
+    root_path: PathBuf,
+    watched: HashSet<PathBuf>,
+    receiver: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl FilesystemWatcher {
+    pub fn new(root_path: &Path) -> notify::Result<Self> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let mut watcher = RecommendedWatcher::new(
+            move |event| {
+                let _ = sender.send(event);
+            },
+            notify::Config::default(),
+        )?;
+        watcher.watch(root_path, RecursiveMode::Recursive)?;
+
+        Ok(Self {
+            // Event paths are absolute
+            root_path: root_path
+                .canonicalize()
+                .unwrap_or_else(|_| root_path.to_owned()),
+            watched: HashSet::new(),
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn watch(&mut self, path: &Path) {
+        self.watched.insert(path.to_owned());
+    }
+
+    // Paths relative to the asset folder, every changed file is reported once
+    pub fn changed_paths(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.receiver.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(error) => {
+                    log::warn!("Failed to watch asset folder: {}", error);
+                    continue;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+
+            for path in event.paths {
+                let Ok(path) = path.strip_prefix(&self.root_path) else {
+                    continue;
+                };
+                if self.watched.contains(path) && !changed.iter().any(|other| other == path) {
+                    changed.push(path.to_owned());
+                }
+            }
+        }
+        changed
+    }
+}
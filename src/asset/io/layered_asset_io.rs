@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    asset::path::{split_source_prefix, with_source_prefix},
+    ty::BoxedFuture,
+};
+
+use super::{AssetIo, AssetIoError, AssetWithMeta};
+
+struct AssetSourceLayer {
+    name: Option<String>,
+    priority: i32,
+    io: Box<dyn AssetIo>,
+}
+
+// A path is resolved by the layer of the highest priority that has it, layers of the same priority
+// in the order they were added. A `name://` path prefix selects a named source directly.
+#[derive(Default)]
+pub struct LayeredAssetIo {
+    layers: Vec<AssetSourceLayer>,
+}
+
+impl LayeredAssetIo {
+    // The layers are resolved in the given order
+    pub fn new(layers: Vec<Box<dyn AssetIo>>) -> Self {
+        Self {
+            layers: layers
+                .into_iter()
+                .map(|io| AssetSourceLayer {
+                    name: None,
+                    priority: 0,
+                    io,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn push<T: AssetIo>(&mut self, layer: T) -> &mut Self {
+        self.insert_layer(None, 0, Box::new(layer))
+    }
+
+    // Base game, DLC and mods are typically added as sources of increasing priority
+    pub fn add_source<T: AssetIo>(&mut self, name: &str, priority: i32, io: T) -> &mut Self {
+        self.add_boxed_source(name, priority, Box::new(io))
+    }
+
+    pub fn add_boxed_source(
+        &mut self,
+        name: &str,
+        priority: i32,
+        io: Box<dyn AssetIo>,
+    ) -> &mut Self {
+        assert!(
+            self.source(name).is_none(),
+            "Asset source {} already exists",
+            name
+        );
+        self.insert_layer(Some(name.to_owned()), priority, io)
+    }
+
+    pub fn source(&self, name: &str) -> Option<&dyn AssetIo> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name.as_deref() == Some(name))
+            .map(|layer| &*layer.io)
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    fn insert_layer(
+        &mut self,
+        name: Option<String>,
+        priority: i32,
+        io: Box<dyn AssetIo>,
+    ) -> &mut Self {
+        let index = self
+            .layers
+            .iter()
+            .position(|layer| layer.priority < priority)
+            .unwrap_or(self.layers.len());
+        self.layers
+            .insert(index, AssetSourceLayer { name, priority, io });
+        self
+    }
+
+    // The layers the path is looked up in, with the source prefix removed
+    fn resolve<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<(Vec<&'a dyn AssetIo>, &'a Path), AssetIoError> {
+        match split_source_prefix(path) {
+            Some((name, source_path)) => match self.source(name) {
+                Some(io) => Ok((vec![io], source_path)),
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            },
+            None => Ok((self.layers.iter().map(|layer| &*layer.io).collect(), path)),
+        }
+    }
+}
+
+impl AssetIo for LayeredAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let (layers, source_path) = self.resolve(path)?;
+            for layer in layers {
+                match layer.load_path(source_path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    // The meta file is read from the layer that serves the asset
+    fn load_path_with_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<AssetWithMeta, AssetIoError>> {
+        Box::pin(async move {
+            let (layers, source_path) = self.resolve(path)?;
+            for layer in layers {
+                match layer.load_path_with_meta(source_path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let (layers, source_path) = self.resolve(path)?;
+        let source = split_source_prefix(path).map(|(name, _)| name);
+        let mut found = false;
+        let mut paths = Vec::new();
+        for layer in layers
+            .iter()
+            .filter(|layer| layer.is_directory(source_path))
+        {
+            found = true;
+            for child in layer.read_directory(source_path)? {
+                let child = match source {
+                    Some(name) => with_source_prefix(name, &child),
+                    None => child,
+                };
+                if !paths.contains(&child) {
+                    paths.push(child);
+                }
+            }
+        }
+
+        if !found {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(paths.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.resolve(path).is_ok_and(|(layers, source_path)| {
+            layers.iter().any(|layer| layer.is_directory(source_path))
+        })
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        let (layers, source_path) = self.resolve(path)?;
+        for layer in layers {
+            layer.watch_path_for_changes(source_path)?;
+        }
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        for layer in &self.layers {
+            layer.io.watch_for_changes()?;
+        }
+        Ok(())
+    }
+
+    // A change in a named source is reported both with and without the source prefix
+    fn changed_paths(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for layer in &self.layers {
+            for path in layer.io.changed_paths() {
+                if let Some(name) = &layer.name {
+                    let prefixed = with_source_prefix(name, &path);
+                    if !changed.contains(&prefixed) {
+                        changed.push(prefixed);
+                    }
+                }
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_lite::future;
+    use std::path::{Path, PathBuf};
+
+    use crate::asset::{AssetIo, AssetPath, EmbeddedAssetIo, LayeredAssetIo, MemoryAssetIo};
+
+    #[test]
+    fn earlier_layers_take_precedence() {
+        let memory = MemoryAssetIo::new();
+        memory.insert("textures/player.png", vec![1u8]);
+        let mut embedded = EmbeddedAssetIo::new();
+        embedded
+            .insert("textures/player.png", &[2])
+            .insert("textures/enemy.png", &[3])
+            .insert("fonts/main.ttf", &[4]);
+
+        let io = LayeredAssetIo::new(vec![Box::new(memory.clone()), Box::new(embedded)]);
+        let load = |path: &str| future::block_on(io.load_path(Path::new(path))).ok();
+        assert_eq!(load("textures/player.png"), Some(vec![1]));
+        assert_eq!(load("textures/enemy.png"), Some(vec![3]));
+        assert_eq!(load("textures/missing.png"), None);
+
+        memory.insert("textures/missing.png", vec![5u8]);
+        assert_eq!(load("textures/missing.png"), Some(vec![5]));
+
+        assert!(io.is_directory(Path::new("textures")));
+        assert!(!io.is_directory(Path::new("textures/enemy.png")));
+        let mut root = io
+            .read_directory(Path::new(""))
+            .unwrap()
+            .collect::<Vec<_>>();
+        root.sort();
+        assert_eq!(
+            root,
+            vec![PathBuf::from("fonts"), PathBuf::from("textures")]
+        );
+        assert_eq!(io.read_directory(Path::new("textures")).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn sources_override_by_priority() {
+        let base = MemoryAssetIo::new();
+        base.insert("textures/player.png", vec![1u8]);
+        base.insert("sounds/jump.ogg", vec![1u8]);
+        let dlc = MemoryAssetIo::new();
+        dlc.insert("textures/player.png", vec![2u8]);
+        dlc.insert("textures/boss.png", vec![2u8]);
+        let modded = MemoryAssetIo::new();
+        modded.insert("textures/player.png", vec![3u8]);
+
+        let mut io = LayeredAssetIo::default();
+        io.add_source("mod", 10, modded)
+            .add_source("base", 0, base)
+            .add_source("dlc", 5, dlc);
+
+        let load = |path: &str| future::block_on(io.load_path(Path::new(path))).ok();
+        assert_eq!(load("textures/player.png"), Some(vec![3]));
+        assert_eq!(load("textures/boss.png"), Some(vec![2]));
+        assert_eq!(load("sounds/jump.ogg"), Some(vec![1]));
+        assert_eq!(load("base://textures/player.png"), Some(vec![1]));
+        assert_eq!(load("dlc://textures/player.png"), Some(vec![2]));
+        assert_eq!(load("mod://sounds/jump.ogg"), None);
+        assert_eq!(load("missing://sounds/jump.ogg"), None);
+
+        let mut textures = io
+            .read_directory(Path::new("dlc://textures"))
+            .unwrap()
+            .collect::<Vec<_>>();
+        textures.sort();
+        assert_eq!(
+            textures,
+            vec![
+                PathBuf::from("dlc://textures/boss.png"),
+                PathBuf::from("dlc://textures/player.png")
+            ]
+        );
+        assert!(!io.is_directory(Path::new("mod://sounds")));
+        assert_eq!(io.read_directory(Path::new("textures")).unwrap().count(), 2);
+
+        let path = AssetPath::from("mod://textures/player.png#sprite");
+        assert_eq!(path.source(), Some("mod"));
+        assert_eq!(path.source_path(), Path::new("textures/player.png"));
+        assert_eq!(path.label(), Some("sprite"));
+        let path = path.with_source("dlc");
+        assert_eq!(path.path(), Path::new("dlc://textures/player.png"));
+        assert_eq!(path.label(), Some("sprite"));
+    }
+
+    #[test]
+    fn meta_files_from_serving_layer() {
+        let base = MemoryAssetIo::new();
+        base.insert("player.png", vec![1u8]);
+        base.insert("player.png.meta", vec![1u8]);
+        base.insert("enemy.png", vec![1u8]);
+        let modded = MemoryAssetIo::new();
+        modded.insert("player.png", vec![2u8]);
+        modded.insert("enemy.png.meta", vec![2u8]);
+
+        let mut io = LayeredAssetIo::default();
+        io.add_source("base", 0, base).add_source("mod", 10, modded);
+        let load = |path: &str| future::block_on(io.load_path_with_meta(Path::new(path))).unwrap();
+        assert_eq!(load("player.png"), (vec![2], None));
+        assert_eq!(load("enemy.png"), (vec![1], None));
+        assert_eq!(load("base://player.png"), (vec![1], Some(vec![1])));
+    }
+}
@@ -0,0 +1,134 @@
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::ty::BoxedFuture;
+
+use super::{AssetIo, AssetIoError, child_paths, has_children};
+
+#[derive(Default)]
+struct MemoryAssets {
+    files: HashMap<PathBuf, Arc<[u8]>>,
+    // None until watching is enabled
+    watched: Option<HashSet<PathBuf>>,
+    changed: Vec<PathBuf>,
+}
+
+// Clones share the same files, a clone can be kept to insert assets after the server was created
+#[derive(Clone, Default)]
+pub struct MemoryAssetIo {
+    assets: Arc<RwLock<MemoryAssets>>,
+}
+
+impl MemoryAssetIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replacing a watched file reloads the assets loaded from it
+    pub fn insert<P: Into<PathBuf>, B: Into<Arc<[u8]>>>(&self, path: P, bytes: B) {
+        let path = path.into();
+        let mut assets = self.assets.write();
+        let watched = assets
+            .watched
+            .as_ref()
+            .is_some_and(|watched| watched.contains(&path));
+        if watched && !assets.changed.contains(&path) {
+            assets.changed.push(path.clone());
+        }
+        assets.files.insert(path, bytes.into());
+    }
+
+    pub fn remove<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.assets.write().files.remove(path.as_ref()).is_some()
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.assets.read().files.contains_key(path.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.read().files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.read().files.is_empty()
+    }
+}
+
+impl AssetIo for MemoryAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            self.assets
+                .read()
+                .files
+                .get(path)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let assets = self.assets.read();
+        if !has_children(assets.files.keys(), path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(child_paths(assets.files.keys(), path).into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        has_children(self.assets.read().files.keys(), path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        if let Some(watched) = self.assets.write().watched.as_mut() {
+            watched.insert(path.to_owned());
+        }
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        let mut assets = self.assets.write();
+        if assets.watched.is_none() {
+            assets.watched = Some(HashSet::new());
+        }
+        Ok(())
+    }
+
+    fn changed_paths(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.assets.write().changed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::asset::{AssetIo, MemoryAssetIo};
+
+    #[test]
+    fn watched_memory_files_change() {
+        let memory = MemoryAssetIo::new();
+        memory.insert("config.ron", vec![1u8]);
+        memory
+            .watch_path_for_changes(Path::new("config.ron"))
+            .unwrap();
+        memory.insert("config.ron", vec![2u8]);
+        assert!(memory.changed_paths().is_empty());
+
+        memory.watch_for_changes().unwrap();
+        memory
+            .watch_path_for_changes(Path::new("config.ron"))
+            .unwrap();
+        memory.insert("config.ron", vec![3u8]);
+        memory.insert("other.ron", vec![3u8]);
+        assert_eq!(memory.changed_paths(), vec![PathBuf::from("config.ron")]);
+        assert!(memory.changed_paths().is_empty());
+    }
+}
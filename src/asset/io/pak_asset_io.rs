@@ -0,0 +1,435 @@
+use bincode::Options;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::ty::BoxedFuture;
+
+use super::{AssetIo, AssetIoError, child_paths, has_children};
+
+// Layout: magic, format version (u32), index size (u64), bincode index, file data. Integers are
+// little endian and the data offsets of the index are relative to the end of the index.
+const PAK_MAGIC: &[u8; 4] = b"QPAK";
+const PAK_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct PakIndexEntry {
+    // Relative to the asset folder, always separated by '/'
+    path: String,
+    offset: u64,
+    size: u64,
+}
+
+trait PakSource: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> PakSource for T {}
+
+struct PakArchive {
+    name: PathBuf,
+    source: Mutex<Box<dyn PakSource>>,
+    data_offset: u64,
+}
+
+#[derive(Clone, Copy)]
+struct PakFile {
+    archive: usize,
+    offset: u64,
+    size: u64,
+}
+
+// Files of later archives replace the files of the same path in the earlier ones, so patches can
+// be shipped as small archives
+#[derive(Default)]
+pub struct PakAssetIo {
+    archives: Vec<PakArchive>,
+    files: HashMap<PathBuf, PakFile>,
+}
+
+impl PakAssetIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, AssetIoError> {
+        let mut io = Self::new();
+        for path in paths {
+            io.add_archive_file(path)?;
+        }
+        Ok(io)
+    }
+
+    pub fn add_archive_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), AssetIoError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => AssetIoError::NotFound(path.to_owned()),
+            _ => error.into(),
+        })?;
+        self.add_archive(path, BufReader::new(file))
+    }
+
+    // The name is only used in errors
+    pub fn add_archive<P, R>(&mut self, name: P, mut source: R) -> Result<(), AssetIoError>
+    where
+        P: Into<PathBuf>,
+        R: Read + Seek + Send + 'static,
+    {
+        let name = name.into();
+        let invalid = |reason: &str| AssetIoError::InvalidArchive(name.clone(), reason.to_owned());
+
+        let mut magic = [0u8; 4];
+        source.read_exact(&mut magic)?;
+        if &magic != PAK_MAGIC {
+            return Err(invalid("not a pak archive"));
+        }
+        let mut version = [0u8; 4];
+        source.read_exact(&mut version)?;
+        if u32::from_le_bytes(version) != PAK_VERSION {
+            return Err(invalid("unsupported format version"));
+        }
+        let mut index_size = [0u8; 8];
+        source.read_exact(&mut index_size)?;
+        let index_size = u64::from_le_bytes(index_size);
+
+        let mut index = Vec::new();
+        (&mut source).take(index_size).read_to_end(&mut index)?;
+        if index.len() as u64 != index_size {
+            return Err(invalid("truncated index"));
+        }
+        let entries: Vec<PakIndexEntry> = bincode::options()
+            .with_limit(index_size)
+            .deserialize(&index)
+            .map_err(|_| invalid("corrupted index"))?;
+        let data_offset = source.stream_position()?;
+        let data_size = source.seek(SeekFrom::End(0))? - data_offset;
+        let in_bounds = |entry: &PakIndexEntry| {
+            entry
+                .offset
+                .checked_add(entry.size)
+                .is_some_and(|end| end <= data_size)
+        };
+        if !entries.iter().all(in_bounds) {
+            return Err(invalid("file data out of bounds"));
+        }
+
+        let archive = self.archives.len();
+        for entry in entries {
+            self.files.insert(
+                PathBuf::from(entry.path),
+                PakFile {
+                    archive,
+                    offset: entry.offset,
+                    size: entry.size,
+                },
+            );
+        }
+        self.archives.push(PakArchive {
+            name,
+            source: Mutex::new(Box::new(source)),
+            data_offset,
+        });
+        Ok(())
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.files.contains_key(path.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn read_file(&self, file: PakFile) -> Result<Vec<u8>, AssetIoError> {
+        let archive = &self.archives[file.archive];
+        let mut source = archive.source.lock();
+        source.seek(SeekFrom::Start(archive.data_offset + file.offset))?;
+        let mut bytes = vec![0; file.size as usize];
+        source.read_exact(&mut bytes).map_err(|_| {
+            AssetIoError::InvalidArchive(archive.name.clone(), "truncated file data".to_owned())
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl AssetIo for PakAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match self.files.get(path) {
+                Some(file) => self.read_file(*file),
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        if !has_children(self.files.keys(), path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(child_paths(self.files.keys(), path).into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        has_children(self.files.keys(), path)
+    }
+
+    // Archives are read only
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+enum PakWriterData {
+    Bytes(Vec<u8>),
+    // Streamed from disk when the archive is written
+    File(PathBuf),
+}
+
+struct PakWriterFile {
+    path: String,
+    size: u64,
+    data: PakWriterData,
+}
+
+// Builds a pak archive, the files are written in the order they were added
+#[derive(Default)]
+pub struct PakWriter {
+    files: Vec<PakWriterFile>,
+    indices: HashMap<String, usize>,
+}
+
+impl PakWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds every file of the folder with its path relative to the folder
+    pub fn add_folder<P: AsRef<Path>>(&mut self, folder: P) -> Result<&mut Self, AssetIoError> {
+        let folder = folder.as_ref();
+        let mut paths = Vec::new();
+        collect_files(folder, &mut paths)?;
+        paths.sort();
+        for path in paths {
+            let size = fs::metadata(&path)?.len();
+            let relative = path.strip_prefix(folder).unwrap().to_owned();
+            self.insert(&relative, size, PakWriterData::File(path));
+        }
+        Ok(self)
+    }
+
+    // Replaces a previously added file of the same path
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, bytes: Vec<u8>) -> &mut Self {
+        let size = bytes.len() as u64;
+        self.insert(path.as_ref(), size, PakWriterData::Bytes(bytes));
+        self
+    }
+
+    fn insert(&mut self, path: &Path, size: u64, data: PakWriterData) {
+        let path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        match self.indices.get(&path) {
+            Some(&index) => {
+                let file = &mut self.files[index];
+                file.size = size;
+                file.data = data;
+            }
+            None => {
+                self.indices.insert(path.clone(), self.files.len());
+                self.files.push(PakWriterFile { path, size, data });
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), AssetIoError> {
+        let mut offset = 0;
+        let entries = self
+            .files
+            .iter()
+            .map(|file| {
+                let entry = PakIndexEntry {
+                    path: file.path.clone(),
+                    offset,
+                    size: file.size,
+                };
+                offset += entry.size;
+                entry
+            })
+            .collect::<Vec<_>>();
+        let index = bincode::options()
+            .serialize(&entries)
+            .expect("Pak index should be serializable");
+
+        writer.write_all(PAK_MAGIC)?;
+        writer.write_all(&PAK_VERSION.to_le_bytes())?;
+        writer.write_all(&(index.len() as u64).to_le_bytes())?;
+        writer.write_all(&index)?;
+        for file in &self.files {
+            match &file.data {
+                PakWriterData::Bytes(bytes) => writer.write_all(bytes)?,
+                PakWriterData::File(path) => {
+                    // The index already holds the size, a file that shrank since would corrupt it
+                    let copied = io::copy(&mut File::open(path)?.take(file.size), &mut writer)?;
+                    if copied != file.size {
+                        let message = format!("{} changed while packing", path.display());
+                        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, message).into());
+                    }
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<(), AssetIoError> {
+        let file = File::create(path)?;
+        self.write(std::io::BufWriter::new(file))
+    }
+}
+
+// Packs the whole asset folder into a single archive, returns the number of packed files
+pub fn pack_asset_folder<P: AsRef<Path>, Q: AsRef<Path>>(
+    folder: P,
+    output: Q,
+) -> Result<usize, AssetIoError> {
+    let mut writer = PakWriter::new();
+    writer.add_folder(folder)?;
+    writer.write_file(output)?;
+    Ok(writer.len())
+}
+
+fn collect_files(folder: &Path, paths: &mut Vec<PathBuf>) -> Result<(), AssetIoError> {
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use futures_lite::future;
+    use std::{
+        fs,
+        io::Cursor,
+        path::{Path, PathBuf},
+    };
+
+    use crate::asset::{AssetIo, AssetIoError, PakAssetIo, PakWriter};
+
+    fn archive(files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+        let mut writer = PakWriter::new();
+        for (path, bytes) in files {
+            writer.add_file(path, bytes.to_vec());
+        }
+        let mut bytes = Vec::new();
+        writer.write(&mut bytes).unwrap();
+        Cursor::new(bytes)
+    }
+
+    #[test]
+    fn read_archives() {
+        let mut io = PakAssetIo::new();
+        io.add_archive(
+            "base.pak",
+            archive(&[
+                ("textures/player.png", &[1, 2, 3]),
+                ("textures/enemy.png", &[4]),
+                ("fonts/main.ttf", &[]),
+            ]),
+        )
+        .unwrap();
+        io.add_archive("patch.pak", archive(&[("textures/player.png", &[5])]))
+            .unwrap();
+
+        let load = |path: &str| future::block_on(io.load_path(Path::new(path)));
+        assert_eq!(load("textures/player.png").unwrap(), vec![5]);
+        assert_eq!(load("textures/enemy.png").unwrap(), vec![4]);
+        assert_eq!(load("fonts/main.ttf").unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            load("textures/missing.png"),
+            Err(AssetIoError::NotFound(_))
+        ));
+
+        assert!(io.is_directory(Path::new("textures")));
+        assert!(!io.is_directory(Path::new("fonts/main.ttf")));
+        let mut textures = io
+            .read_directory(Path::new("textures"))
+            .unwrap()
+            .collect::<Vec<_>>();
+        textures.sort();
+        assert_eq!(
+            textures,
+            vec![
+                PathBuf::from("textures/enemy.png"),
+                PathBuf::from("textures/player.png")
+            ]
+        );
+    }
+
+    #[test]
+    fn pack_folder() {
+        let folder = std::env::temp_dir().join(format!("quad_pak_{}", std::process::id()));
+        fs::create_dir_all(folder.join("textures")).unwrap();
+        fs::write(folder.join("textures/player.png"), [1, 2, 3]).unwrap();
+        fs::write(folder.join("config.ron"), [4]).unwrap();
+
+        let mut writer = PakWriter::new();
+        writer
+            .add_folder(&folder)
+            .unwrap()
+            .add_file("config.ron", vec![5]);
+        assert_eq!(writer.len(), 2);
+        let output = folder.join("assets.pak");
+        writer.write_file(&output).unwrap();
+
+        let io = PakAssetIo::open(&[&output]).unwrap();
+        let load = |path: &str| future::block_on(io.load_path(Path::new(path))).unwrap();
+        assert_eq!(load("textures/player.png"), vec![1, 2, 3]);
+        assert_eq!(load("config.ron"), vec![5]);
+        drop(io);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_archive() {
+        let mut io = PakAssetIo::new();
+        let result = io.add_archive("invalid.pak", Cursor::new(b"ZIP!0000".to_vec()));
+        assert!(matches!(result, Err(AssetIoError::InvalidArchive(..))));
+        assert!(io.is_empty());
+
+        let mut truncated = archive(&[("textures/player.png", &[1, 2, 3])]).into_inner();
+        truncated.pop();
+        let result = io.add_archive("truncated.pak", Cursor::new(truncated));
+        assert!(matches!(result, Err(AssetIoError::InvalidArchive(..))));
+        assert!(io.is_empty());
+    }
+}
@@ -1,7 +1,14 @@
 use anyhow::Result;
 use crossbeam_channel::{Receiver, Sender};
 use downcast_rs::{Downcast, impl_downcast};
-use std::{any::TypeId, collections::HashMap, path::Path};
+use serde::de::DeserializeOwned;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
 
 use crate::{
     ecs::{Res, ResMut},
@@ -27,6 +34,27 @@ pub trait AssetLoader: Send + Sync + 'static {
     fn extensions(&self) -> &[&str];
 }
 
+// Per asset configuration of a loader, read with `LoadContext::settings`
+pub trait LoaderSettings:
+    Clone + Default + DeserializeOwned + PartialEq + Send + Sync + 'static
+{
+}
+
+impl<T> LoaderSettings for T where
+    T: Clone + Default + DeserializeOwned + PartialEq + Send + Sync + 'static
+{
+}
+
+pub(crate) type BoxedLoaderSettings = Arc<dyn Any + Send + Sync>;
+
+#[derive(Error, Debug)]
+pub enum LoaderSettingsError {
+    #[error("invalid meta file of {0}: {1}")]
+    InvalidMetaFile(PathBuf, ron::error::SpannedError),
+    #[error("loader settings of {0} have a different type than {1}")]
+    UnexpectedType(PathBuf, &'static str),
+}
+
 pub trait Asset: AssetDynamic {
     fn static_asset_type_id() -> TypeId;
 }
@@ -115,6 +143,8 @@ pub struct LoadContext<'a> {
     pub(crate) path: &'a Path,
     pub(crate) version: usize,
     pub(crate) task_pool: &'a TaskPool,
+    pub(crate) settings: Option<BoxedLoaderSettings>,
+    pub(crate) meta_file: Option<Vec<u8>>,
 }
 
 impl<'a> LoadContext<'a> {
@@ -132,6 +162,8 @@ impl<'a> LoadContext<'a> {
             version,
             path,
             task_pool,
+            settings: None,
+            meta_file: None,
         }
     }
 
@@ -139,6 +171,24 @@ impl<'a> LoadContext<'a> {
         self.path
     }
 
+    // Settings passed to `AssetServer::load_with_settings`, otherwise the RON encoded settings of the
+    // `.meta` file next to the asset, otherwise the defaults
+    pub fn settings<S: LoaderSettings>(&self) -> Result<S, LoaderSettingsError> {
+        if let Some(settings) = &self.settings {
+            return settings.downcast_ref::<S>().cloned().ok_or_else(|| {
+                LoaderSettingsError::UnexpectedType(
+                    self.path.to_owned(),
+                    std::any::type_name::<S>(),
+                )
+            });
+        }
+        match &self.meta_file {
+            Some(bytes) => ron::de::from_bytes(bytes)
+                .map_err(|error| LoaderSettingsError::InvalidMetaFile(self.path.to_owned(), error)),
+            None => Ok(S::default()),
+        }
+    }
+
     pub fn has_labeled_asset(&self, label: &str) -> bool {
         self.labeled_assets.contains_key(&Some(label.to_string()))
     }
@@ -43,6 +43,26 @@ impl<'a> AssetPath<'a> {
         &self.path
     }
 
+    // Name of the source selected by a `name://` prefix of the path
+    pub fn source(&self) -> Option<&str> {
+        split_source_prefix(&self.path).map(|(source, _)| source)
+    }
+
+    // Path without the source prefix
+    pub fn source_path(&self) -> &Path {
+        split_source_prefix(&self.path).map_or(&self.path, |(_, path)| path)
+    }
+
+    pub fn with_source(&self, source: &str) -> AssetPath<'static> {
+        AssetPath {
+            path: Cow::Owned(with_source_prefix(source, self.source_path())),
+            label: self
+                .label
+                .as_ref()
+                .map(|value| Cow::Owned(value.to_string())),
+        }
+    }
+
     #[inline]
     pub fn to_owned(&self) -> AssetPath<'static> {
         AssetPath {
@@ -102,6 +122,29 @@ impl AssetPathId {
     }
 }
 
+pub(crate) fn split_source_prefix(path: &Path) -> Option<(&str, &Path)> {
+    let (source, path) = path.to_str()?.split_once("://")?;
+    if source.is_empty() || source.contains(['/', '\\', '#']) {
+        return None;
+    }
+    Some((source, Path::new(path)))
+}
+
+pub(crate) fn with_source_prefix(source: &str, path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}://{}", source, path.display()))
+}
+
+// Loader settings of `texture.png` are read from `texture.png.meta`
+pub(crate) fn meta_file_path(path: &Path) -> PathBuf {
+    let mut meta_path = path.as_os_str().to_owned();
+    meta_path.push(".meta");
+    PathBuf::from(meta_path)
+}
+
+pub(crate) fn strip_meta_extension(path: &Path) -> Option<PathBuf> {
+    (path.extension()? == "meta").then(|| path.with_extension(""))
+}
+
 pub(crate) fn get_hasher() -> impl Hasher {
     DefaultHasher::new() // TODO: Not stable for serialization
 }
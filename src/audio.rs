@@ -10,7 +10,7 @@ pub use audio_output::*;
 pub use audio_source::*;
 use rodio::{OutputStream, OutputStreamHandle};
 
-use crate::app::{App, MainStage};
+use crate::app::{App, MainStage, Plugin};
 
 pub mod prelude {
     pub use crate::audio::{Audio, AudioOutput, AudioSource, Decodable};
@@ -47,12 +47,34 @@ impl AudioDevice {
     }
 }
 
+// Audio is played through the given device, the default plugin has no device and drops all audio
+#[derive(Default)]
+pub struct AudioPlugin {
+    stream_handle: Option<OutputStreamHandle>,
+}
+
+impl AudioPlugin {
+    pub fn new(audio_device: &AudioDevice) -> Self {
+        Self {
+            stream_handle: audio_device.stream_handle.clone(),
+        }
+    }
+}
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        add_audio_output(app, self.stream_handle.clone());
+    }
+}
+
 pub fn audio_plugin(app: &mut App, audio_device: &AudioDevice) {
+    add_audio_output(app, audio_device.stream_handle.clone());
+}
+
+fn add_audio_output(app: &mut App, stream_handle: Option<OutputStreamHandle>) {
     app.add_asset::<AudioSource>()
         .add_asset::<AudioSink>()
-        .insert_resource(AudioOutput::<AudioSource>::new(
-            audio_device.stream_handle.clone(),
-        ))
+        .insert_resource(AudioOutput::<AudioSource>::new(stream_handle))
         .init_resource::<Audio<AudioSource>>()
         .add_system_to_stage(
             MainStage::PostTransformUpdate,
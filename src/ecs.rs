@@ -1,7 +1,10 @@
 mod component;
+mod diagnostics;
 mod entity;
 mod event;
+mod observer;
 mod query;
+mod relation;
 mod schedule;
 mod storage;
 mod system;
@@ -10,20 +13,29 @@ mod world;
 pub use quad_macros::{Bundle, Component, Event, Resource};
 
 pub use component::{
-    Bundle, Component, ComponentId, Components, DetectChanges, Res, ResMut, Resource, ResourceId,
-    Tick,
+    Bundle, CmptMut, Component, ComponentDescriptor, ComponentHook, ComponentHooks, ComponentId,
+    ComponentInfo, Components, DetectChanges, Res, ResMut, Resource, ResourceId, StorageType, Tick,
 };
-pub use entity::Entity;
-pub use event::{Event, EventId, EventReader, EventWriter, Events};
+pub use diagnostics::{
+    ArchetypeDiagnostics, EcsDiagnostics, StageDiagnostics, SystemDiagnostics, TableDiagnostics,
+};
+pub use entity::{Entity, EntityMap, MapEntities, MapEntitiesError};
+pub use event::{Event, EventId, EventReader, EventRetention, EventWriter, Events};
+pub use observer::{BoxedObserver, ComponentEvent, Trigger};
 pub use query::{
+    dynamic::DynamicQuery,
     fetch::{ChangeTrackers, QueryItem, ReadOnlyWorldQuery, WorldQuery},
     filter::{Added, Changed, Or, With, Without},
     state::QueryState,
 };
-pub use schedule::{Schedule, Scheduler};
+pub use relation::{Related, RelatedBy, Relation};
+pub use schedule::{
+    BoxedCondition, BoxedSystem, ConditionalSystem, ParallelExecutor, ParallelSystem, Schedule,
+    Scheduler, resource_added, resource_changed, resource_equals, resource_exists,
+};
 pub use system::{
     IntoSystem, System,
-    command::Commands,
+    command::{Command, Commands, EntityCommands},
     function_system::SystemMeta,
     local::Local,
     query::Query,
@@ -40,7 +52,8 @@ pub mod prelude {
     pub use crate::ecs::{
         Added, Bundle, ChangeTrackers, Changed, Commands, Component, DetectChanges, Entity,
         EventReader, EventWriter, FromWorld, IntoSystem, Local, Or, ParamSet, Query, QueryState,
-        RemovedComponents, Res, ResMut, Resource, Schedule, Scheduler, System, With, Without,
-        World,
+        Related, RelatedBy, Relation, RemovedComponents, Res, ResMut, Resource, Schedule,
+        Scheduler, System, With, Without, World, resource_added, resource_changed, resource_equals,
+        resource_exists,
     };
 }
@@ -1,25 +1,38 @@
 mod bundle;
 mod change_detection;
+mod hooks;
 mod resource;
 mod ticks;
 mod type_info;
 
 pub use bundle::{Bundle, BundleId, BundleInfo, Bundles};
 pub use change_detection::{CmptMut, DetectChanges, Res, ResMut};
+pub use hooks::{ComponentHook, ComponentHooks};
 pub use resource::{Resource, ResourceId, Resources};
 pub use ticks::{ComponentTicks, Tick};
 
 use std::{
     alloc::Layout,
     any::TypeId,
+    borrow::Cow,
     collections::{HashMap, hash_map::Entry},
 };
 
-use crate::ecs::query::access::AccessIndex;
+use crate::ecs::{query::access::AccessIndex, world::CloneFn};
 
 use type_info::TypeInfo;
 
-pub trait Component: Send + Sync + 'static {}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageType {
+    #[default]
+    Table,
+    // Stored outside of archetype tables, inserting or removing the component does not move the entity
+    SparseSet,
+}
+
+pub trait Component: Send + Sync + 'static {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
 
 #[derive(Debug, Copy, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
 pub struct ComponentId(usize);
@@ -50,10 +63,13 @@ pub enum ComponentStatus {
 #[derive(Debug)]
 pub struct ComponentInfo {
     id: ComponentId,
-    name: &'static str,
-    type_id: TypeId,
+    name: Cow<'static, str>,
+    type_id: Option<TypeId>,
     layout: Layout,
-    drop: unsafe fn(*mut u8),
+    drop: Option<unsafe fn(*mut u8)>,
+    storage_type: StorageType,
+    hooks: ComponentHooks,
+    cloner: Option<CloneFn>,
 }
 
 impl ComponentInfo {
@@ -63,12 +79,13 @@ impl ComponentInfo {
     }
 
     #[inline]
-    pub fn name(&self) -> &'static str {
-        self.name
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
+    // None for components registered at runtime
     #[inline]
-    pub fn type_id(&self) -> TypeId {
+    pub fn type_id(&self) -> Option<TypeId> {
         self.type_id
     }
 
@@ -79,18 +96,101 @@ impl ComponentInfo {
 
     #[inline]
     pub fn drop(&self) -> unsafe fn(*mut u8) {
-        self.drop
+        self.drop.unwrap_or(drop_nothing)
+    }
+
+    #[inline]
+    pub fn needs_drop(&self) -> bool {
+        self.drop.is_some()
+    }
+
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        self.type_id.is_none()
+    }
+
+    #[inline]
+    pub fn storage_type(&self) -> StorageType {
+        self.storage_type
+    }
+
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
     }
 
-    pub fn new(id: ComponentId, type_info: &TypeInfo) -> Self {
+    #[inline]
+    pub fn is_cloneable(&self) -> bool {
+        self.cloner.is_some()
+    }
+
+    #[inline]
+    pub(crate) fn cloner(&self) -> Option<CloneFn> {
+        self.cloner
+    }
+
+    pub fn new(id: ComponentId, type_info: &TypeInfo, storage_type: StorageType) -> Self {
         Self {
             id,
-            name: type_info.type_name(),
-            type_id: type_info.type_id(),
-            drop: type_info.drop(),
+            name: Cow::Borrowed(type_info.type_name()),
+            type_id: Some(type_info.type_id()),
+            drop: Some(type_info.drop()),
             layout: type_info.layout(),
+            storage_type,
+            hooks: Default::default(),
+            cloner: None,
+        }
+    }
+
+    fn from_descriptor(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
+        Self {
+            id,
+            name: descriptor.name,
+            type_id: None,
+            drop: descriptor.drop,
+            layout: descriptor.layout,
+            storage_type: descriptor.storage_type,
+            hooks: Default::default(),
+            cloner: None,
+        }
+    }
+}
+
+unsafe fn drop_nothing(_value: *mut u8) {}
+
+// Component defined at runtime, e.g. by a script, its values are handled as raw bytes
+#[derive(Debug, Clone)]
+pub struct ComponentDescriptor {
+    name: Cow<'static, str>,
+    layout: Layout,
+    drop: Option<unsafe fn(*mut u8)>,
+    storage_type: StorageType,
+}
+
+impl ComponentDescriptor {
+    // The drop function is called with a pointer to the value, use None for plain data
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            layout,
+            drop,
+            storage_type: StorageType::Table,
         }
     }
+
+    pub fn with_storage_type(mut self, storage_type: StorageType) -> Self {
+        self.storage_type = storage_type;
+        self
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 #[derive(Debug)]
@@ -102,10 +202,16 @@ pub enum ComponentsError {
 pub struct Components {
     components: Vec<ComponentInfo>,
     indices: HashMap<TypeId, ComponentId>,
+    dynamic_indices: HashMap<Cow<'static, str>, ComponentId>,
+    has_hooks: bool,
 }
 
 impl Components {
-    fn add(&mut self, info: &TypeInfo) -> Result<ComponentId, ComponentsError> {
+    fn add(
+        &mut self,
+        info: &TypeInfo,
+        storage_type: StorageType,
+    ) -> Result<ComponentId, ComponentsError> {
         let index = self.components.len();
         let index_entry = self.indices.entry(info.type_id());
         if let Entry::Occupied(_) = index_entry {
@@ -114,7 +220,25 @@ impl Components {
 
         let id = ComponentId::new(index);
         self.indices.insert(info.type_id(), id);
-        self.components.push(ComponentInfo::new(id, info));
+        self.components
+            .push(ComponentInfo::new(id, info, storage_type));
+
+        Ok(id)
+    }
+
+    pub fn add_dynamic(
+        &mut self,
+        descriptor: ComponentDescriptor,
+    ) -> Result<ComponentId, ComponentsError> {
+        let index_entry = self.dynamic_indices.entry(descriptor.name.clone());
+        if let Entry::Occupied(_) = index_entry {
+            return Err(ComponentsError::ComponentAlreadyExists);
+        }
+
+        let id = ComponentId::new(self.components.len());
+        index_entry.or_insert(id);
+        self.components
+            .push(ComponentInfo::from_descriptor(id, descriptor));
 
         Ok(id)
     }
@@ -140,19 +264,38 @@ impl Components {
         self.components.get_unchecked(id.index())
     }
 
+    #[inline]
+    pub fn has_hooks(&self) -> bool {
+        self.has_hooks
+    }
+
+    pub(crate) fn hooks_mut(&mut self, id: ComponentId) -> &mut ComponentHooks {
+        self.has_hooks = true;
+        &mut self.components[id.index()].hooks
+    }
+
+    pub(crate) fn set_cloner(&mut self, id: ComponentId, cloner: CloneFn) {
+        self.components[id.index()].cloner = Some(cloner);
+    }
+
     #[inline]
     #[allow(clippy::missing_safety_doc)]
     pub fn get_id(&self, type_id: TypeId) -> Option<ComponentId> {
         self.indices.get(&type_id).copied()
     }
 
+    #[inline]
+    pub fn get_dynamic_id(&self, name: &str) -> Option<ComponentId> {
+        self.dynamic_indices.get(name).copied()
+    }
+
     pub fn get_or_insert<T: Component>(&mut self) -> ComponentId {
         let component_id = self.get_id(TypeId::of::<T>());
         if let Some(id) = component_id {
             id
         } else {
             let info = TypeInfo::of::<T>();
-            self.add(&info).unwrap()
+            self.add(&info, T::STORAGE_TYPE).unwrap()
         }
     }
 }
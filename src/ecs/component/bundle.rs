@@ -3,10 +3,16 @@ use std::{
     collections::HashMap,
 };
 
-use crate::{ecs::storage::Table, macros::all_tuples};
+use crate::{
+    ecs::{
+        Entity,
+        storage::{SparseSets, Table},
+    },
+    macros::all_tuples,
+};
 
 use super::{
-    Component, ComponentId, ComponentStatus, Components,
+    Component, ComponentId, ComponentStatus, Components, StorageType,
     ticks::{ComponentTicks, Tick},
 };
 
@@ -65,13 +71,41 @@ impl BundleId {
 pub struct BundleInfo {
     pub(crate) id: BundleId,
     pub(crate) component_ids: Vec<ComponentId>,
+    pub(crate) storage_types: Vec<StorageType>,
 }
 
 impl BundleInfo {
+    #[inline]
+    pub fn table_components(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.components_with_storage(StorageType::Table)
+    }
+
+    #[inline]
+    pub fn sparse_components(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.components_with_storage(StorageType::SparseSet)
+    }
+
+    fn components_with_storage(
+        &self,
+        storage_type: StorageType,
+    ) -> impl Iterator<Item = ComponentId> + '_ {
+        self.component_ids
+            .iter()
+            .zip(self.storage_types.iter())
+            .filter(move |(_, storage)| **storage == storage_type)
+            .map(|(id, _)| *id)
+    }
+
+    // Sparse set components ignore the bundle status, the set itself knows whether the entity
+    // already has the component.
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     pub unsafe fn write_components<T: Bundle>(
         &self,
         table: &mut Table,
+        sparse_sets: &mut SparseSets,
+        components: &Components,
+        entity: Entity,
         table_row: usize,
         bundle: T,
         bundle_status: &[ComponentStatus],
@@ -79,21 +113,61 @@ impl BundleInfo {
     ) {
         let mut bundle_component = 0;
         bundle.get_components(|component_ptr| {
-            let component_id = *self.component_ids.get_unchecked(bundle_component);
-            let status = bundle_status.get_unchecked(bundle_component);
-            let column = table.get_column_mut(component_id).unwrap();
+            self.write_component(
+                table,
+                sparse_sets,
+                components,
+                entity,
+                table_row,
+                bundle_component,
+                component_ptr,
+                bundle_status,
+                change_tick,
+            );
+            bundle_component += 1;
+        });
+    }
 
-            match status {
-                ComponentStatus::Added => {
-                    column.initialize(table_row, component_ptr, ComponentTicks::new(change_tick));
-                }
-                ComponentStatus::Mutated => {
-                    column.replace(table_row, component_ptr, change_tick);
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub unsafe fn write_component(
+        &self,
+        table: &mut Table,
+        sparse_sets: &mut SparseSets,
+        components: &Components,
+        entity: Entity,
+        table_row: usize,
+        bundle_component: usize,
+        component_ptr: *mut u8,
+        bundle_status: &[ComponentStatus],
+        change_tick: Tick,
+    ) {
+        let component_id = *self.component_ids.get_unchecked(bundle_component);
+        match self.storage_types.get_unchecked(bundle_component) {
+            StorageType::Table => {
+                let status = bundle_status.get_unchecked(bundle_component);
+                let column = table.get_column_mut(component_id).unwrap();
+
+                match status {
+                    ComponentStatus::Added => {
+                        column.initialize(
+                            table_row,
+                            component_ptr,
+                            ComponentTicks::new(change_tick),
+                        );
+                    }
+                    ComponentStatus::Mutated => {
+                        column.replace(table_row, component_ptr, change_tick);
+                    }
                 }
             }
-
-            bundle_component += 1;
-        });
+            StorageType::SparseSet => {
+                let info = components.get_info_unchecked(component_id);
+                sparse_sets
+                    .get_or_insert(info)
+                    .insert(entity, component_ptr, change_tick);
+            }
+        }
     }
 }
 
@@ -101,6 +175,7 @@ impl BundleInfo {
 pub struct Bundles {
     bundle_infos: Vec<BundleInfo>,
     bundle_ids: HashMap<TypeId, BundleId>,
+    dynamic_bundle_ids: HashMap<Vec<ComponentId>, BundleId>,
 }
 
 impl Bundles {
@@ -109,18 +184,44 @@ impl Bundles {
         let id = self.bundle_ids.entry(TypeId::of::<T>()).or_insert_with(|| {
             let component_ids = T::component_ids(components);
             let id = BundleId(bundle_infos.len());
-            let bundle_info = initialize_bundle(type_name::<T>(), component_ids, id);
+            let bundle_info = initialize_bundle(type_name::<T>(), component_ids, components, id);
             bundle_infos.push(bundle_info);
             id
         });
         unsafe { self.bundle_infos.get_unchecked(id.0) }
     }
+
+    // Bundle of components that are not known at compile time
+    pub fn init_dynamic_info<'a>(
+        &'a mut self,
+        components: &Components,
+        component_ids: &[ComponentId],
+    ) -> &'a BundleInfo {
+        let bundle_infos = &mut self.bundle_infos;
+        let id = self
+            .dynamic_bundle_ids
+            .entry(component_ids.to_vec())
+            .or_insert_with(|| {
+                let id = BundleId(bundle_infos.len());
+                let bundle_info =
+                    initialize_bundle("dynamic bundle", component_ids.to_vec(), components, id);
+                bundle_infos.push(bundle_info);
+                id
+            });
+        unsafe { self.bundle_infos.get_unchecked(id.0) }
+    }
+
+    #[inline]
+    pub fn get(&self, id: BundleId) -> Option<&BundleInfo> {
+        self.bundle_infos.get(id.0)
+    }
 }
 
 // TODO: Are (A, B) and (B, A) different bundles? Sort components to treat them as the same bundle?
 fn initialize_bundle(
     bundle_type_name: &'static str,
     component_ids: Vec<ComponentId>,
+    components: &Components,
     id: BundleId,
 ) -> BundleInfo {
     let mut deduped = component_ids.clone();
@@ -130,5 +231,14 @@ fn initialize_bundle(
         panic!("Bundle {bundle_type_name} has duplicate components");
     }
 
-    BundleInfo { id, component_ids }
+    let storage_types = component_ids
+        .iter()
+        .map(|id| unsafe { components.get_info_unchecked(*id).storage_type() })
+        .collect();
+
+    BundleInfo {
+        id,
+        component_ids,
+        storage_types,
+    }
 }
@@ -171,6 +171,11 @@ impl<'w, T: Component> CmptMut<'w, T> {
             system_ticks,
         }
     }
+
+    #[inline]
+    pub fn into_inner(self) -> &'w mut T {
+        self.value
+    }
 }
 
 impl<'w, T: Component> DetectChanges for CmptMut<'w, T> {
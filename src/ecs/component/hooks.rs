@@ -0,0 +1,52 @@
+use crate::ecs::{Entity, World, observer::ComponentEvent};
+
+use super::ComponentId;
+
+pub type ComponentHook = fn(&mut World, Entity, ComponentId);
+
+// Hooks run immediately, before any observers of the same event. They receive the whole world,
+// but must not despawn the entity that triggered them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComponentHooks {
+    on_add: Option<ComponentHook>,
+    on_insert: Option<ComponentHook>,
+    on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_add.is_none(),
+            "Component already has an on_add hook"
+        );
+        self.on_add = Some(hook);
+        self
+    }
+
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_insert.is_none(),
+            "Component already has an on_insert hook"
+        );
+        self.on_insert = Some(hook);
+        self
+    }
+
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_remove.is_none(),
+            "Component already has an on_remove hook"
+        );
+        self.on_remove = Some(hook);
+        self
+    }
+
+    #[inline]
+    pub fn get(&self, event: ComponentEvent) -> Option<ComponentHook> {
+        match event {
+            ComponentEvent::Add => self.on_add,
+            ComponentEvent::Insert => self.on_insert,
+            ComponentEvent::Remove => self.on_remove,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    app::{StageId, StageLabel},
+    ecs::{Resource, World},
+};
+
+// Inserting the resource enables the diagnostics, the app then refreshes the world snapshot every
+// frame and records the last run of every stage, including the stages of the render app
+#[derive(Resource, Serialize, Clone, Debug, Default)]
+pub struct EcsDiagnostics {
+    pub entity_count: usize,
+    pub archetypes: Vec<ArchetypeDiagnostics>,
+    pub tables: Vec<TableDiagnostics>,
+    pub stages: Vec<StageDiagnostics>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ArchetypeDiagnostics {
+    pub id: usize,
+    pub table_id: usize,
+    pub entity_count: usize,
+    pub components: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TableDiagnostics {
+    pub id: usize,
+    pub entity_count: usize,
+    pub capacity: usize,
+    pub allocated_bytes: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StageDiagnostics {
+    #[serde(skip)]
+    pub id: StageId,
+    pub name: String,
+    pub run_time: Duration,
+    pub systems: Vec<SystemDiagnostics>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SystemDiagnostics {
+    pub name: String,
+    pub run_time: Duration,
+    // Commands queued by the system in the last run, counted before they were applied
+    pub pending_commands: usize,
+}
+
+impl EcsDiagnostics {
+    // Snapshot of the archetypes and tables, the stages are only recorded by the app
+    pub fn from_world(world: &World) -> Self {
+        let components = world.components();
+        let archetypes = world
+            .archetypes()
+            .iter()
+            .map(|archetype| ArchetypeDiagnostics {
+                id: archetype.id().index(),
+                table_id: archetype.table_id().index(),
+                entity_count: archetype.len(),
+                components: archetype
+                    .components()
+                    .iter()
+                    .filter_map(|component_id| components.get_info(*component_id))
+                    .map(|info| info.name().to_owned())
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        let tables = world
+            .storages()
+            .tables
+            .iter()
+            .map(|(table_id, table)| TableDiagnostics {
+                id: table_id.index(),
+                entity_count: table.len(),
+                capacity: table.capacity(),
+                allocated_bytes: table.allocated_bytes(),
+            })
+            .collect();
+
+        Self {
+            entity_count: archetypes
+                .iter()
+                .map(|archetype| archetype.entity_count)
+                .sum(),
+            archetypes,
+            tables,
+            stages: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn archetype_count(&self) -> usize {
+        self.archetypes.len()
+    }
+
+    pub fn allocated_bytes(&self) -> usize {
+        self.tables.iter().map(|table| table.allocated_bytes).sum()
+    }
+
+    pub fn stage<L: StageLabel>(&self, label: L) -> Option<&StageDiagnostics> {
+        let id = label.id();
+        self.stages.iter().find(|stage| stage.id == id)
+    }
+
+    // Replaces the previous run of the same stage
+    pub fn record_stage(&mut self, stage: StageDiagnostics) {
+        match self.stages.iter_mut().find(|other| other.id == stage.id) {
+            Some(other) => *other = stage,
+            None => self.stages.push(stage),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl World {
+    pub fn update_ecs_diagnostics(&mut self) {
+        if self.get_resource::<EcsDiagnostics>().is_none() {
+            return;
+        }
+
+        let snapshot = EcsDiagnostics::from_world(self);
+        let mut diagnostics = self.resource_mut::<EcsDiagnostics>();
+        diagnostics.entity_count = snapshot.entity_count;
+        diagnostics.archetypes = snapshot.archetypes;
+        diagnostics.tables = snapshot.tables;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecs::{Component, EcsDiagnostics, World};
+
+    #[derive(Component)]
+    struct Position(f32, f32);
+
+    #[derive(Component)]
+    struct Velocity(f32, f32);
+
+    #[test]
+    fn world_snapshot() {
+        let mut world = World::new();
+        world.spawn().insert(Position(0.0, 0.0));
+        world
+            .spawn()
+            .insert_bundle((Position(1.0, 1.0), Velocity(1.0, 0.0)));
+        world
+            .spawn()
+            .insert_bundle((Position(2.0, 2.0), Velocity(0.0, 1.0)));
+
+        world.update_ecs_diagnostics();
+        assert!(world.get_resource::<EcsDiagnostics>().is_none());
+
+        world.init_resource::<EcsDiagnostics>();
+        world.update_ecs_diagnostics();
+        let diagnostics = world.resource::<EcsDiagnostics>();
+        assert_eq!(diagnostics.entity_count, 3);
+        let archetype = diagnostics
+            .archetypes
+            .iter()
+            .find(|archetype| archetype.components.len() == 2)
+            .unwrap();
+        assert_eq!(archetype.entity_count, 2);
+        assert!(
+            archetype
+                .components
+                .iter()
+                .any(|name| name.ends_with("Velocity"))
+        );
+        assert!(diagnostics.allocated_bytes() > 0);
+
+        let json = diagnostics.to_json().unwrap();
+        assert!(json.contains("\"entity_count\": 3"));
+    }
+}
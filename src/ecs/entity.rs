@@ -1,9 +1,13 @@
 mod archetype;
+mod map_entities;
 
 use std::convert::TryFrom;
 use std::sync::atomic::{AtomicI64, Ordering};
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 pub use archetype::{Archetype, ArchetypeGeneration, ArchetypeId, Archetypes};
+pub use map_entities::{EntityMap, MapEntities, MapEntitiesError};
 
 pub enum AllocAtWithoutReplacement {
     Exists(EntityLocation),
@@ -32,6 +36,31 @@ impl Entity {
     pub fn generation(self) -> u32 {
         self.generation
     }
+
+    #[inline]
+    pub fn to_bits(self) -> u64 {
+        ((self.generation as u64) << 32) | self.id as u64
+    }
+
+    #[inline]
+    pub fn from_bits(bits: u64) -> Self {
+        Self {
+            generation: (bits >> 32) as u32,
+            id: bits as u32,
+        }
+    }
+}
+
+impl Serialize for Entity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Entity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Entity::from_bits)
+    }
 }
 
 #[derive(Default)]
@@ -56,10 +85,13 @@ impl EntityMeta {
     };
 }
 
+// Archetypes that only differ in sparse set components share a table, so the row in the table can
+// differ from the index in the archetype
 #[derive(Copy, Clone, Debug)]
 pub struct EntityLocation {
     pub archetype_id: ArchetypeId,
     pub index: usize,
+    pub table_row: usize,
 }
 
 impl EntityLocation {
@@ -67,6 +99,7 @@ impl EntityLocation {
     const EMPTY: EntityLocation = EntityLocation {
         archetype_id: ArchetypeId::INVALID,
         index: usize::MAX,
+        table_row: usize::MAX,
     };
 }
 
@@ -215,6 +248,14 @@ impl Entities {
         self.meta[entity.id as usize].location = location;
     }
 
+    pub fn update_index(&mut self, entity: Entity, index: usize) {
+        self.meta[entity.id as usize].location.index = index;
+    }
+
+    pub fn update_table_row(&mut self, entity: Entity, table_row: usize) {
+        self.meta[entity.id as usize].location.table_row = table_row;
+    }
+
     pub unsafe fn flush(&mut self, mut init: impl FnMut(Entity) -> EntityLocation) {
         let free_cursor = self.free_cursor.get_mut();
         let mut current_free_cursor = *free_cursor;
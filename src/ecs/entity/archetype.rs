@@ -108,10 +108,12 @@ impl ArchetypeIdentity {
     }
 }
 
+// The components include sparse set components, which have no column in the table
 pub struct Archetype {
     id: ArchetypeId,
     table_id: TableId,
     entities: Vec<Entity>,
+    table_rows: Vec<usize>,
     edges: Edges,
     components: Vec<ComponentId>,
 }
@@ -123,6 +125,7 @@ impl Archetype {
             id,
             table_id,
             entities: Default::default(),
+            table_rows: Default::default(),
             edges: Default::default(),
             components: components.to_vec(),
         }
@@ -173,24 +176,36 @@ impl Archetype {
         &self.entities
     }
 
-    pub fn next_location(&self) -> EntityLocation {
-        EntityLocation {
-            archetype_id: self.id,
-            index: self.entities.len(),
-        }
+    #[inline]
+    pub fn table_rows(&self) -> &[usize] {
+        &self.table_rows
+    }
+
+    #[inline]
+    pub fn set_table_row(&mut self, index: usize, table_row: usize) {
+        self.table_rows[index] = table_row;
     }
 
-    pub fn allocate(&mut self, entity: Entity) {
+    pub fn allocate(&mut self, entity: Entity, table_row: usize) -> EntityLocation {
+        let location = EntityLocation {
+            archetype_id: self.id,
+            index: self.entities.len(),
+            table_row,
+        };
         self.entities.push(entity);
+        self.table_rows.push(table_row);
+        location
     }
 
     pub fn reserve(&mut self, additional: usize) {
         self.entities.reserve(additional);
+        self.table_rows.reserve(additional);
     }
 
     pub fn swap_remove(&mut self, index: usize) -> Option<Entity> {
         let is_last = index == self.entities.len() - 1;
         self.entities.swap_remove(index);
+        self.table_rows.swap_remove(index);
 
         if is_last {
             None
@@ -201,6 +216,7 @@ impl Archetype {
 
     pub fn clear(&mut self) {
         self.entities.clear();
+        self.table_rows.clear();
     }
 
     pub fn get_remove_bundle_archetype(
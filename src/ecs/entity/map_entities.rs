@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::Entity;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapEntitiesError {
+    #[error("entity {0:?} is not present in the entity map")]
+    EntityNotFound(Entity),
+}
+
+// Components holding references to other entities implement this, so that the references can be
+// updated when the entities are recreated, e.g. when a scene is spawned
+pub trait MapEntities {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError>;
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    #[inline]
+    pub fn insert(&mut self, from: Entity, to: Entity) -> Option<Entity> {
+        self.map.insert(from, to)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, entity: Entity) -> Option<Entity> {
+        self.map.remove(&entity)
+    }
+
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Result<Entity, MapEntitiesError> {
+        self.map
+            .get(&entity)
+            .copied()
+            .ok_or(MapEntitiesError::EntityNotFound(entity))
+    }
+
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.map.keys().copied()
+    }
+
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.map.values().copied()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
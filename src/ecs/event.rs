@@ -1,4 +1,14 @@
-use std::{fmt, marker::PhantomData};
+use std::{
+    collections::VecDeque,
+    fmt,
+    marker::PhantomData,
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use parking_lot::Mutex;
 
 use super::{ResMut, Resource};
 
@@ -40,32 +50,38 @@ struct EventInstance<T> {
     pub event: T,
 }
 
-#[derive(Debug)]
-enum State {
-    A,
-    B,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventRetention {
+    // Events are dropped by the given number of updates after they were sent, the default of two
+    // updates lets every system read an event once, no matter in which stage it was sent
+    Frames(usize),
+    // Events are kept until every reader that ever read the channel has read them
+    UntilRead,
+    // Ring buffer keeping only the most recent events
+    Capacity(usize),
+}
+
+impl Default for EventRetention {
+    fn default() -> Self {
+        Self::Frames(2)
+    }
 }
 
 #[derive(Debug)]
 pub struct Events<T> {
-    events_a: Vec<EventInstance<T>>,
-    events_b: Vec<EventInstance<T>>,
-    a_start_event_count: usize,
-    b_start_event_count: usize,
+    events: VecDeque<EventInstance<T>>,
+    start_event_count: usize,
     event_count: usize,
-    state: State,
+    // Events before this count were consumed by drain or clear and are not missed by readers
+    consumed_event_count: usize,
+    retention: EventRetention,
+    frame_starts: VecDeque<usize>,
+    readers: Mutex<Vec<Weak<AtomicUsize>>>,
 }
 
 impl<T> Default for Events<T> {
     fn default() -> Self {
-        Events {
-            a_start_event_count: 0,
-            b_start_event_count: 0,
-            event_count: 0,
-            events_a: Vec::new(),
-            events_b: Vec::new(),
-            state: State::A,
-        }
+        Self::with_retention(EventRetention::default())
     }
 }
 
@@ -77,11 +93,11 @@ fn map_instance_event_with_id<T>(event_instance: &EventInstance<T>) -> (&T, Even
 
 pub struct EventReader<'w, 's, T: Event> {
     events: &'w Events<T>,
-    last_event_count: &'s mut usize,
+    last_event_count: &'s AtomicUsize,
 }
 
 impl<'w, 's, T: Event> EventReader<'w, 's, T> {
-    pub(crate) fn new(events: &'w Events<T>, last_event_count: &'s mut usize) -> Self {
+    pub(crate) fn new(events: &'w Events<T>, last_event_count: &'s AtomicUsize) -> Self {
         Self {
             events,
             last_event_count,
@@ -93,7 +109,36 @@ impl<'w, 's, T: Event> EventReader<'w, 's, T> {
     }
 
     pub fn iter_with_id(&mut self) -> impl DoubleEndedIterator<Item = (&T, EventId<T>)> {
-        internal_event_reader(self.last_event_count, self.events)
+        let mut last_event_count = self.last_event_count.load(Ordering::Relaxed);
+        let events = internal_event_reader(&mut last_event_count, self.events);
+        self.last_event_count
+            .store(last_event_count, Ordering::Relaxed);
+        events
+    }
+
+    // Number of events dropped by the retention before this reader could read them, reset by
+    // reading
+    pub fn missed(&self) -> usize {
+        let last_event_count = self.last_event_count.load(Ordering::Relaxed);
+        self.events
+            .start_event_count
+            .saturating_sub(last_event_count.max(self.events.consumed_event_count))
+    }
+
+    pub fn len(&self) -> usize {
+        let last_event_count = self.last_event_count.load(Ordering::Relaxed);
+        self.events.event_count - last_event_count.max(self.events.start_event_count)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Marks all events as read without iterating them
+    pub fn clear(&mut self) {
+        self.last_event_count
+            .store(self.events.event_count, Ordering::Relaxed);
     }
 }
 
@@ -118,113 +163,134 @@ impl<'w, T: Event> EventWriter<'w, T> {
 fn internal_event_reader<'a, T>(
     last_event_count: &mut usize,
     events: &'a Events<T>,
-) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-    let a_index = (*last_event_count).saturating_sub(events.a_start_event_count);
-    let b_index = (*last_event_count).saturating_sub(events.b_start_event_count);
+) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> + use<'a, T> {
+    let index = (*last_event_count)
+        .saturating_sub(events.start_event_count)
+        .min(events.events.len());
     *last_event_count = events.event_count;
-    match events.state {
-        State::A => events
-            .events_b
-            .get(b_index..)
-            .unwrap_or(&[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_a
-                    .get(a_index..)
-                    .unwrap_or(&[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
-        State::B => events
-            .events_a
-            .get(a_index..)
-            .unwrap_or(&[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_b
-                    .get(b_index..)
-                    .unwrap_or(&[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
-    }
+    events.events.range(index..).map(map_instance_event_with_id)
 }
 
-impl<T: Event> Events<T> {
-    pub fn send(&mut self, event: T) {
-        let event_id = EventId {
-            id: self.event_count,
-            _marker: PhantomData,
-        };
+impl<T> Events<T> {
+    pub fn with_retention(retention: EventRetention) -> Self {
+        Self::validate_retention(retention);
+        Self {
+            events: VecDeque::new(),
+            start_event_count: 0,
+            event_count: 0,
+            consumed_event_count: 0,
+            retention,
+            frame_starts: VecDeque::new(),
+            readers: Mutex::new(Vec::new()),
+        }
+    }
 
-        let event_instance = EventInstance { event_id, event };
+    #[inline]
+    pub fn retention(&self) -> EventRetention {
+        self.retention
+    }
 
-        match self.state {
-            State::A => self.events_a.push(event_instance),
-            State::B => self.events_b.push(event_instance),
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        Self::validate_retention(retention);
+        self.retention = retention;
+        self.frame_starts.clear();
+        self.enforce_capacity();
+    }
+
+    fn validate_retention(retention: EventRetention) {
+        match retention {
+            EventRetention::Frames(0) => panic!("Events must be retained for at least one frame"),
+            EventRetention::Capacity(0) => panic!("Event capacity must not be zero"),
+            _ => (),
         }
+    }
 
-        self.event_count += 1;
+    // Registers the read position of a reader for the UntilRead retention. A new reader starts at
+    // the oldest retained event, events dropped before it existed are not missed.
+    pub(crate) fn register_reader(&self, last_event_count: &Arc<AtomicUsize>) {
+        last_event_count.fetch_max(self.start_event_count, Ordering::Relaxed);
+        self.readers.lock().push(Arc::downgrade(last_event_count));
     }
 
-    pub fn update(&mut self) {
-        match self.state {
-            State::A => {
-                self.events_b = Vec::new();
-                self.state = State::B;
-                self.b_start_event_count = self.event_count;
-            }
-            State::B => {
-                self.events_a = Vec::new();
-                self.state = State::A;
-                self.a_start_event_count = self.event_count;
-            }
+    fn drop_before(&mut self, event_count: usize) {
+        while self.start_event_count < event_count && self.events.pop_front().is_some() {
+            self.start_event_count += 1;
         }
     }
 
-    pub fn update_system(mut events: ResMut<Self>) {
-        events.update();
+    fn enforce_capacity(&mut self) {
+        if let EventRetention::Capacity(capacity) = self.retention {
+            let excess = self.events.len().saturating_sub(capacity);
+            self.drop_before(self.start_event_count + excess);
+        }
     }
 
     #[inline]
-    fn reset_start_event_count(&mut self) {
-        self.a_start_event_count = self.event_count;
-        self.b_start_event_count = self.event_count;
+    pub fn len(&self) -> usize {
+        self.events.len()
     }
 
     #[inline]
-    pub fn clear(&mut self) {
-        self.reset_start_event_count();
-        self.events_a.clear();
-        self.events_b.clear();
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
     }
 
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.events_a.is_empty() && self.events_b.is_empty()
+    pub fn clear(&mut self) {
+        self.start_event_count = self.event_count;
+        self.consumed_event_count = self.event_count;
+        self.events.clear();
     }
 
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
-        self.reset_start_event_count();
-
-        let map = |i: EventInstance<T>| i.event;
-        match self.state {
-            State::A => self
-                .events_b
-                .drain(..)
-                .map(map)
-                .chain(self.events_a.drain(..).map(map)),
-            State::B => self
-                .events_a
-                .drain(..)
-                .map(map)
-                .chain(self.events_b.drain(..).map(map)),
+        self.start_event_count = self.event_count;
+        self.consumed_event_count = self.event_count;
+        self.events.drain(..).map(|instance| instance.event)
+    }
+}
+
+impl<T: Event> Events<T> {
+    pub fn send(&mut self, event: T) {
+        let event_id = EventId {
+            id: self.event_count,
+            _marker: PhantomData,
+        };
+
+        self.events.push_back(EventInstance { event_id, event });
+        self.event_count += 1;
+        self.enforce_capacity();
+    }
+
+    pub fn update(&mut self) {
+        match self.retention {
+            EventRetention::Frames(frames) => {
+                self.frame_starts.push_back(self.event_count);
+                if self.frame_starts.len() > frames {
+                    self.frame_starts.pop_front();
+                }
+                if self.frame_starts.len() == frames {
+                    self.drop_before(self.frame_starts[0]);
+                }
+            }
+            EventRetention::UntilRead => {
+                let mut readers = self.readers.lock();
+                readers.retain(|reader| reader.strong_count() > 0);
+                let read = readers
+                    .iter()
+                    .filter_map(|reader| reader.upgrade())
+                    .map(|reader| reader.load(Ordering::Relaxed))
+                    .min()
+                    .unwrap_or(self.event_count);
+                drop(readers);
+                self.drop_before(read);
+            }
+            EventRetention::Capacity(_) => (),
         }
     }
+
+    pub fn update_system(mut events: ResMut<Self>) {
+        events.update();
+    }
 }
 
 impl<T> std::iter::Extend<T> for Events<T> {
@@ -242,20 +308,20 @@ impl<T> std::iter::Extend<T> for Events<T> {
             EventInstance { event_id, event }
         });
 
-        match self.state {
-            State::A => self.events_a.extend(events),
-            State::B => self.events_b.extend(events),
-        }
-
+        self.events.extend(events);
         self.event_count = event_count;
+        self.enforce_capacity();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
+    use std::{
+        marker::PhantomData,
+        sync::{Arc, atomic::AtomicUsize},
+    };
 
-    use crate::ecs::{Event, Events};
+    use crate::ecs::{Event, EventReader, EventRetention, Events};
 
     use super::internal_event_reader;
 
@@ -379,4 +445,113 @@ mod tests {
             "reader_missed missed events unread after two update() calls"
         );
     }
+
+    #[test]
+    fn frame_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::Frames(3));
+        let mut reader = ManualEventReader::new();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.send(TestEvent { i: 1 });
+        events.update();
+        events.update();
+        assert_eq!(events.len(), 1);
+        assert_eq!(get_events(&events, &mut reader), vec![TestEvent { i: 1 }]);
+
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn until_read_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::UntilRead);
+        let cursor_a = Arc::new(AtomicUsize::new(0));
+        let cursor_b = Arc::new(AtomicUsize::new(0));
+        events.register_reader(&cursor_a);
+        events.register_reader(&cursor_b);
+
+        events.send(TestEvent { i: 0 });
+        events.send(TestEvent { i: 1 });
+        assert_eq!(EventReader::new(&events, &cursor_a).iter().count(), 2);
+        for _ in 0..5 {
+            events.update();
+        }
+        assert_eq!(events.len(), 2, "reader_b has not read the events yet");
+
+        let mut reader_b = EventReader::new(&events, &cursor_b);
+        assert_eq!(reader_b.missed(), 0);
+        assert_eq!(reader_b.iter().count(), 2);
+        events.update();
+        assert!(events.is_empty());
+
+        drop(cursor_b);
+        events.send(TestEvent { i: 2 });
+        assert_eq!(EventReader::new(&events, &cursor_a).iter().count(), 1);
+        events.update();
+        assert!(events.is_empty(), "dropped readers are not waited for");
+    }
+
+    #[test]
+    fn capacity_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::Capacity(2));
+        let cursor = Arc::new(AtomicUsize::new(0));
+        events.extend((0..5).map(|i| TestEvent { i }));
+        events.update();
+
+        let mut reader = EventReader::new(&events, &cursor);
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.missed(), 3);
+        assert_eq!(
+            reader.iter().copied().collect::<Vec<_>>(),
+            vec![TestEvent { i: 3 }, TestEvent { i: 4 }]
+        );
+        assert_eq!(reader.missed(), 0);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn drain_events() {
+        let mut events = Events::<TestEvent>::default();
+        let mut reader = ManualEventReader::new();
+        events.send(TestEvent { i: 0 });
+        events.send(TestEvent { i: 1 });
+
+        assert_eq!(events.drain().count(), 2);
+        assert!(events.is_empty());
+        events.send(TestEvent { i: 2 });
+        assert_eq!(get_events(&events, &mut reader), vec![TestEvent { i: 2 }]);
+    }
+
+    #[test]
+    fn missed_events() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::Capacity(2));
+        events.extend((0..5).map(|i| TestEvent { i }));
+
+        // Readers registered after events were dropped start at the oldest retained event
+        let late = Arc::new(AtomicUsize::new(0));
+        events.register_reader(&late);
+        let reader = EventReader::new(&events, &late);
+        assert_eq!(reader.missed(), 0);
+        assert_eq!(reader.len(), 2);
+
+        let early = Arc::new(AtomicUsize::new(0));
+        let cursor = Arc::new(AtomicUsize::new(0));
+        events.register_reader(&early);
+        events.register_reader(&cursor);
+        events.extend((5..8).map(|i| TestEvent { i }));
+        assert_eq!(EventReader::new(&events, &early).missed(), 3);
+
+        // Consumed events are not missed
+        assert_eq!(events.drain().count(), 2);
+        assert_eq!(EventReader::new(&events, &cursor).missed(), 0);
+        events.extend((8..11).map(|i| TestEvent { i }));
+        let mut reader = EventReader::new(&events, &cursor);
+        assert_eq!(reader.missed(), 1);
+        assert_eq!(reader.iter().count(), 2);
+        assert_eq!(reader.missed(), 0);
+
+        events.clear();
+        assert_eq!(EventReader::new(&events, &early).missed(), 0);
+    }
 }
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use crate::ecs::{Entity, World, component::ComponentId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentEvent {
+    // The entity did not have the component before
+    Add,
+    // The component was added or its value replaced
+    Insert,
+    // Runs before the component is removed, so its value is still accessible
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trigger {
+    pub event: ComponentEvent,
+    pub entity: Entity,
+    pub component_id: ComponentId,
+}
+
+pub type BoxedObserver = Box<dyn FnMut(&mut World, Trigger) + Send + Sync>;
+
+type ObserverKey = (ComponentEvent, ComponentId);
+
+#[derive(Default)]
+pub struct Observers {
+    global: HashMap<ObserverKey, Vec<BoxedObserver>>,
+    entities: HashMap<Entity, HashMap<ObserverKey, Vec<BoxedObserver>>>,
+}
+
+impl Observers {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.entities.is_empty()
+    }
+
+    pub fn add(
+        &mut self,
+        target: Option<Entity>,
+        event: ComponentEvent,
+        component_id: ComponentId,
+        observer: BoxedObserver,
+    ) {
+        self.target_mut(target)
+            .entry((event, component_id))
+            .or_default()
+            .push(observer);
+    }
+
+    pub fn remove_entity(&mut self, entity: Entity) {
+        self.entities.remove(&entity);
+    }
+
+    pub fn clear_entities(&mut self) {
+        self.entities.clear();
+    }
+
+    fn target_mut(
+        &mut self,
+        target: Option<Entity>,
+    ) -> &mut HashMap<ObserverKey, Vec<BoxedObserver>> {
+        match target {
+            Some(entity) => self.entities.entry(entity).or_default(),
+            None => &mut self.global,
+        }
+    }
+
+    fn take(&mut self, target: Option<Entity>, key: ObserverKey) -> Option<Vec<BoxedObserver>> {
+        match target {
+            Some(entity) => {
+                let observers = self.entities.get_mut(&entity)?;
+                let result = observers.remove(&key);
+                if observers.is_empty() {
+                    self.entities.remove(&entity);
+                }
+                result
+            }
+            None => self.global.remove(&key),
+        }
+    }
+
+    // Observers added while the taken ones were running are kept after them
+    fn restore(&mut self, target: Option<Entity>, key: ObserverKey, observers: Vec<BoxedObserver>) {
+        let entry = self.target_mut(target).entry(key).or_default();
+        let added = std::mem::replace(entry, observers);
+        entry.extend(added);
+    }
+
+    // Entity observers run before global ones. Observers are taken out of the world while they
+    // run, so an observer does not run again for events it causes itself.
+    pub(crate) fn run(world: &mut World, trigger: Trigger) {
+        let key = (trigger.event, trigger.component_id);
+        for target in [Some(trigger.entity), None] {
+            let Some(mut observers) = world.observers_mut().take(target, key) else {
+                continue;
+            };
+            for observer in observers.iter_mut() {
+                observer(world, trigger);
+            }
+            if target.is_none() || world.has_entity(trigger.entity) {
+                world.observers_mut().restore(target, key, observers);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecs::{
+            Component, ComponentId, Entity, World,
+            system::command::{CommandQueue, Commands},
+        },
+        test_util::TestLog,
+    };
+
+    use super::{ComponentEvent, Trigger};
+
+    #[derive(Component)]
+    struct Health(u32);
+
+    #[derive(Component)]
+    struct Armor;
+
+    type Log = TestLog<(&'static str, Entity)>;
+
+    fn log(world: &mut World, name: &'static str, entity: Entity) {
+        Log::push(world, (name, entity));
+    }
+
+    fn on_add(world: &mut World, entity: Entity, _component_id: ComponentId) {
+        log(world, "add", entity);
+    }
+
+    fn on_insert(world: &mut World, entity: Entity, _component_id: ComponentId) {
+        log(world, "insert", entity);
+    }
+
+    fn on_remove(world: &mut World, entity: Entity, _component_id: ComponentId) {
+        assert!(world.entity(entity).contains::<Health>());
+        log(world, "remove", entity);
+    }
+
+    fn take_log(world: &mut World) -> Vec<(&'static str, Entity)> {
+        Log::take(world)
+    }
+
+    #[test]
+    fn hooks_run_immediately() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world
+            .register_component_hooks::<Health>()
+            .on_add(on_add)
+            .on_insert(on_insert)
+            .on_remove(on_remove);
+
+        let entity = world.spawn().insert_bundle((Health(10), Armor)).id();
+        assert_eq!(
+            take_log(&mut world),
+            vec![("add", entity), ("insert", entity)]
+        );
+
+        world.entity_mut(entity).insert(Health(5));
+        assert_eq!(take_log(&mut world), vec![("insert", entity)]);
+
+        world.entity_mut(entity).remove::<Armor>();
+        assert_eq!(world.entity_mut(entity).remove::<Health>().unwrap().0, 5);
+        assert_eq!(take_log(&mut world), vec![("remove", entity)]);
+
+        world.entity_mut(entity).insert(Health(1));
+        world.despawn(entity);
+        assert_eq!(
+            take_log(&mut world),
+            vec![("add", entity), ("insert", entity), ("remove", entity)]
+        );
+    }
+
+    #[test]
+    fn observers_see_commands_within_a_frame() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.observe::<Health>(ComponentEvent::Remove, |world, trigger| {
+            log(world, "global remove", trigger.entity);
+        });
+
+        let observed = world.spawn().id();
+        let other = world.spawn().id();
+        world.entity_mut(observed).observe::<Health>(
+            ComponentEvent::Add,
+            |world, trigger: Trigger| {
+                assert_eq!(trigger.event, ComponentEvent::Add);
+                log(world, "add", trigger.entity);
+            },
+        );
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        for entity in [observed, other] {
+            commands.entity(entity).insert(Health(1)).remove::<Health>();
+        }
+        queue.apply(&mut world);
+
+        assert_eq!(
+            take_log(&mut world),
+            vec![
+                ("add", observed),
+                ("global remove", observed),
+                ("global remove", other)
+            ]
+        );
+
+        world.despawn(observed);
+        assert!(world.observers_mut().entities.is_empty());
+    }
+
+    #[test]
+    fn observer_can_despawn_the_entity() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.observe::<Health>(ComponentEvent::Remove, |world, trigger| {
+            log(world, "remove", trigger.entity);
+            world.despawn(trigger.entity);
+        });
+
+        let entity = world.spawn().insert_bundle((Health(1), Armor)).id();
+        let other = world.spawn().insert(Armor).id();
+        world.despawn(entity);
+        assert_eq!(take_log(&mut world), vec![("remove", entity)]);
+        assert!(!world.has_entity(entity));
+        assert_eq!(world.query::<&Armor>().iter(&world).count(), 1);
+        assert!(world.has_entity(other));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod access;
+pub mod dynamic;
 pub mod fetch;
 pub mod filter;
 pub mod iter;
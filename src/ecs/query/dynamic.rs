@@ -0,0 +1,270 @@
+use crate::ecs::{
+    Entity, World,
+    component::{ComponentId, StorageType, Tick},
+    entity::{Archetype, ArchetypeGeneration, ArchetypeId},
+    storage::{Column, ComponentSparseSet},
+};
+
+// Query assembled at runtime from component ids, e.g. by a scripting layer. The callbacks receive
+// pointers to the fetched components in the order the ids were given.
+#[derive(Debug)]
+pub struct DynamicQuery {
+    archetype_generation: ArchetypeGeneration,
+    matched_archetype_ids: Vec<ArchetypeId>,
+    fetch: Vec<ComponentId>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+}
+
+enum ComponentSource<'w> {
+    Table(&'w Column),
+    SparseSet(&'w ComponentSparseSet),
+}
+
+impl DynamicQuery {
+    pub fn new(component_ids: &[ComponentId]) -> Self {
+        let mut deduped = component_ids.to_vec();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(
+            deduped.len(),
+            component_ids.len(),
+            "Dynamic query fetches a component more than once"
+        );
+
+        Self {
+            archetype_generation: ArchetypeGeneration::initial(),
+            matched_archetype_ids: Vec::new(),
+            fetch: component_ids.to_vec(),
+            with: Vec::new(),
+            without: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, component_id: ComponentId) -> Self {
+        self.with.push(component_id);
+        self
+    }
+
+    pub fn without(mut self, component_id: ComponentId) -> Self {
+        self.without.push(component_id);
+        self
+    }
+
+    #[inline]
+    pub fn component_ids(&self) -> &[ComponentId] {
+        &self.fetch
+    }
+
+    pub fn update_archetypes(&mut self, world: &World) {
+        let components = world.components();
+        for &component_id in self.fetch.iter().chain(&self.with).chain(&self.without) {
+            assert!(
+                components.get_info(component_id).is_some(),
+                "Component {:?} is not registered",
+                component_id
+            );
+        }
+
+        let archetypes = world.archetypes();
+        let new_generation = archetypes.generation();
+        let old_generation = std::mem::replace(&mut self.archetype_generation, new_generation);
+        for archetype_index in old_generation.value()..new_generation.value() {
+            let archetype = &archetypes[ArchetypeId::new(archetype_index)];
+            if self.matches_archetype(archetype) {
+                self.matched_archetype_ids.push(archetype.id());
+            }
+        }
+    }
+
+    pub fn for_each(&mut self, world: &World, mut func: impl FnMut(Entity, &[*const u8])) {
+        self.update_archetypes(world);
+        let mut components = Vec::with_capacity(self.fetch.len());
+        unsafe {
+            self.for_each_unchecked(world, None, |entity, pointers| {
+                components.clear();
+                components.extend(pointers.iter().map(|pointer| *pointer as *const u8));
+                func(entity, &components);
+            });
+        }
+    }
+
+    // All fetched components of the visited entities are marked as changed
+    pub fn for_each_mut(&mut self, world: &mut World, func: impl FnMut(Entity, &[*mut u8])) {
+        self.update_archetypes(world);
+        unsafe { self.for_each_unchecked(world, Some(world.change_tick()), func) };
+    }
+
+    pub fn get(&mut self, world: &World, entity: Entity) -> Option<Vec<*const u8>> {
+        self.update_archetypes(world);
+        let location = world.entities().get(entity)?;
+        if !self.matched_archetype_ids.contains(&location.archetype_id) {
+            return None;
+        }
+
+        let archetype = world.archetype(location.archetype_id);
+        let sources = self.sources(world, archetype)?;
+        Some(
+            sources
+                .iter()
+                .map(|source| unsafe {
+                    fetch(source, entity, location.table_row, None) as *const u8
+                })
+                .collect(),
+        )
+    }
+
+    unsafe fn for_each_unchecked(
+        &self,
+        world: &World,
+        change_tick: Option<Tick>,
+        mut func: impl FnMut(Entity, &[*mut u8]),
+    ) {
+        let mut components = Vec::with_capacity(self.fetch.len());
+        for archetype_id in self.matched_archetype_ids.iter() {
+            let archetype = world.archetype(*archetype_id);
+            let Some(sources) = self.sources(world, archetype) else {
+                continue;
+            };
+            let rows = archetype.entities().iter().zip(archetype.table_rows());
+            for (&entity, &table_row) in rows {
+                components.clear();
+                components.extend(
+                    sources
+                        .iter()
+                        .map(|source| fetch(source, entity, table_row, change_tick)),
+                );
+                func(entity, &components);
+            }
+        }
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        self.fetch
+            .iter()
+            .chain(&self.with)
+            .all(|&component_id| archetype.contains(component_id))
+            && self
+                .without
+                .iter()
+                .all(|&component_id| !archetype.contains(component_id))
+    }
+
+    // Sparse sets that do not exist yet have no entities, so the archetype is skipped
+    fn sources<'w>(
+        &self,
+        world: &'w World,
+        archetype: &Archetype,
+    ) -> Option<Vec<ComponentSource<'w>>> {
+        let table = &world.storages().tables[archetype.table_id()];
+        let sparse_sets = &world.storages().sparse_sets;
+        self.fetch
+            .iter()
+            .map(|component_id| match storage_type(world, *component_id) {
+                StorageType::Table => table.get_column(*component_id).map(ComponentSource::Table),
+                StorageType::SparseSet => sparse_sets
+                    .get(*component_id)
+                    .map(ComponentSource::SparseSet),
+            })
+            .collect()
+    }
+}
+
+fn storage_type(world: &World, component_id: ComponentId) -> StorageType {
+    unsafe {
+        world
+            .components()
+            .get_info_unchecked(component_id)
+            .storage_type()
+    }
+}
+
+unsafe fn fetch(
+    source: &ComponentSource,
+    entity: Entity,
+    table_row: usize,
+    change_tick: Option<Tick>,
+) -> *mut u8 {
+    let (data, ticks) = match source {
+        ComponentSource::Table(column) => (
+            column.get_unchecked(table_row),
+            column.get_ticks_mut_ptr_unchecked(table_row),
+        ),
+        ComponentSource::SparseSet(set) => set.get_with_ticks(entity).unwrap(),
+    };
+    if let Some(change_tick) = change_tick {
+        (*ticks).set_changed(change_tick);
+    }
+    data
+}
+
+#[cfg(test)]
+mod test {
+    use std::alloc::Layout;
+
+    use crate::ecs::{Component, ComponentDescriptor, DynamicQuery, Entity, StorageType, World};
+
+    #[derive(Component)]
+    struct Static(u32);
+
+    #[test]
+    fn dynamic_components() {
+        let mut world = World::new();
+        let position = world.register_dynamic_component(ComponentDescriptor::new(
+            "position",
+            Layout::new::<[f32; 2]>(),
+            None,
+        ));
+        let tag = world.register_dynamic_component(
+            ComponentDescriptor::new("tag", Layout::new::<()>(), None)
+                .with_storage_type(StorageType::SparseSet),
+        );
+        assert_eq!(world.dynamic_component_id("position"), Some(position));
+        assert!(world.component_info(position).unwrap().is_dynamic());
+
+        let bytes = |value: [f32; 2]| {
+            value
+                .iter()
+                .flat_map(|v| v.to_ne_bytes())
+                .collect::<Vec<_>>()
+        };
+        let entity1 = world
+            .spawn()
+            .insert(Static(1))
+            .insert_bytes(position, &bytes([1.0, 2.0]))
+            .insert_bytes(tag, &[])
+            .id();
+        let entity2 = world
+            .spawn()
+            .insert_bytes(position, &bytes([3.0, 4.0]))
+            .id();
+
+        let read = |world: &World, entity: Entity| unsafe {
+            let data = world.entity(entity).get_by_id(position).unwrap();
+            (data as *const [f32; 2]).read_unaligned()
+        };
+        assert_eq!(read(&world, entity1), [1.0, 2.0]);
+
+        let mut query = DynamicQuery::new(&[position]);
+        query.for_each_mut(&mut world, |_, components| unsafe {
+            let value = &mut *(components[0] as *mut [f32; 2]);
+            value[0] += 10.0;
+        });
+        assert_eq!(read(&world, entity1), [11.0, 2.0]);
+        assert_eq!(read(&world, entity2), [13.0, 4.0]);
+
+        let mut entities = Vec::new();
+        DynamicQuery::new(&[position])
+            .with(tag)
+            .for_each(&world, |entity, _| entities.push(entity));
+        assert_eq!(entities, vec![entity1]);
+
+        let mut query = DynamicQuery::new(&[position]).without(tag);
+        assert!(query.get(&world, entity1).is_none());
+        assert!(query.get(&world, entity2).is_some());
+
+        world.entity_mut(entity1).remove_by_id(position);
+        assert!(world.entity(entity1).get_by_id(position).is_none());
+        assert!(world.entity(entity1).contains::<Static>());
+    }
+}
@@ -8,9 +8,9 @@ use std::{
 use crate::{
     ecs::{
         World,
-        component::{CmptMut, Component, ComponentId, ComponentTicks},
+        component::{CmptMut, Component, ComponentId, ComponentTicks, StorageType},
         entity::{Archetype, Entity},
-        storage::Table,
+        storage::{ComponentSparseSet, Table},
         system::SystemTicks,
     },
     macros::all_pair_tuples,
@@ -27,6 +27,8 @@ pub unsafe trait WorldQuery {
 
     fn new_state(world: &mut World) -> Self::State;
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort>;
+
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>);
 
     fn matches_archetype(state: &Self::State, archetype: &Archetype) -> bool;
@@ -63,6 +65,17 @@ pub unsafe trait WorldQuery {
     }
 }
 
+#[inline]
+pub(crate) fn get_sparse_set<T: Component>(
+    world: &World,
+    state: ComponentId,
+) -> Option<&ComponentSparseSet> {
+    match T::STORAGE_TYPE {
+        StorageType::Table => None,
+        StorageType::SparseSet => world.storages().sparse_sets.get(state),
+    }
+}
+
 #[allow(clippy::missing_safety_doc)]
 pub unsafe trait ReadOnlyWorldQuery: WorldQuery<ReadOnly = Self> {}
 pub type QueryItem<'w, Q> = <Q as WorldQuery>::Item<'w>;
@@ -77,6 +90,10 @@ unsafe impl WorldQuery for Entity {
     #[inline]
     fn new_state(_world: &mut World) -> Self::State {}
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     #[inline]
     fn update_component_access(_state: &Self::State, _access: &mut FilteredAccess<ComponentId>) {}
 
@@ -116,6 +133,7 @@ unsafe impl ReadOnlyWorldQuery for Entity {}
 
 pub struct ReadFetch<'w, T> {
     table_components: NonNull<T>, // TODO
+    sparse_set: Option<&'w ComponentSparseSet>,
     _marker: PhantomData<&'w [T]>,
 }
 
@@ -129,6 +147,10 @@ unsafe impl<T: Component> WorldQuery for &T {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         if access.access().has_write(*state) {
             panic!(
@@ -144,12 +166,13 @@ unsafe impl<T: Component> WorldQuery for &T {
     }
 
     unsafe fn new_fetch<'w>(
-        _world: &'w World,
-        _state: &Self::State,
+        world: &'w World,
+        state: &Self::State,
         _system_ticks: SystemTicks,
     ) -> Self::Fetch<'w> {
         ReadFetch {
             table_components: NonNull::dangling(),
+            sparse_set: get_sparse_set::<T>(world, *state),
             _marker: PhantomData,
         }
     }
@@ -161,16 +184,24 @@ unsafe impl<T: Component> WorldQuery for &T {
         _archetype: &Archetype,
         table: &Table,
     ) {
-        fetch.table_components = table.get_column(*state).unwrap().get_data_ptr().cast::<T>();
+        if T::STORAGE_TYPE == StorageType::Table {
+            fetch.table_components = table.get_column(*state).unwrap().get_data_ptr().cast::<T>();
+        }
     }
 
     #[inline]
     unsafe fn fetch<'w>(
         fetch: &mut Self::Fetch<'w>,
-        _entity: Entity,
+        entity: Entity,
         table_row: usize,
     ) -> Self::Item<'w> {
-        &*fetch.table_components.as_ptr().add(table_row)
+        match T::STORAGE_TYPE {
+            StorageType::Table => &*fetch.table_components.as_ptr().add(table_row),
+            StorageType::SparseSet => {
+                let set = fetch.sparse_set.unwrap_unchecked();
+                &*set.get(entity).unwrap().cast::<T>()
+            }
+        }
     }
 }
 
@@ -179,6 +210,7 @@ unsafe impl<T: Component> ReadOnlyWorldQuery for &T {}
 pub struct WriteFetch<'w, T> {
     table_components: NonNull<T>,                   // TODO
     table_ticks: *const UnsafeCell<ComponentTicks>, // TODO
+    sparse_set: Option<&'w ComponentSparseSet>,
     system_ticks: SystemTicks,
     _marker: PhantomData<&'w [T]>,
 }
@@ -193,6 +225,10 @@ unsafe impl<'__w, T: Component> WorldQuery for &'__w mut T {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         if access.access().has_read(*state) {
             panic!(
@@ -208,13 +244,14 @@ unsafe impl<'__w, T: Component> WorldQuery for &'__w mut T {
     }
 
     unsafe fn new_fetch<'w>(
-        _world: &'w World,
-        _state: &Self::State,
+        world: &'w World,
+        state: &Self::State,
         system_ticks: SystemTicks,
     ) -> WriteFetch<'w, T> {
         WriteFetch {
             table_components: NonNull::dangling(),
             table_ticks: ptr::null::<UnsafeCell<ComponentTicks>>(),
+            sparse_set: get_sparse_set::<T>(world, *state),
             system_ticks,
             _marker: PhantomData,
         }
@@ -227,20 +264,31 @@ unsafe impl<'__w, T: Component> WorldQuery for &'__w mut T {
         _archetype: &Archetype,
         table: &Table,
     ) {
-        let column = table.get_column(*state).unwrap();
-        fetch.table_components = column.get_data_ptr().cast::<T>();
-        fetch.table_ticks = column.get_ticks_ptr();
+        if T::STORAGE_TYPE == StorageType::Table {
+            let column = table.get_column(*state).unwrap();
+            fetch.table_components = column.get_data_ptr().cast::<T>();
+            fetch.table_ticks = column.get_ticks_ptr();
+        }
     }
 
     #[inline]
     unsafe fn fetch<'w>(
         fetch: &mut Self::Fetch<'w>,
-        _entity: Entity,
+        entity: Entity,
         table_row: usize,
     ) -> Self::Item<'w> {
-        let value = &mut *fetch.table_components.as_ptr().add(table_row);
-        let component_ticks = &mut *(*fetch.table_ticks.add(table_row)).get();
-        CmptMut::new(value, component_ticks, fetch.system_ticks)
+        match T::STORAGE_TYPE {
+            StorageType::Table => {
+                let value = &mut *fetch.table_components.as_ptr().add(table_row);
+                let component_ticks = &mut *(*fetch.table_ticks.add(table_row)).get();
+                CmptMut::new(value, component_ticks, fetch.system_ticks)
+            }
+            StorageType::SparseSet => {
+                let set = fetch.sparse_set.unwrap_unchecked();
+                let (data, ticks) = set.get_with_ticks(entity).unwrap();
+                CmptMut::new(&mut *data.cast::<T>(), &mut *ticks, fetch.system_ticks)
+            }
+        }
     }
 }
 
@@ -259,6 +307,10 @@ unsafe impl<T: WorldQuery> WorldQuery for Option<T> {
         T::new_state(world)
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item.map(T::shrink)
+    }
+
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         let mut intermediate = access.clone();
         T::update_component_access(state, &mut intermediate);
@@ -330,6 +382,7 @@ impl<T: Component> ChangeTrackers<T> {
 
 pub struct ChangeTrackersFetch<'w, T> {
     table_ticks: *const ComponentTicks,
+    sparse_set: Option<&'w ComponentSparseSet>,
     system_ticks: SystemTicks,
     marker: PhantomData<&'w [T]>,
 }
@@ -344,6 +397,10 @@ unsafe impl<T: Component> WorldQuery for ChangeTrackers<T> {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         if access.access().has_write(*state) {
             panic!(
@@ -359,12 +416,13 @@ unsafe impl<T: Component> WorldQuery for ChangeTrackers<T> {
     }
 
     unsafe fn new_fetch<'w>(
-        _world: &'w World,
-        _state: &Self::State,
+        world: &'w World,
+        state: &Self::State,
         system_ticks: SystemTicks,
     ) -> ChangeTrackersFetch<'w, T> {
         ChangeTrackersFetch {
             table_ticks: ptr::null::<ComponentTicks>(),
+            sparse_set: get_sparse_set::<T>(world, *state),
             system_ticks,
             marker: PhantomData,
         }
@@ -377,17 +435,26 @@ unsafe impl<T: Component> WorldQuery for ChangeTrackers<T> {
         _archetype: &Archetype,
         table: &Table,
     ) {
-        fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_const_ptr();
+        if T::STORAGE_TYPE == StorageType::Table {
+            fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_const_ptr();
+        }
     }
 
     #[inline]
     unsafe fn fetch<'w>(
         fetch: &mut Self::Fetch<'w>,
-        _entity: Entity,
+        entity: Entity,
         table_row: usize,
     ) -> Self::Item<'w> {
+        let component_ticks = match T::STORAGE_TYPE {
+            StorageType::Table => (*fetch.table_ticks.add(table_row)).clone(),
+            StorageType::SparseSet => {
+                let set = fetch.sparse_set.unwrap_unchecked();
+                (*set.get_with_ticks(entity).unwrap().1).clone()
+            }
+        };
         ChangeTrackers {
-            component_ticks: (*fetch.table_ticks.add(table_row)).clone(),
+            component_ticks,
             system_ticks: fetch.system_ticks,
             marker: PhantomData,
         }
@@ -410,6 +477,11 @@ macro_rules! impl_tuple_fetch {
                 ($($name::new_state(_world),)*)
             }
 
+            fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+                let ($($name,)*) = item;
+                ($($name::shrink($name),)*)
+            }
+
             fn update_component_access(state: &Self::State, _access: &mut FilteredAccess<ComponentId>) {
                 let ($($name,)*) = state;
                 $($name::update_component_access($name, _access);)*
@@ -3,9 +3,9 @@ use std::{cell::UnsafeCell, marker::PhantomData, ptr};
 use crate::{
     ecs::{
         World,
-        component::{Component, ComponentId, ComponentTicks},
+        component::{Component, ComponentId, ComponentTicks, StorageType},
         entity::{Archetype, Entity},
-        storage::Table,
+        storage::{ComponentSparseSet, Table},
         system::SystemTicks,
     },
     macros::{all_pair_tuples, all_tuples},
@@ -13,7 +13,7 @@ use crate::{
 
 use super::{
     access::FilteredAccess,
-    fetch::{ReadOnlyWorldQuery, WorldQuery},
+    fetch::{ReadOnlyWorldQuery, WorldQuery, get_sparse_set},
 };
 
 pub struct With<T>(PhantomData<T>);
@@ -28,6 +28,10 @@ unsafe impl<T: Component> WorldQuery for With<T> {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     #[inline]
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         access.add_with(*state);
@@ -72,6 +76,10 @@ unsafe impl<T: Component> WorldQuery for Without<T> {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     #[inline]
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         access.add_without(*state);
@@ -201,6 +209,10 @@ macro_rules! impl_query_filter_tuple {
                 ($($filter::new_state(world),)*)
             }
 
+            fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+                item
+            }
+
             fn matches_archetype(_state: &Self::State, _archetype: &Archetype) -> bool {
                 let ($($filter,)*) = _state;
                 false $(|| $filter::matches_archetype($filter, _archetype))*
@@ -217,6 +229,7 @@ pub struct Added<T>(PhantomData<T>);
 
 pub struct AddedFetch<'w, T> {
     table_ticks: *const UnsafeCell<ComponentTicks>,
+    sparse_set: Option<&'w ComponentSparseSet>,
     marker: PhantomData<&'w [T]>,
     system_ticks: SystemTicks,
 }
@@ -231,6 +244,10 @@ unsafe impl<T: Component> WorldQuery for Added<T> {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     #[inline]
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         if access.access().has_write(*state) {
@@ -247,12 +264,13 @@ unsafe impl<T: Component> WorldQuery for Added<T> {
     }
 
     unsafe fn new_fetch<'w>(
-        _world: &'w World,
-        _state: &Self::State,
+        world: &'w World,
+        state: &Self::State,
         system_ticks: SystemTicks,
     ) -> Self::Fetch<'w> {
         AddedFetch {
             table_ticks: ptr::null::<UnsafeCell<ComponentTicks>>(),
+            sparse_set: get_sparse_set::<T>(world, *state),
             marker: PhantomData,
             system_ticks,
         }
@@ -264,15 +282,23 @@ unsafe impl<T: Component> WorldQuery for Added<T> {
         _archetype: &Archetype,
         table: &Table,
     ) {
-        fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_ptr();
+        if T::STORAGE_TYPE == StorageType::Table {
+            fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_ptr();
+        }
     }
 
     unsafe fn fetch<'w>(
         fetch: &mut Self::Fetch<'w>,
-        _entity: Entity,
+        entity: Entity,
         table_row: usize,
     ) -> Self::Item<'w> {
-        let ticks = &*(*fetch.table_ticks.add(table_row)).get();
+        let ticks = match T::STORAGE_TYPE {
+            StorageType::Table => &*(*fetch.table_ticks.add(table_row)).get(),
+            StorageType::SparseSet => {
+                let set = fetch.sparse_set.unwrap_unchecked();
+                &*set.get_with_ticks(entity).unwrap().1
+            }
+        };
         ticks.is_added(fetch.system_ticks.last_change_tick)
     }
 
@@ -287,6 +313,7 @@ pub struct Changed<T>(PhantomData<T>);
 
 pub struct ChangedFetch<'w, T> {
     table_ticks: *const UnsafeCell<ComponentTicks>,
+    sparse_set: Option<&'w ComponentSparseSet>,
     marker: PhantomData<&'w [T]>,
     system_ticks: SystemTicks,
 }
@@ -301,6 +328,10 @@ unsafe impl<T: Component> WorldQuery for Changed<T> {
         world.register_component::<T>()
     }
 
+    fn shrink<'wlong: 'wshort, 'wshort>(item: Self::Item<'wlong>) -> Self::Item<'wshort> {
+        item
+    }
+
     #[inline]
     fn update_component_access(state: &Self::State, access: &mut FilteredAccess<ComponentId>) {
         if access.access().has_write(*state) {
@@ -317,12 +348,13 @@ unsafe impl<T: Component> WorldQuery for Changed<T> {
     }
 
     unsafe fn new_fetch<'w>(
-        _world: &'w World,
-        _state: &Self::State,
+        world: &'w World,
+        state: &Self::State,
         system_ticks: SystemTicks,
     ) -> Self::Fetch<'w> {
         ChangedFetch {
             table_ticks: ptr::null::<UnsafeCell<ComponentTicks>>(),
+            sparse_set: get_sparse_set::<T>(world, *state),
             marker: PhantomData,
             system_ticks,
         }
@@ -334,15 +366,23 @@ unsafe impl<T: Component> WorldQuery for Changed<T> {
         _archetype: &Archetype,
         table: &Table,
     ) {
-        fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_ptr();
+        if T::STORAGE_TYPE == StorageType::Table {
+            fetch.table_ticks = table.get_column(*state).unwrap().get_ticks_ptr();
+        }
     }
 
     unsafe fn fetch<'w>(
         fetch: &mut Self::Fetch<'w>,
-        _entity: Entity,
+        entity: Entity,
         table_row: usize,
     ) -> Self::Item<'w> {
-        let ticks = &*(*fetch.table_ticks.add(table_row)).get();
+        let ticks = match T::STORAGE_TYPE {
+            StorageType::Table => &*(*fetch.table_ticks.add(table_row)).get(),
+            StorageType::SparseSet => {
+                let set = fetch.sparse_set.unwrap_unchecked();
+                &*set.get_with_ticks(entity).unwrap().1
+            }
+        };
         ticks.is_changed(fetch.system_ticks.last_change_tick)
     }
 
@@ -9,7 +9,6 @@ use crate::ecs::{
 
 use super::{
     fetch::{ReadOnlyWorldQuery, WorldQuery},
-    filter::ArchetypeFilter,
     state::QueryState,
 };
 
@@ -18,6 +17,7 @@ pub struct QueryIter<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> {
     archetypes: &'w Archetypes,
     query_state: &'s QueryState<Q, F>,
     cursor: QueryIterationCursor<'w, 's, Q, F>,
+    yielded: usize,
 }
 
 impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> QueryIter<'w, 's, Q, F> {
@@ -31,6 +31,7 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> QueryIter<'w, 's, Q, F> {
             tables: &world.storages().tables,
             archetypes: world.archetypes(),
             cursor: QueryIterationCursor::new(world, query_state, system_ticks),
+            yielded: 0,
         }
     }
 }
@@ -39,42 +40,34 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> Iterator for QueryIter<'w, 's
     type Item = Q::Item<'w>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
+        let item = unsafe {
             self.cursor
                 .next(self.tables, self.archetypes, self.query_state)
-        }
+        };
+        self.yielded += item.is_some() as usize;
+        item
     }
 
+    // Change filters are checked per entity, so only an upper bound is known without iterating
     fn size_hint(&self) -> (usize, Option<usize>) {
         let max_size = self
             .query_state
             .matched_archetype_ids
             .iter()
             .map(|id| self.archetypes[*id].len())
-            .sum();
+            .sum::<usize>();
 
-        (0, Some(max_size))
+        (0, Some(max_size - self.yielded))
     }
 }
 
 impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> FusedIterator for QueryIter<'w, 's, Q, F> {}
 
-impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> ExactSizeIterator for QueryIter<'w, 's, Q, F>
-where
-    F: ArchetypeFilter,
-{
-    fn len(&self) -> usize {
-        self.query_state
-            .matched_archetype_ids
-            .iter()
-            .map(|id| self.archetypes[*id].len())
-            .sum()
-    }
-}
-
 struct QueryIterationCursor<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> {
     archetype_id_iter: Iter<'s, ArchetypeId>,
+    archetype_id: Option<ArchetypeId>,
     archetype_entities: &'w [Entity],
+    archetype_rows: &'w [usize],
     fetch: Q::Fetch<'w>,
     filter: F::Fetch<'w>,
     current_len: usize,
@@ -93,7 +86,9 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> QueryIterationCursor<'w, 's,
             fetch,
             filter,
             archetype_id_iter: query_state.matched_archetype_ids.iter(),
+            archetype_id: None,
             archetype_entities: &[],
+            archetype_rows: &[],
             current_len: 0,
             current_index: 0,
         }
@@ -106,33 +101,172 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> QueryIterationCursor<'w, 's,
         archetypes: &'w Archetypes,
         query_state: &'s QueryState<Q, F>,
     ) -> Option<Q::Item<'w>> {
+        let (entity, table_row) = self.next_entity(tables, archetypes, query_state)?;
+        Some(Q::fetch(&mut self.fetch, entity, table_row))
+    }
+
+    // Moves to the next matched entity without fetching it
+    #[inline(always)]
+    unsafe fn next_entity(
+        &mut self,
+        tables: &'w Tables,
+        archetypes: &'w Archetypes,
+        query_state: &'s QueryState<Q, F>,
+    ) -> Option<(Entity, usize)> {
         loop {
             if self.current_index == self.current_len {
-                let archetype_id = self.archetype_id_iter.next()?;
-                let archetype = &archetypes[*archetype_id];
-                let table = &tables[archetype.table_id()];
-                Q::set_archetype(&mut self.fetch, &query_state.fetch_state, archetype, table);
-                F::set_archetype(
-                    &mut self.filter,
-                    &query_state.filter_state,
-                    archetype,
-                    table,
-                );
-                self.archetype_entities = archetype.entities();
-                self.current_len = archetype.len();
+                let archetype_id = *self.archetype_id_iter.next()?;
+                self.set_archetype(tables, archetypes, query_state, archetype_id);
                 self.current_index = 0;
                 continue;
             }
 
             let entity = *self.archetype_entities.get_unchecked(self.current_index);
-            if !F::filter_fetch(&mut self.filter, entity, self.current_index) {
-                self.current_index += 1;
+            let table_row = *self.archetype_rows.get_unchecked(self.current_index);
+            self.current_index += 1;
+            if F::filter_fetch(&mut self.filter, entity, table_row) {
+                return Some((entity, table_row));
+            }
+        }
+    }
+
+    unsafe fn set_archetype(
+        &mut self,
+        tables: &'w Tables,
+        archetypes: &'w Archetypes,
+        query_state: &'s QueryState<Q, F>,
+        archetype_id: ArchetypeId,
+    ) {
+        let archetype = &archetypes[archetype_id];
+        let table = &tables[archetype.table_id()];
+        Q::set_archetype(&mut self.fetch, &query_state.fetch_state, archetype, table);
+        F::set_archetype(
+            &mut self.filter,
+            &query_state.filter_state,
+            archetype,
+            table,
+        );
+        self.archetype_id = Some(archetype_id);
+        self.archetype_entities = archetype.entities();
+        self.archetype_rows = archetype.table_rows();
+        self.current_len = archetype.len();
+    }
+
+    // Continues from the position of `other`, keeping its own fetches
+    unsafe fn seek(
+        &mut self,
+        other: &Self,
+        tables: &'w Tables,
+        archetypes: &'w Archetypes,
+        query_state: &'s QueryState<Q, F>,
+    ) {
+        if self.archetype_id != other.archetype_id
+            && let Some(archetype_id) = other.archetype_id
+        {
+            self.set_archetype(tables, archetypes, query_state, archetype_id);
+        }
+        self.archetype_id_iter = other.archetype_id_iter.clone();
+        self.current_index = other.current_index;
+    }
+}
+
+// Yields every unique K-sized combination of matched entities, in the order the entities are
+// iterated. Mutable items can't be handed out by an `Iterator` since a later combination can
+// contain an entity from an earlier one, so mutable queries are advanced with `fetch_next`.
+pub struct QueryCombinationIter<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery, const K: usize> {
+    tables: &'w Tables,
+    archetypes: &'w Archetypes,
+    query_state: &'s QueryState<Q, F>,
+    cursors: [QueryIterationCursor<'w, 's, Q, F>; K],
+    rows: [Option<(Entity, usize)>; K],
+}
+
+impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery, const K: usize>
+    QueryCombinationIter<'w, 's, Q, F, K>
+{
+    pub unsafe fn new(
+        world: &'w World,
+        query_state: &'s QueryState<Q, F>,
+        system_ticks: SystemTicks,
+    ) -> Self {
+        QueryCombinationIter {
+            tables: &world.storages().tables,
+            archetypes: world.archetypes(),
+            query_state,
+            cursors: std::array::from_fn(|_| {
+                QueryIterationCursor::new(world, query_state, system_ticks)
+            }),
+            rows: [None; K],
+        }
+    }
+
+    pub fn fetch_next(&mut self) -> Option<[Q::Item<'_>; K]> {
+        unsafe {
+            self.fetch_next_aliased_unchecked()
+                .map(|items| items.map(|item| Q::shrink(item)))
+        }
+    }
+
+    // Each cursor stays one entity ahead of the cursor before it. The rightmost cursor that isn't
+    // exhausted is moved and the cursors after it restart right behind it.
+    unsafe fn advance(&mut self) -> bool {
+        if K == 0 {
+            return false;
+        }
+
+        let last = if self.rows[0].is_none() { 0 } else { K - 1 };
+        'outer: for slot in (0..=last).rev() {
+            let Some(row) =
+                self.cursors[slot].next_entity(self.tables, self.archetypes, self.query_state)
+            else {
                 continue;
+            };
+            self.rows[slot] = Some(row);
+            for next in slot + 1..K {
+                let (previous, rest) = self.cursors.split_at_mut(next);
+                let cursor = &mut rest[0];
+                cursor.seek(
+                    &previous[next - 1],
+                    self.tables,
+                    self.archetypes,
+                    self.query_state,
+                );
+                match cursor.next_entity(self.tables, self.archetypes, self.query_state) {
+                    Some(row) => self.rows[next] = Some(row),
+                    None => continue 'outer,
+                }
             }
+            return true;
+        }
+        false
+    }
 
-            let item = Q::fetch(&mut self.fetch, entity, self.current_index);
-            self.current_index += 1;
-            return Some(item);
+    // The returned items may alias items returned by earlier calls
+    unsafe fn fetch_next_aliased_unchecked(&mut self) -> Option<[Q::Item<'w>; K]> {
+        if !self.advance() {
+            return None;
         }
+
+        let mut slots = self.cursors.iter_mut().zip(self.rows.iter());
+        Some(std::array::from_fn(|_| {
+            let (cursor, row) = slots.next().unwrap();
+            let (entity, table_row) = row.unwrap();
+            Q::fetch(&mut cursor.fetch, entity, table_row)
+        }))
     }
 }
+
+impl<'w, 's, Q: ReadOnlyWorldQuery, F: ReadOnlyWorldQuery, const K: usize> Iterator
+    for QueryCombinationIter<'w, 's, Q, F, K>
+{
+    type Item = [Q::Item<'w>; K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe { self.fetch_next_aliased_unchecked() }
+    }
+}
+
+impl<'w, 's, Q: ReadOnlyWorldQuery, F: ReadOnlyWorldQuery, const K: usize> FusedIterator
+    for QueryCombinationIter<'w, 's, Q, F, K>
+{
+}
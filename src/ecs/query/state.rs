@@ -1,16 +1,19 @@
 use thiserror::Error;
 
-use crate::ecs::{
-    Entity, World,
-    component::{ComponentId, Tick},
-    entity::{ArchetypeGeneration, ArchetypeId},
-    system::SystemTicks,
+use crate::{
+    ecs::{
+        Entity, World,
+        component::{ComponentId, Tick},
+        entity::{ArchetypeGeneration, ArchetypeId},
+        system::SystemTicks,
+    },
+    tasks::TaskPool,
 };
 
 use super::{
     access::FilteredAccess,
     fetch::{ROQueryItem, ReadOnlyWorldQuery, WorldQuery},
-    iter::QueryIter,
+    iter::{QueryCombinationIter, QueryIter},
 };
 
 pub struct QueryState<Q: WorldQuery, F: ReadOnlyWorldQuery = ()> {
@@ -135,8 +138,8 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
 
         Q::set_archetype(&mut fetch, &self.fetch_state, archetype, table);
         F::set_archetype(&mut filter, &self.filter_state, archetype, table);
-        if F::filter_fetch(&mut filter, entity, location.index) {
-            Ok(Q::fetch(&mut fetch, entity, location.index))
+        if F::filter_fetch(&mut filter, entity, location.table_row) {
+            Ok(Q::fetch(&mut fetch, entity, location.table_row))
         } else {
             Err(QueryEntityError::QueryDoesNotMatch)
         }
@@ -233,6 +236,37 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
         QueryIter::new(world, self, system_ticks)
     }
 
+    #[inline]
+    pub fn iter_combinations<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w World,
+    ) -> QueryCombinationIter<'w, 's, Q::ReadOnly, F::ReadOnly, K> {
+        self.update_archetypes(world);
+        unsafe {
+            self.as_readonly()
+                .iter_combinations_unchecked_manual(world, world.ticks())
+        }
+    }
+
+    #[inline]
+    pub fn iter_combinations_mut<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w mut World,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K> {
+        self.update_archetypes(world);
+        unsafe { self.iter_combinations_unchecked_manual(world, world.ticks()) }
+    }
+
+    #[inline]
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn iter_combinations_unchecked_manual<'w, 's, const K: usize>(
+        &'s self,
+        world: &'w World,
+        system_ticks: SystemTicks,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K> {
+        QueryCombinationIter::new(world, self, system_ticks)
+    }
+
     #[inline]
     pub fn for_each<'w, 's>(&'s mut self, world: &'w World, func: impl FnMut(ROQueryItem<'w, Q>)) {
         self.update_archetypes(world);
@@ -267,8 +301,9 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
             Q::set_archetype(&mut fetch, &self.fetch_state, archetype, table);
             F::set_archetype(&mut filter, &self.filter_state, archetype, table);
 
-            for table_row in 0..archetype.len() {
-                let entity = *archetype.entities().get_unchecked(table_row);
+            for index in 0..archetype.len() {
+                let entity = *archetype.entities().get_unchecked(index);
+                let table_row = *archetype.table_rows().get_unchecked(index);
                 if !F::filter_fetch(&mut filter, entity, table_row) {
                     continue;
                 }
@@ -276,6 +311,83 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
             }
         }
     }
+
+    #[inline]
+    pub fn par_for_each<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+        task_pool: &TaskPool,
+        batch_size: usize,
+        func: impl Fn(ROQueryItem<'w, Q>) + Send + Sync,
+    ) {
+        self.update_archetypes(world);
+        unsafe {
+            self.as_readonly().par_for_each_unchecked_manual(
+                world,
+                task_pool,
+                batch_size,
+                func,
+                world.ticks(),
+            );
+        }
+    }
+
+    #[inline]
+    pub fn par_for_each_mut<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+        task_pool: &TaskPool,
+        batch_size: usize,
+        func: impl Fn(Q::Item<'w>) + Send + Sync,
+    ) {
+        self.update_archetypes(world);
+        unsafe {
+            self.par_for_each_unchecked_manual(world, task_pool, batch_size, func, world.ticks());
+        }
+    }
+
+    // Splits every matched archetype into batches of at most `batch_size` entities, each batch runs
+    // as a separate task. Batches never share a table row, so mutable items and their ticks stay
+    // disjoint.
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn par_for_each_unchecked_manual<'w, 's>(
+        &'s self,
+        world: &'w World,
+        task_pool: &TaskPool,
+        batch_size: usize,
+        func: impl Fn(Q::Item<'w>) + Send + Sync,
+        system_ticks: SystemTicks,
+    ) {
+        assert!(batch_size > 0, "Batch size must be greater than zero");
+
+        let func = &func;
+        task_pool.scope(|scope| {
+            let tables = &world.storages().tables;
+            for archetype_id in self.matched_archetype_ids.iter() {
+                let archetype = world.archetype(*archetype_id);
+                let table = &tables[archetype.table_id()];
+
+                for start in (0..archetype.len()).step_by(batch_size) {
+                    let end = archetype.len().min(start + batch_size);
+                    scope.spawn(async move {
+                        let mut fetch = Q::new_fetch(world, &self.fetch_state, system_ticks);
+                        let mut filter = F::new_fetch(world, &self.filter_state, system_ticks);
+                        Q::set_archetype(&mut fetch, &self.fetch_state, archetype, table);
+                        F::set_archetype(&mut filter, &self.filter_state, archetype, table);
+
+                        for index in start..end {
+                            let entity = *archetype.entities().get_unchecked(index);
+                            let table_row = *archetype.table_rows().get_unchecked(index);
+                            if !F::filter_fetch(&mut filter, entity, table_row) {
+                                continue;
+                            }
+                            func(Q::fetch(&mut fetch, entity, table_row));
+                        }
+                    });
+                }
+            }
+        });
+    }
 }
 
 #[derive(Error, Debug)]
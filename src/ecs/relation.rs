@@ -0,0 +1,280 @@
+use std::{marker::PhantomData, ops::Deref, slice};
+
+use crate::ecs::{
+    Component, Entity, EntityMap, MapEntities, MapEntitiesError, World, component::ComponentId,
+    observer::ComponentEvent,
+};
+
+// Kind of a relation between two entities, e.g. `struct OwnedBy; impl Relation for OwnedBy {}`
+pub trait Relation: Send + Sync + 'static {
+    // An exclusive relation has at most one target, relating to another target replaces it
+    const EXCLUSIVE: bool = false;
+}
+
+// Targets of relation R on the source entity
+#[derive(Debug)]
+pub struct Related<R: Relation> {
+    entities: Vec<Entity>,
+    marker: PhantomData<fn() -> R>,
+}
+
+// Reverse links of relation R on the target entity, i.e. the sources relating to it
+#[derive(Debug)]
+pub struct RelatedBy<R: Relation> {
+    entities: Vec<Entity>,
+    marker: PhantomData<fn() -> R>,
+}
+
+// Both sides are kept in sync by the world, so the components can only be read by users
+trait Links: Component {
+    fn with(entity: Entity) -> Self;
+    fn entities_mut(&mut self) -> &mut Vec<Entity>;
+}
+
+macro_rules! impl_links {
+    ($name:ident) => {
+        impl<R: Relation> Component for $name<R> {}
+
+        impl<R: Relation> Links for $name<R> {
+            fn with(entity: Entity) -> Self {
+                Self {
+                    entities: vec![entity],
+                    marker: PhantomData,
+                }
+            }
+
+            fn entities_mut(&mut self) -> &mut Vec<Entity> {
+                &mut self.entities
+            }
+        }
+
+        impl<R: Relation> MapEntities for $name<R> {
+            fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+                for entity in self.entities.iter_mut() {
+                    *entity = entity_map.get(*entity)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl<R: Relation> Deref for $name<R> {
+            type Target = [Entity];
+
+            fn deref(&self) -> &Self::Target {
+                &self.entities[..]
+            }
+        }
+
+        impl<'a, R: Relation> IntoIterator for &'a $name<R> {
+            type Item = <Self::IntoIter as Iterator>::Item;
+            type IntoIter = slice::Iter<'a, Entity>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.entities.iter()
+            }
+        }
+    };
+}
+
+impl_links!(Related);
+impl_links!(RelatedBy);
+
+pub(crate) fn relate<R: Relation>(world: &mut World, source: Entity, target: Entity) {
+    assert_ne!(
+        source, target,
+        "Entity {:?} cannot relate to itself",
+        source
+    );
+    assert!(
+        world.entities().contains(target),
+        "Related entity {:?} does not exist",
+        target
+    );
+    register_relation::<R>(world);
+
+    if R::EXCLUSIVE {
+        let current = world
+            .get::<Related<R>>(source)
+            .map(|related| related.to_vec());
+        if current.as_deref() == Some(&[target]) {
+            return;
+        }
+        unrelate_all::<R>(world, source);
+    }
+
+    if add_link::<Related<R>>(world, source, target) {
+        add_link::<RelatedBy<R>>(world, target, source);
+    }
+}
+
+pub(crate) fn unrelate<R: Relation>(world: &mut World, source: Entity, target: Entity) {
+    remove_link::<Related<R>>(world, source, target);
+    remove_link::<RelatedBy<R>>(world, target, source);
+}
+
+pub(crate) fn unrelate_all<R: Relation>(world: &mut World, source: Entity) {
+    // The reverse links are removed by the hook
+    world.entity_mut(source).remove::<Related<R>>();
+}
+
+// Despawning either side removes the links that point to it
+fn register_relation<R: Relation>(world: &mut World) {
+    let hooks = world.register_component_hooks::<Related<R>>();
+    if hooks.get(ComponentEvent::Remove).is_none() {
+        hooks.on_remove(remove_related::<R>);
+        world
+            .register_component_hooks::<RelatedBy<R>>()
+            .on_remove(remove_related_by::<R>);
+    }
+}
+
+fn remove_related<R: Relation>(world: &mut World, source: Entity, _component_id: ComponentId) {
+    let targets = world
+        .get::<Related<R>>(source)
+        .map(|related| related.to_vec())
+        .unwrap_or_default();
+    for target in targets {
+        remove_link::<RelatedBy<R>>(world, target, source);
+    }
+}
+
+fn remove_related_by<R: Relation>(world: &mut World, target: Entity, _component_id: ComponentId) {
+    let sources = world
+        .get::<RelatedBy<R>>(target)
+        .map(|related_by| related_by.to_vec())
+        .unwrap_or_default();
+    for source in sources {
+        remove_link::<Related<R>>(world, source, target);
+    }
+}
+
+fn add_link<L: Links>(world: &mut World, entity: Entity, other: Entity) -> bool {
+    let mut entity_mut = world.entity_mut(entity);
+    match entity_mut.get_mut::<L>() {
+        Some(mut links) => {
+            if links.entities_mut().contains(&other) {
+                return false;
+            }
+            links.entities_mut().push(other);
+        }
+        None => {
+            entity_mut.insert(L::with(other));
+        }
+    }
+    true
+}
+
+fn remove_link<L: Links>(world: &mut World, entity: Entity, other: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let is_empty = match entity_mut.get_mut::<L>() {
+        Some(mut links) => {
+            links.entities_mut().retain(|item| *item != other);
+            links.entities_mut().is_empty()
+        }
+        None => return,
+    };
+    if is_empty {
+        entity_mut.remove::<L>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecs::{
+        Entity, Related, RelatedBy, Relation, World,
+        system::command::{CommandQueue, Commands},
+    };
+
+    struct Targets;
+
+    impl Relation for Targets {}
+
+    struct OwnedBy;
+
+    impl Relation for OwnedBy {
+        const EXCLUSIVE: bool = true;
+    }
+
+    fn related<R: Relation>(world: &World, entity: Entity) -> Vec<Entity> {
+        world.entity(entity).related::<R>().to_vec()
+    }
+
+    fn related_by<R: Relation>(world: &World, entity: Entity) -> Vec<Entity> {
+        world.entity(entity).related_by::<R>().to_vec()
+    }
+
+    #[test]
+    fn relate_and_unrelate() {
+        let mut world = World::new();
+        let target1 = world.spawn().id();
+        let target2 = world.spawn().id();
+        let source = world
+            .spawn()
+            .relate::<Targets>(target1)
+            .relate::<Targets>(target2)
+            .relate::<Targets>(target1)
+            .id();
+
+        assert_eq!(related::<Targets>(&world, source), vec![target1, target2]);
+        assert_eq!(related_by::<Targets>(&world, target1), vec![source]);
+        assert_eq!(related_by::<Targets>(&world, target2), vec![source]);
+
+        let mut query = world.query::<(Entity, &RelatedBy<Targets>)>();
+        assert_eq!(query.iter(&world).count(), 2);
+
+        world.entity_mut(source).unrelate::<Targets>(target1);
+        assert_eq!(related::<Targets>(&world, source), vec![target2]);
+        assert!(!world.entity(target1).contains::<RelatedBy<Targets>>());
+
+        world.entity_mut(source).unrelate_all::<Targets>();
+        assert!(!world.entity(source).contains::<Related<Targets>>());
+        assert!(!world.entity(target2).contains::<RelatedBy<Targets>>());
+    }
+
+    #[test]
+    fn exclusive_relation() {
+        let mut world = World::new();
+        let owner1 = world.spawn().id();
+        let owner2 = world.spawn().id();
+        let item = world.spawn().relate::<OwnedBy>(owner1).id();
+
+        world.entity_mut(item).relate::<OwnedBy>(owner2);
+        assert_eq!(related::<OwnedBy>(&world, item), vec![owner2]);
+        assert!(related_by::<OwnedBy>(&world, owner1).is_empty());
+        assert_eq!(related_by::<OwnedBy>(&world, owner2), vec![item]);
+    }
+
+    #[test]
+    fn despawn_removes_links() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let source1 = world.spawn().relate::<Targets>(target).id();
+        let source2 = world.spawn().relate::<Targets>(target).id();
+
+        world.despawn(source1);
+        assert_eq!(related_by::<Targets>(&world, target), vec![source2]);
+
+        world.despawn(target);
+        assert!(!world.entity(source2).contains::<Related<Targets>>());
+    }
+
+    #[test]
+    fn relate_command_skips_despawned_entities() {
+        let mut world = World::new();
+        let source = world.spawn().id();
+        let target = world.spawn().id();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(target).despawn();
+        commands.entity(source).relate::<Targets>(target);
+        let despawned = commands.spawn().id();
+        commands.entity(despawned).despawn();
+        commands.entity(despawned).relate::<Targets>(source);
+
+        queue.apply(&mut world);
+        assert!(!world.entity(source).contains::<Related<Targets>>());
+        assert!(!world.entity(source).contains::<RelatedBy<Targets>>());
+    }
+}
@@ -1,12 +1,19 @@
 use std::marker::PhantomData;
 
-use self::chain_system::EmptyChainBuilder;
+use self::{chain_system::EmptyChainBuilder, parallel_system::ParallelBuilder};
 
 use super::{IntoSystem, System, World};
 
 mod chain_system;
+mod conditional_system;
 mod parallel_system;
 
+pub use conditional_system::{
+    BoxedCondition, ConditionalSystem, resource_added, resource_changed, resource_equals,
+    resource_exists,
+};
+pub use parallel_system::{BoxedSystem, ParallelExecutor, ParallelSystem};
+
 pub struct Schedule<In = (), Out = ()> {
     system: Box<dyn System<In = In, Out = Out>>,
     initialized: bool,
@@ -73,7 +80,7 @@ impl Scheduler {
         EmptyChainBuilder::new(world)
     }
 
-    // pub fn parallel() -> ParallelBuilder {
-    //     ParallelBuilder::default()
-    // }
+    pub fn parallel() -> ParallelBuilder {
+        ParallelBuilder::default()
+    }
 }
@@ -74,6 +74,10 @@ where
         self.left.apply_buffers(world);
         self.right.apply_buffers(world);
     }
+
+    fn pending_commands(&self) -> usize {
+        self.left.pending_commands() + self.right.pending_commands()
+    }
 }
 
 pub struct EmptyChainBuilder<'w> {
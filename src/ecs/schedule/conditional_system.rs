@@ -0,0 +1,159 @@
+use crate::ecs::{
+    IntoSystem, Res, Resource, System, World,
+    component::{ComponentId, ResourceId},
+    query::access::Access,
+};
+
+use super::BoxedSystem;
+
+pub type BoxedCondition = Box<dyn System<In = (), Out = bool>>;
+
+// Runs the wrapped system only when all conditions hold. A skipped system keeps its last change
+// tick, so once it runs again it observes every change made since its previous run.
+pub struct ConditionalSystem {
+    system: BoxedSystem,
+    conditions: Vec<BoxedCondition>,
+    resource_access: Access<ResourceId>,
+    component_access: Access<ComponentId>,
+}
+
+impl ConditionalSystem {
+    pub fn new(system: BoxedSystem, conditions: Vec<BoxedCondition>) -> Self {
+        Self {
+            system,
+            conditions,
+            resource_access: Default::default(),
+            component_access: Default::default(),
+        }
+    }
+}
+
+impl System for ConditionalSystem {
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> &str {
+        self.system.name()
+    }
+
+    fn resource_access(&self) -> &Access<ResourceId> {
+        &self.resource_access
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world);
+        self.resource_access.clear();
+        self.component_access.clear();
+        self.resource_access.extend(self.system.resource_access());
+        self.component_access.extend(self.system.component_access());
+        for condition in self.conditions.iter_mut() {
+            condition.initialize(world);
+            self.resource_access.extend(condition.resource_access());
+            self.component_access.extend(condition.component_access());
+        }
+    }
+
+    unsafe fn run(&mut self, _input: (), world: &World) {
+        // Every condition is evaluated so that their own change ticks advance each frame
+        let mut should_run = true;
+        for condition in self.conditions.iter_mut() {
+            should_run &= condition.run((), world);
+        }
+        if should_run {
+            self.system.run((), world);
+        }
+    }
+
+    fn is_exclusive(&self) -> bool {
+        self.system.is_exclusive()
+    }
+
+    fn run_exclusive(&mut self, world: &mut World) {
+        let mut should_run = true;
+        for condition in self.conditions.iter_mut() {
+            should_run &= unsafe { condition.run((), world) };
+        }
+        if should_run {
+            self.system.run_exclusive(world);
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        for condition in self.conditions.iter_mut() {
+            condition.apply_buffers(world);
+        }
+        self.system.apply_buffers(world);
+    }
+
+    fn pending_commands(&self) -> usize {
+        self.conditions
+            .iter()
+            .map(|condition| condition.pending_commands())
+            .sum::<usize>()
+            + self.system.pending_commands()
+    }
+}
+
+pub fn resource_exists<T: Resource>(resource: Option<Res<T>>) -> bool {
+    resource.is_some()
+}
+
+pub fn resource_added<T: Resource>(resource: Option<Res<T>>) -> bool {
+    resource.is_some_and(|resource| resource.is_added())
+}
+
+pub fn resource_changed<T: Resource>(resource: Option<Res<T>>) -> bool {
+    resource.is_some_and(|resource| resource.is_changed())
+}
+
+pub fn resource_equals<T>(value: T) -> impl System<In = (), Out = bool> + Clone
+where
+    T: Resource + PartialEq + Clone,
+{
+    (move |resource: Option<Res<T>>| resource.is_some_and(|resource| *resource == value)).system()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecs::{IntoSystem, Res, ResMut, Resource, System, World};
+
+    use super::{ConditionalSystem, resource_changed};
+
+    #[derive(Resource, Default)]
+    struct Input(u32);
+
+    #[derive(Resource, Default)]
+    struct Output(u32);
+
+    fn copy_input(input: Res<Input>, mut output: ResMut<Output>) {
+        output.0 = input.0;
+    }
+
+    #[test]
+    fn skipped_system_sees_changes_after_resume() {
+        let mut world = World::new();
+        world.init_resource::<Input>();
+        world.init_resource::<Output>();
+
+        let mut system = ConditionalSystem::new(
+            Box::new(copy_input.system()),
+            vec![Box::new(resource_changed::<Input>.system())],
+        );
+        system.initialize(&mut world);
+
+        unsafe { system.run((), &world) };
+        assert_eq!(world.resource::<Output>().0, 0);
+
+        world.resource_mut::<Output>().0 = 10;
+        unsafe { system.run((), &world) };
+        assert_eq!(world.resource::<Output>().0, 10);
+
+        world.resource_mut::<Input>().0 = 5;
+        unsafe { system.run((), &world) };
+        assert_eq!(world.resource::<Output>().0, 5);
+    }
+}
@@ -1 +1,356 @@
+use std::time::{Duration, Instant};
 
+use crate::{
+    ecs::{
+        IntoSystem, System, World,
+        component::{ComponentId, ResourceId},
+        query::access::Access,
+    },
+    tasks::{ComputeTaskPool, TaskPool},
+};
+
+use super::Schedule;
+
+pub type BoxedSystem = Box<dyn System<In = (), Out = ()>>;
+
+// Splits systems into batches of mutually compatible systems. A system is placed into the batch
+// following the last batch containing a system it conflicts with, so conflicting systems keep
+// their insertion order while compatible ones can run concurrently. Explicit dependencies (indices
+// of earlier systems, one list per system) are treated the same way as conflicts.
+#[derive(Default)]
+pub struct ParallelExecutor {
+    batches: Vec<Vec<usize>>,
+}
+
+impl ParallelExecutor {
+    pub fn rebuild(&mut self, systems: &[BoxedSystem], dependencies: &[Vec<usize>]) {
+        self.batches.clear();
+
+        let mut system_batches: Vec<usize> = Vec::with_capacity(systems.len());
+        for (index, system) in systems.iter().enumerate() {
+            let conflicts = systems[..index]
+                .iter()
+                .zip(system_batches.iter())
+                .filter(|(other, _)| !is_compatible(system.as_ref(), other.as_ref()))
+                .map(|(_, batch)| batch + 1);
+            let dependencies = dependencies
+                .get(index)
+                .into_iter()
+                .flatten()
+                .map(|&dependency| system_batches[dependency] + 1);
+            let batch = conflicts.chain(dependencies).max().unwrap_or(0);
+
+            if batch == self.batches.len() {
+                self.batches.push(Vec::new());
+            }
+            self.batches[batch].push(index);
+            system_batches.push(batch);
+        }
+    }
+
+    #[inline]
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// # Safety
+    /// The systems must be initialized and `rebuild` must have been called with the same systems.
+    /// If given, `run_times` must have one entry per system and receives how long each system ran.
+    pub unsafe fn run(
+        &self,
+        systems: &mut [BoxedSystem],
+        world: &mut World,
+        task_pool: Option<&TaskPool>,
+        mut run_times: Option<&mut [Duration]>,
+    ) {
+        let Some(task_pool) = task_pool else {
+            for (index, system) in systems.iter_mut().enumerate() {
+                let run_time = run_times
+                    .as_deref_mut()
+                    .map(|run_times| &mut run_times[index]);
+                if system.is_exclusive() {
+                    run_exclusive_system(system, world, run_time);
+                } else {
+                    run_system(system, world, run_time);
+                }
+            }
+            return;
+        };
+
+        // An exclusive system conflicts with every other system, so it is alone in its batch
+        for batch in &self.batches {
+            match batch.as_slice() {
+                [index] if systems[*index].is_exclusive() => {
+                    let run_time = run_times
+                        .as_deref_mut()
+                        .map(|run_times| &mut run_times[*index]);
+                    run_exclusive_system(&mut systems[*index], world, run_time);
+                }
+                _ => self.run_batch(batch, systems, world, task_pool, run_times.as_deref_mut()),
+            }
+        }
+    }
+
+    /// # Safety
+    /// Same as `run`, none of the systems may be exclusive.
+    pub unsafe fn run_shared(
+        &self,
+        systems: &mut [BoxedSystem],
+        world: &World,
+        task_pool: Option<&TaskPool>,
+    ) {
+        let Some(task_pool) = task_pool else {
+            for system in systems.iter_mut() {
+                run_system(system, world, None);
+            }
+            return;
+        };
+
+        for batch in &self.batches {
+            self.run_batch(batch, systems, world, task_pool, None);
+        }
+    }
+
+    unsafe fn run_batch(
+        &self,
+        batch: &[usize],
+        systems: &mut [BoxedSystem],
+        world: &World,
+        task_pool: &TaskPool,
+        run_times: Option<&mut [Duration]>,
+    ) {
+        if let [index] = batch {
+            let run_time = run_times.map(|run_times| &mut run_times[*index]);
+            run_system(&mut systems[*index], world, run_time);
+            return;
+        }
+
+        let mut run_times = run_times.map(|run_times| run_times.iter_mut());
+        task_pool.scope(|scope| {
+            let mut batch_indices = batch.iter().peekable();
+            for (index, system) in systems.iter_mut().enumerate() {
+                let run_time = run_times
+                    .as_mut()
+                    .map(|run_times| run_times.next().unwrap());
+                if batch_indices.next_if_eq(&&index).is_some() {
+                    scope.spawn(async move { unsafe { run_system(system, world, run_time) } });
+                }
+            }
+        });
+    }
+}
+
+unsafe fn run_system(system: &mut BoxedSystem, world: &World, run_time: Option<&mut Duration>) {
+    match run_time {
+        Some(run_time) => {
+            let start = Instant::now();
+            system.run((), world);
+            *run_time = start.elapsed();
+        }
+        None => system.run((), world),
+    }
+}
+
+fn run_exclusive_system(
+    system: &mut BoxedSystem,
+    world: &mut World,
+    run_time: Option<&mut Duration>,
+) {
+    match run_time {
+        Some(run_time) => {
+            let start = Instant::now();
+            system.run_exclusive(world);
+            *run_time = start.elapsed();
+        }
+        None => system.run_exclusive(world),
+    }
+}
+
+fn is_compatible(
+    left: &dyn System<In = (), Out = ()>,
+    right: &dyn System<In = (), Out = ()>,
+) -> bool {
+    !left.is_exclusive()
+        && !right.is_exclusive()
+        && left
+            .resource_access()
+            .is_compatible(right.resource_access())
+        && left
+            .component_access()
+            .is_compatible(right.component_access())
+}
+
+pub struct ParallelSystem {
+    systems: Vec<BoxedSystem>,
+    executor: ParallelExecutor,
+    name: String,
+    resource_access: Access<ResourceId>,
+    component_access: Access<ComponentId>,
+}
+
+impl ParallelSystem {
+    pub fn new(systems: Vec<BoxedSystem>) -> Self {
+        let names = systems
+            .iter()
+            .map(|system| system.name())
+            .collect::<Vec<_>>();
+        let name = format!("Parallel: {}", names.join(", "));
+
+        Self {
+            systems,
+            executor: Default::default(),
+            name,
+            resource_access: Default::default(),
+            component_access: Default::default(),
+        }
+    }
+}
+
+impl System for ParallelSystem {
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn resource_access(&self) -> &Access<ResourceId> {
+        &self.resource_access
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.resource_access.clear();
+        self.component_access.clear();
+        for system in self.systems.iter_mut() {
+            assert!(
+                !system.is_exclusive(),
+                "Exclusive system {} cannot run in a parallel system",
+                system.name()
+            );
+            system.initialize(world);
+            self.resource_access.extend(system.resource_access());
+            self.component_access.extend(system.component_access());
+        }
+        self.executor.rebuild(&self.systems, &[]);
+    }
+
+    unsafe fn run(&mut self, _input: (), world: &World) {
+        let task_pool = world.get_resource::<ComputeTaskPool>();
+        self.executor.run_shared(
+            &mut self.systems,
+            world,
+            task_pool.as_deref().map(|pool| &pool.0),
+        );
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        for system in self.systems.iter_mut() {
+            system.apply_buffers(world);
+        }
+    }
+
+    fn pending_commands(&self) -> usize {
+        self.systems
+            .iter()
+            .map(|system| system.pending_commands())
+            .sum()
+    }
+}
+
+#[derive(Default)]
+pub struct ParallelBuilder {
+    systems: Vec<BoxedSystem>,
+}
+
+impl ParallelBuilder {
+    pub fn add<Param, S>(mut self, system: S) -> Self
+    where
+        S: IntoSystem<(), (), Param>,
+    {
+        self.systems.push(Box::new(system.system()));
+        self
+    }
+
+    pub fn system(self) -> ParallelSystem {
+        ParallelSystem::new(self.systems)
+    }
+
+    pub fn build(self) -> Schedule {
+        Schedule::new(self.system())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecs::{Res, ResMut, Resource, Scheduler, System, World},
+        tasks::{ComputeTaskPool, TaskPool, TaskPoolBuilder},
+        test_util::Log,
+    };
+
+    #[derive(Resource, Default)]
+    struct A(u32);
+
+    #[derive(Resource, Default)]
+    struct B(u32);
+
+    fn write_a(mut a: ResMut<A>, mut log: ResMut<Log>) {
+        a.0 += 1;
+        log.0.push("write_a");
+    }
+
+    fn read_a(a: Res<A>, mut b: ResMut<B>) {
+        b.0 += a.0;
+    }
+
+    fn read_b(b: Res<B>, mut log: ResMut<Log>) {
+        assert_eq!(b.0, 1);
+        log.0.push("read_b");
+    }
+
+    fn create_world(task_pool: TaskPool) -> World {
+        let mut world = World::new();
+        world.insert_resource(ComputeTaskPool(task_pool));
+        world.init_resource::<Log>();
+        world.init_resource::<A>();
+        world.init_resource::<B>();
+        world
+    }
+
+    #[test]
+    fn conflicting_systems_keep_order() {
+        let task_pool = TaskPoolBuilder::new().num_threads(4).build();
+        let mut world = create_world(task_pool);
+
+        Scheduler::parallel()
+            .add(write_a)
+            .add(read_a)
+            .add(read_b)
+            .build()
+            .run(&mut world);
+
+        assert_eq!(world.resource::<A>().0, 1);
+        assert_eq!(world.resource::<B>().0, 1);
+        assert_eq!(world.resource::<Log>().0, vec!["write_a", "read_b"]);
+    }
+
+    #[test]
+    fn compatible_systems_share_batch() {
+        fn read_only_a(_a: Res<A>) {}
+        fn read_only_b(_b: Res<B>) {}
+
+        let mut world = create_world(TaskPool::new());
+        let mut system = Scheduler::parallel()
+            .add(read_only_a)
+            .add(read_only_b)
+            .add(write_a)
+            .system();
+
+        system.initialize(&mut world);
+        assert_eq!(system.executor.batch_count(), 2);
+    }
+}
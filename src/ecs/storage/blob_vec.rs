@@ -55,6 +55,16 @@ impl BlobVec {
         self.capacity
     }
 
+    // Zero sized items never allocate, their capacity is unbounded
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        if self.item_layout.size() == 0 {
+            0
+        } else {
+            self.capacity * self.item_layout.size()
+        }
+    }
+
     pub fn reserve_exact(&mut self, additional: usize) {
         let available_space = self.capacity - self.len;
         if available_space < additional {
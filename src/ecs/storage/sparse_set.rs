@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use crate::ecs::{
+    Entity,
+    component::{ComponentId, ComponentInfo, ComponentTicks, Tick},
+};
+
+use super::Column;
+
+pub struct ComponentSparseSet {
+    dense: Column,
+    entities: Vec<Entity>,
+    sparse: Vec<Option<usize>>,
+}
+
+// SAFETY: The column only holds values of a single component type and components are Send + Sync.
+// The raw column memory is only written through &mut self or through query fetches, whose
+// conflicting accesses are never scheduled in parallel.
+unsafe impl Send for ComponentSparseSet {}
+unsafe impl Sync for ComponentSparseSet {}
+
+impl ComponentSparseSet {
+    pub fn new(component_info: &ComponentInfo, capacity: usize) -> Self {
+        Self {
+            dense: Column::new(component_info, capacity),
+            entities: Vec::with_capacity(capacity),
+            sparse: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    #[inline]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    #[inline]
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let index = self.sparse.get(entity.id() as usize).copied().flatten()?;
+        (self.entities[index] == entity).then_some(index)
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    // Returns true if the component was added, false if an existing value was replaced
+    pub unsafe fn insert(&mut self, entity: Entity, data: *mut u8, change_tick: Tick) -> bool {
+        if let Some(index) = self.dense_index(entity) {
+            self.dense.replace(index, data, change_tick);
+            return false;
+        }
+
+        let index = self.entities.len();
+        self.dense.push(data, ComponentTicks::new(change_tick));
+        self.entities.push(entity);
+
+        let sparse_index = entity.id() as usize;
+        if sparse_index >= self.sparse.len() {
+            self.sparse.resize(sparse_index + 1, None);
+        }
+        self.sparse[sparse_index] = Some(index);
+        true
+    }
+
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Option<*mut u8> {
+        self.dense_index(entity)
+            .map(|index| unsafe { self.dense.get_unchecked(index) })
+    }
+
+    #[inline]
+    pub fn get_with_ticks(&self, entity: Entity) -> Option<(*mut u8, *mut ComponentTicks)> {
+        self.dense_index(entity).map(|index| unsafe {
+            (
+                self.dense.get_unchecked(index),
+                self.dense.get_ticks_mut_ptr_unchecked(index),
+            )
+        })
+    }
+
+    // The returned pointer is only valid until the next modification of the set
+    pub fn remove_and_forget(&mut self, entity: Entity) -> Option<*mut u8> {
+        let index = self.dense_index(entity)?;
+        let (data, _ticks) = unsafe { self.dense.swap_remove_and_forget_unchecked(index) };
+        self.remove_entity(entity, index);
+        Some(data)
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> bool {
+        if let Some(index) = self.dense_index(entity) {
+            unsafe { self.dense.swap_remove_unchecked(index) };
+            self.remove_entity(entity, index);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_entity(&mut self, entity: Entity, index: usize) {
+        self.entities.swap_remove(index);
+        self.sparse[entity.id() as usize] = None;
+        if let Some(swapped_entity) = self.entities.get(index) {
+            self.sparse[swapped_entity.id() as usize] = Some(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { self.dense.clear() };
+        self.entities.clear();
+        self.sparse.clear();
+    }
+}
+
+#[derive(Default)]
+pub struct SparseSets {
+    sets: HashMap<ComponentId, ComponentSparseSet>,
+}
+
+impl SparseSets {
+    #[inline]
+    pub fn get(&self, component_id: ComponentId) -> Option<&ComponentSparseSet> {
+        self.sets.get(&component_id)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, component_id: ComponentId) -> Option<&mut ComponentSparseSet> {
+        self.sets.get_mut(&component_id)
+    }
+
+    pub fn get_or_insert(&mut self, component_info: &ComponentInfo) -> &mut ComponentSparseSet {
+        self.sets
+            .entry(component_info.id())
+            .or_insert_with(|| ComponentSparseSet::new(component_info, 64))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ComponentId, &ComponentSparseSet)> {
+        self.sets.iter().map(|(id, set)| (*id, set))
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ComponentId, &mut ComponentSparseSet)> {
+        self.sets.iter_mut().map(|(id, set)| (*id, set))
+    }
+
+    pub fn clear(&mut self) {
+        for set in self.sets.values_mut() {
+            set.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecs::{
+        Added, Changed, Component, Entity, RemovedComponents, Scheduler, With, Without, World,
+    };
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct Marker(u32);
+
+    #[test]
+    fn insert_and_remove_keep_table() {
+        let mut world = World::new();
+        let entity = world.spawn().insert(Position(1)).id();
+        let archetype_id = world.entity(entity).archetype().id();
+        let table_id = world.entity(entity).archetype().table_id();
+
+        world.entity_mut(entity).insert(Marker(5));
+        assert_ne!(world.entity(entity).archetype().id(), archetype_id);
+        assert_eq!(world.entity(entity).archetype().table_id(), table_id);
+        assert_eq!(world.entity(entity).get::<Marker>(), Some(&Marker(5)));
+
+        world.entity_mut(entity).insert(Marker(6));
+        assert_eq!(world.entity(entity).get::<Marker>(), Some(&Marker(6)));
+
+        assert_eq!(world.entity_mut(entity).remove::<Marker>(), Some(Marker(6)));
+        assert_eq!(world.entity(entity).archetype().id(), archetype_id);
+        assert!(!world.entity(entity).contains::<Marker>());
+        assert_eq!(world.entity_mut(entity).remove::<Marker>(), None);
+        assert_eq!(world.entity(entity).get::<Position>(), Some(&Position(1)));
+    }
+
+    #[test]
+    fn queries_and_filters() {
+        let mut world = World::new();
+        let a = world.spawn().insert_bundle((Position(1), Marker(1))).id();
+        let b = world.spawn().insert(Position(2)).id();
+
+        let mut query = world.query::<(Entity, &Marker)>();
+        assert_eq!(
+            query.iter(&world).collect::<Vec<_>>(),
+            vec![(a, &Marker(1))]
+        );
+        assert_eq!(query.iter(&world).count(), 1);
+
+        let mut with = world.query_filtered::<Entity, With<Marker>>();
+        assert_eq!(with.iter(&world).collect::<Vec<_>>(), vec![a]);
+        let mut without = world.query_filtered::<Entity, Without<Marker>>();
+        assert_eq!(without.iter(&world).collect::<Vec<_>>(), vec![b]);
+        assert_eq!(without.iter(&world).count(), 1);
+
+        let mut optional = world.query::<Option<&Marker>>();
+        assert_eq!(optional.iter(&world).filter(Option::is_some).count(), 1);
+
+        world.clear_trackers();
+        world.entity_mut(b).insert(Marker(2));
+        for mut marker in world.query::<&mut Marker>().iter_mut(&mut world) {
+            if marker.0 == 1 {
+                marker.0 = 10;
+            }
+        }
+
+        let mut added = world.query_filtered::<Entity, Added<Marker>>();
+        assert_eq!(added.iter(&world).collect::<Vec<_>>(), vec![b]);
+        let mut changed = world.query_filtered::<Entity, Changed<Marker>>();
+        assert_eq!(changed.iter(&world).count(), 2);
+    }
+
+    #[test]
+    fn archetypes_share_tables() {
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(1)).id();
+        let b = world.spawn().insert_bundle((Position(2), Marker(2))).id();
+        let c = world.spawn().insert(Position(3)).id();
+
+        let mut query = world.query::<&Marker>();
+        query.iter(&world).for_each(drop);
+        assert_eq!(query.matched_archetype_ids.len(), 1);
+
+        // c is moved into the table row of b, but stays in its archetype
+        world.despawn(b);
+        assert_eq!(world.get::<Position>(a), Some(&Position(1)));
+        assert_eq!(world.get::<Position>(c), Some(&Position(3)));
+        world.entity_mut(c).insert(Marker(3));
+        world.despawn(a);
+
+        let mut query = world.query::<(Entity, &Position, Option<&Marker>)>();
+        assert_eq!(
+            query.iter(&world).collect::<Vec<_>>(),
+            vec![(c, &Position(3), Some(&Marker(3)))]
+        );
+    }
+
+    #[test]
+    fn removed_components() {
+        let mut world = World::new();
+        let a = world.spawn().insert(Marker(1)).id();
+        let b = world.spawn().insert(Marker(2)).id();
+
+        world.entity_mut(a).remove::<Marker>();
+        world.despawn(b);
+        assert_eq!(world.removed::<Marker>().collect::<Vec<_>>(), vec![a, b]);
+
+        fn check_removed(removed: RemovedComponents<Marker>) {
+            assert_eq!(removed.iter().count(), 2);
+        }
+        Scheduler::single(check_removed).run(&mut world);
+    }
+}
@@ -34,6 +34,15 @@ impl Column {
         *self.ticks.get_unchecked_mut(row).get_mut() = ticks;
     }
 
+    #[inline]
+    pub unsafe fn push(&mut self, data: *mut u8, ticks: ComponentTicks) {
+        let row = self.data.len();
+        self.reserve_exact(1);
+        self.data.set_len(row + 1);
+        self.data.initialize_unchecked(row, data);
+        self.ticks.push(UnsafeCell::new(ticks));
+    }
+
     #[inline]
     pub unsafe fn replace(&mut self, row: usize, data: *mut u8, change_tick: Tick) {
         self.data.replace_unchecked(row, data);
@@ -53,6 +62,12 @@ impl Column {
         self.data.is_empty()
     }
 
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.data.allocated_bytes()
+            + self.ticks.capacity() * std::mem::size_of::<UnsafeCell<ComponentTicks>>()
+    }
+
     #[inline]
     pub unsafe fn swap_remove_unchecked(&mut self, row: usize) {
         self.data.swap_remove_and_drop_unchecked(row);
@@ -140,9 +155,15 @@ impl TableIdentity {
     }
 }
 
+pub struct TableMoveResult {
+    pub new_row: usize,
+    // The entity that was moved into the old row
+    pub swapped_entity: Option<Entity>,
+}
+
 pub struct Table {
     columns: HashMap<ComponentId, Column>,
-    entities: Vec<Entity>, // TODO: Not needed
+    entities: Vec<Entity>,
 }
 
 // TODO: Is this safe?
@@ -207,31 +228,38 @@ impl Table {
         index
     }
 
-    pub unsafe fn swap_remove_unchecked(&mut self, row: usize) {
-        self.entities.swap_remove(row);
+    // Returns the entity that was moved into the removed row
+    pub unsafe fn swap_remove_unchecked(&mut self, row: usize) -> Option<Entity> {
         for column in self.columns.values_mut() {
             column.swap_remove_unchecked(row);
         }
+        self.swap_remove_entity(row)
     }
 
     pub unsafe fn move_to_and_forget_missing_unchecked(
         &mut self,
         row: usize,
         new_table: &mut Table,
-    ) {
-        let entity = self.entities.swap_remove(row);
-        let new_row = new_table.allocate(entity);
+    ) -> TableMoveResult {
+        let new_row = new_table.allocate(self.entities[row]);
         for column in self.columns.values_mut() {
             let (data, ticks) = column.swap_remove_and_forget_unchecked(row);
             if let Some(new_column) = new_table.get_column_mut(column.component_id) {
                 new_column.initialize(new_row, data, ticks);
             }
         }
+        TableMoveResult {
+            new_row,
+            swapped_entity: self.swap_remove_entity(row),
+        }
     }
 
-    pub unsafe fn move_to_and_drop_missing_unchecked(&mut self, row: usize, new_table: &mut Table) {
-        let entity = self.entities.swap_remove(row);
-        let new_row = new_table.allocate(entity);
+    pub unsafe fn move_to_and_drop_missing_unchecked(
+        &mut self,
+        row: usize,
+        new_table: &mut Table,
+    ) -> TableMoveResult {
+        let new_row = new_table.allocate(self.entities[row]);
         for column in self.columns.values_mut() {
             if let Some(new_column) = new_table.get_column_mut(column.component_id) {
                 let (data, ticks) = column.swap_remove_and_forget_unchecked(row);
@@ -240,16 +268,32 @@ impl Table {
                 column.swap_remove_unchecked(row);
             }
         }
+        TableMoveResult {
+            new_row,
+            swapped_entity: self.swap_remove_entity(row),
+        }
     }
 
-    pub unsafe fn move_to_superset_unchecked(&mut self, row: usize, new_table: &mut Table) {
-        let entity = self.entities.swap_remove(row);
-        let new_row = new_table.allocate(entity);
+    pub unsafe fn move_to_superset_unchecked(
+        &mut self,
+        row: usize,
+        new_table: &mut Table,
+    ) -> TableMoveResult {
+        let new_row = new_table.allocate(self.entities[row]);
         for column in self.columns.values_mut() {
             let new_column = new_table.get_column_mut(column.component_id).unwrap();
             let (data, ticks) = column.swap_remove_and_forget_unchecked(row);
             new_column.initialize(new_row, data, ticks);
         }
+        TableMoveResult {
+            new_row,
+            swapped_entity: self.swap_remove_entity(row),
+        }
+    }
+
+    fn swap_remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
     }
 
     pub unsafe fn clear(&mut self) {
@@ -268,6 +312,19 @@ impl Table {
     pub fn is_empty(&self) -> bool {
         self.entities.is_empty()
     }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    pub fn allocated_bytes(&self) -> usize {
+        self.columns
+            .values()
+            .map(Column::allocated_bytes)
+            .sum::<usize>()
+            + self.entities.capacity() * std::mem::size_of::<Entity>()
+    }
 }
 
 pub struct Tables {
@@ -310,6 +367,24 @@ impl Tables {
         })
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (TableId, &Table)> {
+        self.tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| (TableId(index), table))
+    }
+
     #[inline]
     pub fn get_2_mut(&mut self, a: TableId, b: TableId) -> (&mut Table, &mut Table) {
         if a.index() > b.index() {
@@ -6,6 +6,7 @@ use super::{
 
 pub mod command;
 pub mod event;
+pub mod exclusive_system;
 pub mod function_system;
 pub mod local;
 pub mod query;
@@ -27,6 +28,20 @@ pub trait System: Send + Sync + 'static {
     unsafe fn run(&mut self, input: Self::In, world: &World) -> Self::Out;
 
     fn apply_buffers(&mut self, world: &mut World);
+
+    // Number of commands buffered by the system until apply_buffers is called
+    fn pending_commands(&self) -> usize {
+        0
+    }
+
+    // Exclusive systems conflict with every other system and are only run with run_exclusive
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn run_exclusive(&mut self, _world: &mut World) {
+        panic!("System {} is not exclusive", self.name());
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -4,6 +4,8 @@ use crate::ecs::{
     Entity, Resource, World,
     component::{Bundle, Component, Tick},
     entity::Entities,
+    observer::{BoxedObserver, ComponentEvent, Trigger},
+    relation::Relation,
 };
 
 use super::{
@@ -29,6 +31,16 @@ impl CommandQueue {
         self.commands.push(Box::new(command));
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
     #[inline]
     pub fn apply(&mut self, world: &mut World) {
         world.flush();
@@ -86,6 +98,28 @@ impl<'w, 's> Commands<'w, 's> {
         self.queue.push(InsertOrSpawnBatch { bundles_iter });
     }
 
+    // The clone is spawned when the commands are applied, see World::clone_entity
+    pub fn clone_entity(&mut self, entity: Entity) -> EntityCommands<'w, 's, '_> {
+        self.clone_entity_with(entity, false)
+    }
+
+    pub fn clone_entity_recursive(&mut self, entity: Entity) -> EntityCommands<'w, 's, '_> {
+        self.clone_entity_with(entity, true)
+    }
+
+    fn clone_entity_with(&mut self, source: Entity, recursive: bool) -> EntityCommands<'w, 's, '_> {
+        let target = self.entities.reserve_entity();
+        self.queue.push(CloneEntity {
+            source,
+            target,
+            recursive,
+        });
+        EntityCommands {
+            entity: target,
+            commands: self,
+        }
+    }
+
     pub fn entity(&mut self, entity: Entity) -> EntityCommands<'w, 's, '_> {
         EntityCommands {
             entity,
@@ -109,7 +143,7 @@ impl<'w, 's> Commands<'w, 's> {
         });
     }
 
-    fn add<C: Command>(&mut self, command: C) {
+    pub fn add<C: Command>(&mut self, command: C) {
         self.queue.push(command);
     }
 }
@@ -168,6 +202,23 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
         self
     }
 
+    pub fn observe<T>(
+        &mut self,
+        event: ComponentEvent,
+        observer: impl FnMut(&mut World, Trigger) + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        T: Component,
+    {
+        self.commands.add(Observe::<T> {
+            entity: self.entity,
+            event,
+            observer: Box::new(observer),
+            phantom: PhantomData,
+        });
+        self
+    }
+
     pub fn despawn(&mut self) {
         self.commands.add(Despawn {
             entity: self.entity,
@@ -252,6 +303,26 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
         self.commands().add(RemoveFromParent { child });
         self
     }
+
+    pub fn relate<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.commands().add(Relate::<R> {
+            source,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    pub fn unrelate<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.commands().add(Unrelate::<R> {
+            source,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
 }
 
 pub struct ChildBuilder<'w, 's, 'a> {
@@ -325,6 +396,29 @@ impl Command for Despawn {
     }
 }
 
+#[derive(Debug)]
+pub struct CloneEntity {
+    pub source: Entity,
+    pub target: Entity,
+    pub recursive: bool,
+}
+
+impl Command for CloneEntity {
+    fn write(self: Box<Self>, world: &mut World) {
+        // The source may have been despawned by an earlier command of the same queue
+        if world.get_entity(self.source).is_none() {
+            log::warn!(
+                "Cannot clone non-existent entity {:?}, despawning its clone {:?}",
+                self.source,
+                self.target
+            );
+            world.despawn(self.target);
+            return;
+        }
+        world.clone_entity_to(self.source, self.target, self.recursive);
+    }
+}
+
 #[derive(Debug)]
 pub struct DespawnRecursive {
     pub entity: Entity,
@@ -401,6 +495,24 @@ where
     }
 }
 
+pub struct Observe<T> {
+    pub entity: Entity,
+    pub event: ComponentEvent,
+    pub observer: BoxedObserver,
+    pub phantom: PhantomData<T>,
+}
+
+impl<T> Command for Observe<T>
+where
+    T: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.observe::<T>(self.event, self.observer);
+        }
+    }
+}
+
 pub struct InsertResource<T: Resource> {
     pub resource: T,
 }
@@ -505,6 +617,41 @@ impl Command for RemoveFromParent {
     }
 }
 
+pub struct Relate<R: Relation> {
+    source: Entity,
+    target: Entity,
+    phantom: PhantomData<fn() -> R>,
+}
+
+impl<R: Relation> Command for Relate<R> {
+    fn write(self: Box<Self>, world: &mut World) {
+        // Either entity may have been despawned by an earlier command of the same queue
+        if !world.has_entity(self.source) || !world.has_entity(self.target) {
+            log::warn!(
+                "Cannot relate {:?} to {:?}, the entity does not exist",
+                self.source,
+                self.target
+            );
+            return;
+        }
+        world.entity_mut(self.source).relate::<R>(self.target);
+    }
+}
+
+pub struct Unrelate<R: Relation> {
+    source: Entity,
+    target: Entity,
+    phantom: PhantomData<fn() -> R>,
+}
+
+impl<R: Relation> Command for Unrelate<R> {
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.source) {
+            entity_mut.unrelate::<R>(self.target);
+        }
+    }
+}
+
 unsafe impl ReadOnlySystemParamFetch for CommandQueue {}
 
 impl<'w, 's> SystemParam for Commands<'w, 's> {
@@ -519,6 +666,10 @@ unsafe impl SystemParamState for CommandQueue {
     fn apply(&mut self, world: &mut World) {
         self.apply(world);
     }
+
+    fn pending_commands(&self) -> usize {
+        self.len()
+    }
 }
 
 impl<'w, 's> SystemParamFetch<'w, 's> for CommandQueue {
@@ -1,4 +1,8 @@
-use std::{any::type_name, marker::PhantomData};
+use std::{
+    any::type_name,
+    marker::PhantomData,
+    sync::{Arc, atomic::AtomicUsize},
+};
 
 use crate::ecs::{
     Event, EventReader, EventWriter, Events, ReadOnlySystemParamFetch, World,
@@ -12,7 +16,8 @@ use super::{
 
 pub struct EventReaderState<T: Event> {
     resource_id: ResourceId,
-    last_event_count: usize,
+    last_event_count: Arc<AtomicUsize>,
+    registered: bool,
     marker: PhantomData<T>,
 }
 
@@ -35,7 +40,8 @@ unsafe impl<T: Event> SystemParamState for EventReaderState<T> {
 
         Self {
             resource_id,
-            last_event_count: 0,
+            last_event_count: Arc::new(AtomicUsize::new(0)),
+            registered: false,
             marker: PhantomData,
         }
     }
@@ -62,7 +68,15 @@ impl<'w, 's, T: Event> SystemParamFetch<'w, 's> for EventReaderState<T> {
                 )
             });
 
-        EventReader::new(&*resource, &mut state.last_event_count)
+        let events: &Events<T> = &*resource;
+
+        // The channel keeps track of its readers for the UntilRead retention
+        if !state.registered {
+            events.register_reader(&state.last_event_count);
+            state.registered = true;
+        }
+
+        EventReader::new(events, &state.last_event_count)
     }
 }
 
@@ -0,0 +1,80 @@
+use std::any::type_name;
+
+use crate::ecs::{
+    World,
+    component::{ComponentId, ResourceId},
+    query::access::Access,
+};
+
+use super::{IntoSystem, System};
+
+// System taking the whole world mutably, e.g. `fn render(world: &mut World)`. It runs alone, after
+// the systems ordered before it and before those ordered after it.
+pub struct ExclusiveFunctionSystem<F> {
+    func: F,
+    name: String,
+    resource_access: Access<ResourceId>,
+    component_access: Access<ComponentId>,
+}
+
+pub struct ExclusiveMarker;
+
+impl<F> IntoSystem<(), (), ExclusiveMarker> for F
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    type System = ExclusiveFunctionSystem<F>;
+
+    fn system(self) -> Self::System {
+        ExclusiveFunctionSystem {
+            func: self,
+            name: type_name::<F>().to_owned(),
+            resource_access: Default::default(),
+            component_access: Default::default(),
+        }
+    }
+}
+
+impl<F> System for ExclusiveFunctionSystem<F>
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    fn resource_access(&self) -> &Access<ResourceId> {
+        &self.resource_access
+    }
+
+    #[inline]
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn initialize(&mut self, _world: &mut World) {}
+
+    unsafe fn run(&mut self, _input: (), _world: &World) {
+        panic!(
+            "Exclusive system {} cannot run on a shared world",
+            self.name
+        );
+    }
+
+    fn apply_buffers(&mut self, _world: &mut World) {}
+
+    #[inline]
+    fn is_exclusive(&self) -> bool {
+        true
+    }
+
+    fn run_exclusive(&mut self, world: &mut World) {
+        world.flush();
+        (self.func)(world);
+    }
+}
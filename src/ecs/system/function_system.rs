@@ -50,6 +50,22 @@ where
     marker: PhantomData<fn() -> (In, Out, Marker)>,
 }
 
+// Only the function is cloned, the copy has to be initialized again before it is run
+impl<In, Out, Param, Marker, F> Clone for FunctionSystem<In, Out, Param, Marker, F>
+where
+    Param: SystemParam,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            func: self.func.clone(),
+            param_state: None,
+            system_meta: SystemMeta::new(self.system_meta.name.clone()),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<In, Out, Param, Marker, F> IntoSystem<In, Out, (Param, Marker)> for F
 where
     In: 'static,
@@ -122,6 +138,13 @@ where
         let param_state = self.param_state.as_mut().unwrap();
         param_state.apply(world);
     }
+
+    #[inline]
+    fn pending_commands(&self) -> usize {
+        self.param_state
+            .as_ref()
+            .map_or(0, |param_state| param_state.pending_commands())
+    }
 }
 
 pub trait SystemParamFunction<In, Out, Param: SystemParam, Marker>: Send + Sync + 'static {
@@ -1,14 +1,17 @@
 use std::any::TypeId;
 
-use crate::ecs::{
-    Entity, World,
-    component::{CmptMut, Component, Tick},
-    query::{
-        fetch::{ROQueryItem, ReadOnlyWorldQuery, WorldQuery},
-        iter::QueryIter,
-        state::{QueryEntityError, QuerySingleError, QueryState},
+use crate::{
+    ecs::{
+        Entity, World,
+        component::{CmptMut, Component, Tick},
+        query::{
+            fetch::{ROQueryItem, ReadOnlyWorldQuery, WorldQuery},
+            iter::{QueryCombinationIter, QueryIter},
+            state::{QueryEntityError, QuerySingleError, QueryState},
+        },
+        system::function_system::SystemMeta,
     },
-    system::function_system::SystemMeta,
+    tasks::TaskPool,
 };
 
 use super::{
@@ -70,6 +73,27 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> Query<'w, 's, Q, F> {
             .iter_unchecked_manual(self.world, self.system_ticks)
     }
 
+    #[inline]
+    pub fn iter_combinations<const K: usize>(
+        &self,
+    ) -> QueryCombinationIter<'_, 's, Q::ReadOnly, F::ReadOnly, K> {
+        unsafe {
+            self.state
+                .as_readonly()
+                .iter_combinations_unchecked_manual(self.world, self.system_ticks)
+        }
+    }
+
+    #[inline]
+    pub fn iter_combinations_mut<const K: usize>(
+        &mut self,
+    ) -> QueryCombinationIter<'_, 's, Q, F, K> {
+        unsafe {
+            self.state
+                .iter_combinations_unchecked_manual(self.world, self.system_ticks)
+        }
+    }
+
     #[inline]
     pub fn for_each<'a>(&'a self, f: impl FnMut(ROQueryItem<'a, Q>)) {
         unsafe {
@@ -87,6 +111,42 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> Query<'w, 's, Q, F> {
         };
     }
 
+    #[inline]
+    pub fn par_for_each<'a>(
+        &'a self,
+        task_pool: &TaskPool,
+        batch_size: usize,
+        f: impl Fn(ROQueryItem<'a, Q>) + Send + Sync,
+    ) {
+        unsafe {
+            self.state.as_readonly().par_for_each_unchecked_manual(
+                self.world,
+                task_pool,
+                batch_size,
+                f,
+                self.system_ticks,
+            )
+        };
+    }
+
+    #[inline]
+    pub fn par_for_each_mut<'a>(
+        &'a mut self,
+        task_pool: &TaskPool,
+        batch_size: usize,
+        f: impl Fn(Q::Item<'a>) + Send + Sync,
+    ) {
+        unsafe {
+            self.state.par_for_each_unchecked_manual(
+                self.world,
+                task_pool,
+                batch_size,
+                f,
+                self.system_ticks,
+            )
+        };
+    }
+
     #[inline]
     pub fn get(&self, entity: Entity) -> Result<ROQueryItem<'_, Q>, QueryEntityError> {
         unsafe {
@@ -124,7 +184,7 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> Query<'w, 's, Q, F> {
             .ok_or(QueryComponentError::MissingComponent)?;
         if self.state.component_access.has_read(component_id) {
             world
-                .get_component::<T>(location)
+                .get_component::<T>(entity, location)
                 .ok_or(QueryComponentError::MissingComponent)
         } else {
             Err(QueryComponentError::MissingReadAccess)
@@ -148,7 +208,7 @@ impl<'w, 's, Q: WorldQuery, F: ReadOnlyWorldQuery> Query<'w, 's, Q, F> {
         if self.state.component_access.has_write(component_id) {
             unsafe {
                 world
-                    .get_component_unchecked_mut::<T>(location)
+                    .get_component_unchecked_mut::<T>(entity, location)
                     .map(|(data, ticks)| {
                         CmptMut::new(&mut *data.cast::<T>(), &mut *ticks, self.system_ticks)
                     })
@@ -267,3 +327,111 @@ impl<'w, 's, Q: WorldQuery + 'static, F: ReadOnlyWorldQuery + 'static> SystemPar
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::{
+        ecs::{Changed, Component, Entity, Query, Res, Scheduler, With, World},
+        tasks::{ComputeTaskPool, TaskPool, TaskPoolBuilder},
+    };
+
+    #[derive(Component)]
+    struct Velocity(u32);
+
+    #[derive(Component)]
+    struct Position(u32);
+
+    fn move_positions(mut query: Query<(&Velocity, &mut Position)>, pool: Res<ComputeTaskPool>) {
+        query.par_for_each_mut(&pool, 64, |(velocity, mut position)| {
+            position.0 += velocity.0;
+        });
+    }
+
+    #[test]
+    fn par_for_each_mut_visits_every_entity() {
+        let mut world = World::new();
+        world.insert_resource(ComputeTaskPool(
+            TaskPoolBuilder::new().num_threads(4).build(),
+        ));
+        for index in 0..1000 {
+            world.spawn().insert_bundle((Velocity(index), Position(0)));
+        }
+        world.spawn().insert(Position(0));
+        world.clear_trackers();
+
+        Scheduler::single(move_positions).run(&mut world);
+
+        let mut changed = world.query_filtered::<Entity, Changed<Position>>();
+        assert_eq!(changed.iter(&world).count(), 1000);
+
+        let sum = AtomicU32::new(0);
+        let mut positions = world.query::<&Position>();
+        positions.par_for_each(&world, &TaskPool::new(), 7, |position| {
+            sum.fetch_add(position.0, Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), (0..1000).sum::<u32>());
+    }
+
+    fn count_contacts(mut query: Query<&mut Position, With<Velocity>>) {
+        let mut pairs = query.iter_combinations_mut();
+        while let Some([mut a, mut b]) = pairs.fetch_next() {
+            a.0 += 1;
+            b.0 += 1;
+        }
+    }
+
+    #[test]
+    fn iter_combinations_yields_unique_sets() {
+        let mut world = World::new();
+        let entities = (0..5)
+            .map(|index| {
+                world
+                    .spawn()
+                    .insert_bundle((Velocity(index), Position(0)))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        world.spawn().insert(Position(0));
+
+        let mut query = world.query_filtered::<Entity, With<Velocity>>();
+        let pairs = query.iter_combinations::<2>(&world).collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 10);
+        assert!(
+            pairs
+                .iter()
+                .all(|[a, b]| entities.contains(a) && a.id() < b.id())
+        );
+        assert_eq!(query.iter_combinations::<3>(&world).count(), 10);
+        assert_eq!(query.iter_combinations::<6>(&world).count(), 0);
+
+        Scheduler::single(count_contacts).run(&mut world);
+        let mut positions = world.query_filtered::<&Position, With<Velocity>>();
+        assert!(positions.iter(&world).all(|position| position.0 == 4));
+    }
+
+    #[test]
+    fn iter_combinations_across_archetypes() {
+        let mut world = World::new();
+        world.spawn().insert(Position(0));
+        world.spawn().insert(Velocity(0));
+        world.spawn().insert_bundle((Position(1), Velocity(1)));
+        world.spawn().insert(Position(2));
+        world.spawn().insert_bundle((Position(3), Velocity(3)));
+        world.spawn().insert(Position(4));
+
+        let mut query = world.query::<&Position>();
+        let mut pairs = query
+            .iter_combinations::<2>(&world)
+            .map(|[a, b]| (a.0.min(b.0), a.0.max(b.0)))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+        assert_eq!(pairs.len(), 10);
+        assert!(pairs.iter().all(|(a, b)| a < b));
+        assert_eq!(query.iter_combinations::<3>(&world).count(), 10);
+        assert_eq!(query.iter_combinations::<5>(&world).count(), 1);
+        assert_eq!(query.iter_combinations::<0>(&world).count(), 0);
+    }
+}
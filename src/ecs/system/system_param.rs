@@ -21,6 +21,12 @@ pub unsafe trait SystemParamState: Send + Sync + 'static {
 
     #[inline]
     fn apply(&mut self, _world: &mut World) {}
+
+    // Number of commands buffered until apply is called
+    #[inline]
+    fn pending_commands(&self) -> usize {
+        0
+    }
 }
 
 pub trait SystemParamFetch<'w, 's>: SystemParamState {
@@ -163,6 +169,10 @@ unsafe impl<S: SystemParamState, P: SystemParam + 'static> SystemParamState
     fn apply(&mut self, world: &mut World) {
         self.0.apply(world)
     }
+
+    fn pending_commands(&self) -> usize {
+        self.0.pending_commands()
+    }
 }
 
 pub struct ParamSet<'w, 's, T: SystemParam> {
@@ -219,6 +229,12 @@ macro_rules! impl_system_param_tuple {
                 let ($($param,)*) = self;
                 $($param.apply(_world);)*
             }
+
+            #[inline]
+            fn pending_commands(&self) -> usize {
+                let ($($param,)*) = self;
+                0 $(+ $param.pending_commands())*
+            }
         }
     };
 }
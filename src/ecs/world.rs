@@ -1,3 +1,4 @@
+mod clone;
 mod entity_ref;
 
 use std::{
@@ -6,18 +7,21 @@ use std::{
     sync::atomic::{AtomicU32, Ordering},
 };
 
+pub(crate) use self::clone::CloneFn;
 use self::entity_ref::{EntityMut, EntityRef};
 
 use super::{
     Res,
     component::{
-        Bundle, Bundles, Component, ComponentId, ComponentTicks, Components, ResMut, Resource,
-        ResourceId, Resources, Tick,
+        Bundle, Bundles, CmptMut, Component, ComponentDescriptor, ComponentHooks, ComponentId,
+        ComponentInfo, ComponentTicks, Components, ResMut, Resource, ResourceId, Resources,
+        StorageType, Tick,
     },
     entity::{
         AllocAtWithoutReplacement, Archetype, ArchetypeId, Archetypes, Entities, Entity,
         EntityLocation,
     },
+    observer::{ComponentEvent, Observers, Trigger},
     query::{
         fetch::{ReadOnlyWorldQuery, WorldQuery},
         state::QueryState,
@@ -34,6 +38,7 @@ pub struct World {
     storages: Storages,
     bundles: Bundles,
     removed_components: HashMap<ComponentId, Vec<Entity>>,
+    observers: Observers,
     last_change_tick: Tick,
     change_tick: AtomicU32,
 }
@@ -48,6 +53,7 @@ impl Default for World {
             storages: Default::default(),
             bundles: Default::default(),
             removed_components: Default::default(),
+            observers: Default::default(),
             last_change_tick: Default::default(),
             change_tick: AtomicU32::new(1), // So that changes are detected for first system run
         }
@@ -76,6 +82,11 @@ impl World {
         &mut self.entities
     }
 
+    #[inline]
+    pub(crate) fn observers_mut(&mut self) -> &mut Observers {
+        &mut self.observers
+    }
+
     #[inline]
     pub(crate) fn archetypes(&self) -> &Archetypes {
         &self.archetypes
@@ -126,6 +137,23 @@ impl World {
         self.components.get_or_insert::<T>()
     }
 
+    pub fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        let name = descriptor.name().to_owned();
+        self.components
+            .add_dynamic(descriptor)
+            .unwrap_or_else(|_| panic!("Component {} already registered", name))
+    }
+
+    #[inline]
+    pub fn dynamic_component_id(&self, name: &str) -> Option<ComponentId> {
+        self.components.get_dynamic_id(name)
+    }
+
+    #[inline]
+    pub fn component_info(&self, component_id: ComponentId) -> Option<&ComponentInfo> {
+        self.components.get_info(component_id)
+    }
+
     #[inline]
     pub fn init_resource<T: Resource + FromWorld>(&mut self) -> Option<T> {
         let resource = T::from_world(self);
@@ -188,10 +216,14 @@ impl World {
     }
 
     #[inline]
-    pub(crate) fn get_component<T: Component>(&self, location: EntityLocation) -> Option<&T> {
+    pub(crate) fn get_component<T: Component>(
+        &self,
+        entity: Entity,
+        location: EntityLocation,
+    ) -> Option<&T> {
         unsafe {
             // TODO: No need to get ticks here
-            get_component(self, TypeId::of::<T>(), location)
+            get_component(self, TypeId::of::<T>(), entity, location)
                 .map(|(data, _ticks)| &*data.cast::<T>())
         }
     }
@@ -199,9 +231,74 @@ impl World {
     #[inline]
     pub(crate) unsafe fn get_component_unchecked_mut<T: Component>(
         &self,
+        entity: Entity,
         location: EntityLocation,
     ) -> Option<(*mut u8, *mut ComponentTicks)> {
-        get_component(self, TypeId::of::<T>(), location)
+        get_component(self, TypeId::of::<T>(), entity, location)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_component_by_id(
+        &self,
+        component_id: ComponentId,
+        entity: Entity,
+        location: EntityLocation,
+    ) -> Option<(*mut u8, *mut ComponentTicks)> {
+        self.components.get_info(component_id)?;
+        get_component_with_id(self, component_id, entity, location)
+    }
+
+    #[inline]
+    pub(crate) fn has_component(
+        &self,
+        location: EntityLocation,
+        component_id: ComponentId,
+    ) -> bool {
+        self.archetypes[location.archetype_id].contains(component_id)
+    }
+
+    pub fn register_component_hooks<T: Component>(&mut self) -> &mut ComponentHooks {
+        let component_id = self.register_component::<T>();
+        self.components.hooks_mut(component_id)
+    }
+
+    // Runs for the given event of component T on any entity
+    pub fn observe<T: Component>(
+        &mut self,
+        event: ComponentEvent,
+        observer: impl FnMut(&mut World, Trigger) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let component_id = self.register_component::<T>();
+        self.observers
+            .add(None, event, component_id, Box::new(observer));
+        self
+    }
+
+    #[inline]
+    pub(crate) fn has_component_listeners(&self) -> bool {
+        self.components.has_hooks() || !self.observers.is_empty()
+    }
+
+    pub(crate) fn trigger(
+        &mut self,
+        event: ComponentEvent,
+        entity: Entity,
+        component_ids: &[ComponentId],
+    ) {
+        for &component_id in component_ids {
+            let info = unsafe { self.components.get_info_unchecked(component_id) };
+            if let Some(hook) = info.hooks().get(event) {
+                hook(self, entity, component_id);
+            }
+            if !self.observers.is_empty() {
+                let trigger = Trigger {
+                    event,
+                    entity,
+                    component_id,
+                };
+                Observers::run(self, trigger);
+            }
+        }
     }
 
     pub fn spawn(&mut self) -> EntityMut {
@@ -211,11 +308,9 @@ impl World {
 
     unsafe fn spawn_at_internal(&mut self, entity: Entity) -> EntityMut {
         let archetype = self.archetypes.empty_mut();
-        let location = archetype.next_location();
-        self.entities.update_location(entity, location);
         let table = &mut self.storages.tables[archetype.table_id()];
-        archetype.allocate(entity);
-        table.allocate(entity);
+        let location = archetype.allocate(entity, table.allocate(entity));
+        self.entities.update_location(entity, location);
         EntityMut::new(self, entity, location)
     }
 
@@ -251,6 +346,8 @@ impl World {
                 self.storages.tables[archetype.table_id()].clear();
             }
         }
+        self.storages.sparse_sets.clear();
+        self.observers.clear_entities();
     }
 
     pub(crate) fn clear_entities(&mut self) {
@@ -261,6 +358,8 @@ impl World {
                 self.storages.tables[archetype.table_id()].clear();
             }
         }
+        self.storages.sparse_sets.clear();
+        self.observers.clear_entities();
     }
 
     #[inline]
@@ -290,6 +389,24 @@ impl World {
         self.entities.has(entity)
     }
 
+    #[inline]
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        let location = self.entities.get(entity)?;
+        self.get_component(entity, location)
+    }
+
+    #[inline]
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<CmptMut<'_, T>> {
+        let location = self.entities.get(entity)?;
+        let system_ticks = self.ticks();
+        unsafe {
+            self.get_component_unchecked_mut::<T>(entity, location)
+                .map(|(data, ticks)| {
+                    CmptMut::new(&mut *data.cast::<T>(), &mut *ticks, system_ticks)
+                })
+        }
+    }
+
     #[inline]
     pub(crate) fn get_or_spawn(&mut self, entity: Entity) -> Option<EntityMut> {
         self.flush();
@@ -369,12 +486,8 @@ impl World {
         let archetype = self.archetypes.empty_mut();
         let table = &mut self.storages.tables[archetype.table_id()];
         unsafe {
-            self.entities.flush(|entity| {
-                let location = archetype.next_location();
-                archetype.allocate(entity);
-                table.allocate(entity);
-                location
-            });
+            self.entities
+                .flush(|entity| archetype.allocate(entity, table.allocate(entity)));
         }
     }
 }
@@ -392,14 +505,37 @@ impl<T: Default> FromWorld for T {
 unsafe fn get_component(
     world: &World,
     type_id: TypeId,
+    entity: Entity,
     location: EntityLocation,
 ) -> Option<(*mut u8, *mut ComponentTicks)> {
     let component_id = world.components.get_id(type_id)?;
+    get_component_with_id(world, component_id, entity, location)
+}
+
+unsafe fn get_component_with_id(
+    world: &World,
+    component_id: ComponentId,
+    entity: Entity,
+    location: EntityLocation,
+) -> Option<(*mut u8, *mut ComponentTicks)> {
+    if world
+        .components
+        .get_info_unchecked(component_id)
+        .storage_type()
+        == StorageType::SparseSet
+    {
+        return world
+            .storages
+            .sparse_sets
+            .get(component_id)?
+            .get_with_ticks(entity);
+    }
+
     let archetype = &world.archetypes[location.archetype_id];
     let table = &world.storages.tables[archetype.table_id()];
     let column = table.get_column(component_id)?;
-    let data = column.get_unchecked(location.index);
-    let ticks = column.get_ticks_mut_ptr_unchecked(location.index);
+    let data = column.get_unchecked(location.table_row);
+    let ticks = column.get_ticks_mut_ptr_unchecked(location.table_row);
     Some((data, ticks))
 }
 
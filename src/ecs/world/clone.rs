@@ -0,0 +1,267 @@
+use std::any::TypeId;
+
+use crate::{
+    ecs::{Component, Entity, EntityMap, MapEntities, MapEntitiesError, World},
+    transform::{Children, Parent},
+};
+
+// Clones the component of an entity, the returned closure inserts the copy into an entity of any
+// world and remaps the entity references it holds. The flag tells whether the copy is written to
+// the world it was cloned from.
+pub(crate) type CloneFn = fn(&World, Entity) -> Option<ClonedComponent>;
+
+pub(crate) type ClonedComponent =
+    Box<dyn FnOnce(&mut World, Entity, &mut EntityMap, bool) + Send + Sync>;
+
+struct ClonedEntity {
+    entity: Entity,
+    parent: Option<Entity>,
+    components: Vec<ClonedComponent>,
+}
+
+// Copies of an entity and its descendants, which can be written to any world
+pub(crate) struct EntityClones(Vec<ClonedEntity>);
+
+impl World {
+    // Only cloneable components are copied by clone_entity
+    pub fn register_cloneable<T: Component + Clone>(&mut self) -> &mut Self {
+        let component_id = self.register_component::<T>();
+        self.components.set_cloner(component_id, |world, entity| {
+            let value = world.get::<T>(entity)?.clone();
+            Some(Box::new(move |world, entity, _, _| {
+                world.register_cloneable::<T>();
+                world.entity_mut(entity).insert(value);
+            }))
+        });
+        self
+    }
+
+    // References to the cloned entities are remapped to the clones. Other references are kept when
+    // cloning within a world, in another world they would point to unrelated entities and the
+    // component is not cloned.
+    pub fn register_cloneable_mapped<T: Component + Clone + MapEntities>(&mut self) -> &mut Self {
+        let component_id = self.register_component::<T>();
+        self.components.set_cloner(component_id, |world, entity| {
+            let value = world.get::<T>(entity)?.clone();
+            Some(Box::new(move |world, entity, entity_map, same_world| {
+                let mut mapped = value.clone();
+                while let Err(MapEntitiesError::EntityNotFound(external)) =
+                    mapped.map_entities(entity_map)
+                {
+                    if !same_world {
+                        return;
+                    }
+                    entity_map.insert(external, external);
+                    mapped = value.clone();
+                }
+                world.register_cloneable_mapped::<T>();
+                world.entity_mut(entity).insert(mapped);
+            }))
+        });
+        self
+    }
+
+    // The clone is added to the parent of the entity, children are not cloned
+    pub fn clone_entity(&mut self, entity: Entity) -> Entity {
+        let clone = self.spawn().id();
+        self.clone_entity_to(entity, clone, false);
+        clone
+    }
+
+    // Clones the entity together with its Children subtree
+    pub fn clone_entity_recursive(&mut self, entity: Entity) -> Entity {
+        let clone = self.spawn().id();
+        self.clone_entity_to(entity, clone, true);
+        clone
+    }
+
+    pub(crate) fn clone_entity_to(&mut self, entity: Entity, target: Entity, recursive: bool) {
+        let clones = self.collect_clones(entity, recursive);
+        self.write_clones(clones, target, true);
+        if let Some(parent) = self.entity(entity).parent() {
+            self.entity_mut(parent).push_child(target);
+        }
+    }
+
+    // Parent and Children are never cloned, the hierarchy of the clones is rebuilt instead
+    pub(crate) fn collect_clones(&self, entity: Entity, recursive: bool) -> EntityClones {
+        assert!(
+            self.has_entity(entity),
+            "Cannot clone non-existent entity {:?}",
+            entity
+        );
+        let mut clones = Vec::new();
+        self.collect_entity_clones(entity, None, recursive, &mut clones);
+        EntityClones(clones)
+    }
+
+    fn collect_entity_clones(
+        &self,
+        entity: Entity,
+        parent: Option<Entity>,
+        recursive: bool,
+        clones: &mut Vec<ClonedEntity>,
+    ) {
+        let hierarchy = [TypeId::of::<Parent>(), TypeId::of::<Children>()];
+        let location = self.entities.get(entity).unwrap();
+        let component_ids = self.archetypes[location.archetype_id].components().to_vec();
+
+        let components = component_ids
+            .into_iter()
+            .filter_map(|component_id| {
+                let info = self.components.get_info(component_id)?;
+                if info
+                    .type_id()
+                    .is_some_and(|type_id| hierarchy.contains(&type_id))
+                {
+                    return None;
+                }
+                info.cloner()?(self, entity)
+            })
+            .collect();
+        clones.push(ClonedEntity {
+            entity,
+            parent,
+            components,
+        });
+
+        if recursive {
+            for &child in self.entity(entity).children().unwrap_or_default() {
+                self.collect_entity_clones(child, Some(entity), true, clones);
+            }
+        }
+    }
+
+    // The root is written to target, its descendants are spawned
+    pub(crate) fn write_clones(&mut self, clones: EntityClones, target: Entity, same_world: bool) {
+        let mut entity_map = EntityMap::default();
+        for (index, clone) in clones.0.iter().enumerate() {
+            let spawned = if index == 0 {
+                target
+            } else {
+                self.spawn().id()
+            };
+            entity_map.insert(clone.entity, spawned);
+        }
+
+        for clone in clones.0 {
+            let spawned = entity_map.get(clone.entity).unwrap();
+            for component in clone.components {
+                component(self, spawned, &mut entity_map, same_world);
+            }
+            if let Some(parent) = clone.parent {
+                let parent = entity_map.get(parent).unwrap();
+                self.entity_mut(parent).push_child(spawned);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecs::{
+            Component, Entity, EntityMap, MapEntities, MapEntitiesError, World,
+            system::command::{CommandQueue, Commands},
+        },
+        transform::Parent,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Component)]
+    struct NotCloneable;
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Target(Entity);
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.0 = entity_map.get(self.0)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn clone_entity() {
+        let mut world = World::new();
+        world.register_cloneable::<Health>();
+        let parent = world.spawn().id();
+        let entity = world.spawn().insert_bundle((Health(3), NotCloneable)).id();
+        world.entity_mut(parent).push_child(entity);
+
+        let clone = world.clone_entity(entity);
+        assert_ne!(clone, entity);
+        assert_eq!(world.get::<Health>(clone), Some(&Health(3)));
+        assert!(!world.entity(clone).contains::<NotCloneable>());
+        assert_eq!(world.entity(clone).parent(), Some(parent));
+        assert_eq!(world.entity(parent).children(), Some(&[entity, clone][..]));
+    }
+
+    #[test]
+    fn clone_entity_recursive() {
+        let mut world = World::new();
+        world
+            .register_cloneable::<Health>()
+            .register_cloneable_mapped::<Target>();
+        let outside = world.spawn().id();
+        let root = world.spawn().insert(Health(1)).id();
+        let child = world.spawn().insert_bundle((Health(2), Target(root))).id();
+        let grandchild = world.spawn().insert(Target(outside)).id();
+        world.entity_mut(root).push_child(child);
+        world.entity_mut(child).push_child(grandchild);
+
+        let clone = world.clone_entity_recursive(root);
+        assert_eq!(world.entity(clone).parent(), None);
+        let cloned_child = world.entity(clone).children().unwrap()[0];
+        assert_ne!(cloned_child, child);
+        assert_eq!(world.get::<Health>(cloned_child), Some(&Health(2)));
+        assert_eq!(world.get::<Target>(cloned_child), Some(&Target(clone)));
+        assert_eq!(
+            world.get::<Parent>(cloned_child).map(Parent::get),
+            Some(clone)
+        );
+
+        let cloned_grandchild = world.entity(cloned_child).children().unwrap()[0];
+        assert_eq!(
+            world.get::<Target>(cloned_grandchild),
+            Some(&Target(outside))
+        );
+        assert_eq!(world.entity(child).children(), Some(&[grandchild][..]));
+    }
+
+    #[test]
+    fn clone_to_other_world() {
+        let mut world = World::new();
+        world.register_cloneable_mapped::<Target>();
+        let outside = world.spawn().id();
+        let root = world.spawn().insert(Target(outside)).id();
+        let child = world.spawn().insert(Target(root)).id();
+        world.entity_mut(root).push_child(child);
+
+        let mut other = World::new();
+        other.spawn();
+        let target = other.spawn().id();
+        other.write_clones(world.collect_clones(root, true), target, false);
+        assert!(!other.entity(target).contains::<Target>());
+        let cloned_child = other.entity(target).children().unwrap()[0];
+        assert_eq!(other.get::<Target>(cloned_child), Some(&Target(target)));
+    }
+
+    #[test]
+    fn clone_command_skips_despawned_source() {
+        let mut world = World::new();
+        world.register_cloneable::<Health>();
+        let entity = world.spawn().insert(Health(1)).id();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(entity).despawn();
+        let clone = commands.clone_entity(entity).id();
+
+        queue.apply(&mut world);
+        assert!(!world.has_entity(entity));
+        assert!(!world.has_entity(clone));
+        assert_eq!(world.entities().len(), 0);
+    }
+}
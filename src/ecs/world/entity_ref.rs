@@ -3,9 +3,14 @@ use std::any::TypeId;
 use crate::{
     ecs::{
         Entity,
-        component::{Bundle, BundleInfo, CmptMut, Component, ComponentStatus, Components},
+        component::{
+            Bundle, BundleId, BundleInfo, CmptMut, Component, ComponentId, ComponentStatus,
+            Components, StorageType,
+        },
         entity::{Archetype, ArchetypeId, Archetypes, Entities, EntityLocation},
-        storage::Storages,
+        observer::{ComponentEvent, Trigger},
+        relation::{self, Related, RelatedBy, Relation},
+        storage::{SparseSets, Storages, Table},
         system::SystemTicks,
     },
     transform::{Children, Parent},
@@ -44,12 +49,22 @@ impl<'w> EntityRef<'w> {
         self.world
             .components()
             .get_id(TypeId::of::<T>())
-            .is_some_and(|id| self.archetype().contains(id))
+            .is_some_and(|id| self.world.has_component(self.location, id))
     }
 
     #[inline]
     pub fn get<T: Component>(&self) -> Option<&T> {
-        self.world.get_component(self.location)
+        self.world.get_component(self.entity, self.location)
+    }
+
+    // Pointer to the value of a component, e.g. one registered at runtime
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<*const u8> {
+        unsafe {
+            self.world
+                .get_component_by_id(component_id, self.entity, self.location)
+                .map(|(data, _ticks)| data as *const u8)
+        }
     }
 
     #[inline]
@@ -61,6 +76,17 @@ impl<'w> EntityRef<'w> {
     pub fn children(&self) -> Option<&[Entity]> {
         self.get::<Children>().map(|children| children.0.as_slice())
     }
+
+    #[inline]
+    pub fn related<R: Relation>(&self) -> &[Entity] {
+        self.get::<Related<R>>().map_or(&[], |related| related)
+    }
+
+    #[inline]
+    pub fn related_by<R: Relation>(&self) -> &[Entity] {
+        self.get::<RelatedBy<R>>()
+            .map_or(&[], |related_by| related_by)
+    }
 }
 
 pub struct EntityMut<'w> {
@@ -94,19 +120,29 @@ impl<'w> EntityMut<'w> {
         self.world
             .components()
             .get_id(TypeId::of::<T>())
-            .is_some_and(|id| self.archetype().contains(id))
+            .is_some_and(|id| self.world.has_component(self.location, id))
     }
 
     #[inline]
     pub fn get<T: Component>(&self) -> Option<&T> {
-        self.world.get_component(self.location)
+        self.world.get_component(self.entity, self.location)
+    }
+
+    // Pointer to the value of a component, e.g. one registered at runtime
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<*const u8> {
+        unsafe {
+            self.world
+                .get_component_by_id(component_id, self.entity, self.location)
+                .map(|(data, _ticks)| data as *const u8)
+        }
     }
 
     #[inline]
     pub fn get_mut<T: Component>(&mut self) -> Option<CmptMut<T>> {
         unsafe {
             self.world
-                .get_component_unchecked_mut::<T>(self.location)
+                .get_component_unchecked_mut::<T>(self.entity, self.location)
                 .map(|(data, ticks)| {
                     let last_change_tick = self.world.last_change_tick();
                     let change_tick = self.world.change_tick();
@@ -119,29 +155,142 @@ impl<'w> EntityMut<'w> {
         }
     }
 
+    // Marks the component as changed, like dereferencing CmptMut mutably
+    #[inline]
+    pub fn get_mut_by_id(&mut self, component_id: ComponentId) -> Option<*mut u8> {
+        unsafe {
+            self.world
+                .get_component_by_id(component_id, self.entity, self.location)
+                .map(|(data, ticks)| {
+                    (*ticks).set_changed(self.world.change_tick());
+                    data
+                })
+        }
+    }
+
     // TODO: move relevant methods to World (add/remove bundle)
     pub fn insert_bundle<T: Bundle>(&mut self, bundle: T) -> &mut Self {
+        let presence = self.bundle_presence::<T>();
         let change_tick = self.world.change_tick();
+        let entity = self.entity;
+        let bundle_id = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components)
+            .id;
+
+        unsafe {
+            self.insert_with(
+                bundle_id,
+                presence,
+                |bundle_info, table, sparse_sets, components, table_row, bundle_status| {
+                    bundle_info.write_components(
+                        table,
+                        sparse_sets,
+                        components,
+                        entity,
+                        table_row,
+                        bundle,
+                        bundle_status,
+                        change_tick,
+                    )
+                },
+            )
+        }
+    }
+
+    // Moves the value behind the pointer into the entity, the caller must not drop it afterwards.
+    // Used for components registered at runtime, the value must match the component's layout.
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn insert_by_id(&mut self, component_id: ComponentId, value: *mut u8) -> &mut Self {
+        assert!(
+            self.world.components.get_info(component_id).is_some(),
+            "Component {:?} is not registered",
+            component_id
+        );
+        let presence = self.component_presence(vec![component_id]);
+        let change_tick = self.world.change_tick();
+        let entity = self.entity;
+        let bundle_id = self
+            .world
+            .bundles
+            .init_dynamic_info(&self.world.components, &[component_id])
+            .id;
+
+        self.insert_with(
+            bundle_id,
+            presence,
+            |bundle_info, table, sparse_sets, components, table_row, bundle_status| {
+                bundle_info.write_component(
+                    table,
+                    sparse_sets,
+                    components,
+                    entity,
+                    table_row,
+                    0,
+                    value,
+                    bundle_status,
+                    change_tick,
+                )
+            },
+        )
+    }
+
+    // Safe variant of insert_by_id for runtime components that are plain data
+    pub fn insert_bytes(&mut self, component_id: ComponentId, bytes: &[u8]) -> &mut Self {
+        let info = self
+            .world
+            .components
+            .get_info(component_id)
+            .unwrap_or_else(|| panic!("Component {:?} is not registered", component_id));
+        assert!(
+            info.is_dynamic() && !info.needs_drop(),
+            "Component {} is not plain data registered at runtime",
+            info.name()
+        );
+        assert_eq!(
+            bytes.len(),
+            info.layout().size(),
+            "Size of the value does not match component {}",
+            info.name()
+        );
+
+        // Values are copied byte by byte, so the buffer does not need the component's alignment
+        let mut value = bytes.to_vec();
+        unsafe { self.insert_by_id(component_id, value.as_mut_ptr()) }
+    }
+
+    unsafe fn insert_with(
+        &mut self,
+        bundle_id: BundleId,
+        presence: Vec<(ComponentId, bool)>,
+        write: impl FnOnce(
+            &BundleInfo,
+            &mut Table,
+            &mut SparseSets,
+            &Components,
+            usize,
+            &[ComponentStatus],
+        ),
+    ) -> &mut Self {
         let entity = self.entity;
         let entities = &mut self.world.entities;
         let archetypes = &mut self.world.archetypes;
         let components = &mut self.world.components;
         let storages = &mut self.world.storages;
 
-        let bundle_info = self.world.bundles.init_info::<T>(components);
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
         let current_location = self.location;
 
-        let new_location = unsafe {
-            get_insert_bundle_info(
-                entities,
-                archetypes,
-                components,
-                storages,
-                bundle_info,
-                current_location,
-                entity,
-            )
-        };
+        let new_location = get_insert_bundle_info(
+            entities,
+            archetypes,
+            components,
+            storages,
+            bundle_info,
+            current_location,
+            entity,
+        );
         self.location = new_location;
 
         let edge = archetypes[current_location.archetype_id]
@@ -151,15 +300,30 @@ impl<'w> EntityMut<'w> {
         let archetype = &archetypes[new_location.archetype_id];
         let table = &mut storages.tables[archetype.table_id()];
 
-        unsafe {
-            bundle_info.write_components(
-                table,
-                new_location.index,
-                bundle,
-                &edge.bundle_status,
-                change_tick,
-            )
-        };
+        write(
+            bundle_info,
+            table,
+            &mut storages.sparse_sets,
+            components,
+            new_location.table_row,
+            &edge.bundle_status,
+        );
+
+        if !presence.is_empty() {
+            let added = presence
+                .iter()
+                .filter(|(_, present)| !present)
+                .map(|(component_id, _)| *component_id)
+                .collect::<Vec<_>>();
+            let inserted = presence
+                .iter()
+                .map(|(component_id, _)| *component_id)
+                .collect::<Vec<_>>();
+            self.world.trigger(ComponentEvent::Add, entity, &added);
+            self.world
+                .trigger(ComponentEvent::Insert, entity, &inserted);
+            self.refresh_location();
+        }
 
         self
     }
@@ -169,6 +333,11 @@ impl<'w> EntityMut<'w> {
     }
 
     pub fn remove_bundle<T: Bundle>(&mut self) -> Option<T> {
+        let presence = self.bundle_presence::<T>();
+        if presence.iter().all(|(_, present)| *present) {
+            self.trigger_remove(&presence);
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -190,22 +359,34 @@ impl<'w> EntityMut<'w> {
         let old_archetype_id = old_location.archetype_id;
         let old_archetype = &mut archetypes[old_archetype_id];
         let old_table = &storages.tables[old_archetype.table_id()];
-        let mut bundle_components = bundle_info.component_ids.iter().cloned();
+        let sparse_sets = &mut storages.sparse_sets;
+        let mut bundle_components = bundle_info
+            .component_ids
+            .iter()
+            .cloned()
+            .zip(bundle_info.storage_types.iter().cloned());
 
         let result = unsafe {
             T::from_components(|| {
-                let component_id = bundle_components.next().unwrap();
+                let (component_id, storage_type) = bundle_components.next().unwrap();
 
                 removed_components
                     .entry(component_id)
                     .or_default()
                     .push(entity);
 
-                let table = old_table;
-                let column = table
-                    .get_column(component_id)
-                    .expect("The entity does not contain given component");
-                column.get_unchecked(old_location.index)
+                match storage_type {
+                    StorageType::Table => {
+                        let column = old_table
+                            .get_column(component_id)
+                            .expect("The entity does not contain given component");
+                        column.get_unchecked(old_location.table_row)
+                    }
+                    StorageType::SparseSet => sparse_sets
+                        .get_mut(component_id)
+                        .and_then(|set| set.remove_and_forget(entity))
+                        .expect("The entity does not contain given component"),
+                }
             })
         };
 
@@ -226,13 +407,41 @@ impl<'w> EntityMut<'w> {
     }
 
     pub fn remove_bundle_intersection<T: Bundle>(&mut self) {
+        let presence = self.bundle_presence::<T>();
+        let bundle_id = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components)
+            .id;
+        self.remove_intersection(bundle_id, presence);
+    }
+
+    // Drops the component if the entity has it, e.g. one registered at runtime
+    pub fn remove_by_id(&mut self, component_id: ComponentId) {
+        assert!(
+            self.world.components.get_info(component_id).is_some(),
+            "Component {:?} is not registered",
+            component_id
+        );
+        let presence = self.component_presence(vec![component_id]);
+        let bundle_id = self
+            .world
+            .bundles
+            .init_dynamic_info(&self.world.components, &[component_id])
+            .id;
+        self.remove_intersection(bundle_id, presence);
+    }
+
+    fn remove_intersection(&mut self, bundle_id: BundleId, presence: Vec<(ComponentId, bool)>) {
+        self.trigger_remove(&presence);
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
         let entities = &mut self.world.entities;
         let removed_components = &mut self.world.removed_components;
 
-        let bundle_info = self.world.bundles.init_info::<T>(components);
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
         let old_location = self.location;
         let old_archetype_id = old_location.archetype_id;
         let new_archetype_id = remove_bundle_from_archetype(
@@ -250,8 +459,8 @@ impl<'w> EntityMut<'w> {
         }
 
         let entity = self.entity;
-        let old_archetype = &mut archetypes[old_archetype_id];
-        for component_id in bundle_info.component_ids.iter().cloned() {
+        let old_archetype = &archetypes[old_archetype_id];
+        for component_id in bundle_info.component_ids.iter().copied() {
             if old_archetype.contains(component_id) {
                 removed_components
                     .entry(component_id)
@@ -259,6 +468,11 @@ impl<'w> EntityMut<'w> {
                     .push(entity);
             }
         }
+        for component_id in bundle_info.sparse_components() {
+            if let Some(set) = storages.sparse_sets.get_mut(component_id) {
+                set.remove(entity);
+            }
+        }
 
         self.location = move_entity_after_remove(
             true,
@@ -300,6 +514,36 @@ impl<'w> EntityMut<'w> {
         self.get::<Children>().map(|children| children.0.as_slice())
     }
 
+    #[inline]
+    pub fn related<R: Relation>(&self) -> &[Entity] {
+        self.get::<Related<R>>().map_or(&[], |related| related)
+    }
+
+    #[inline]
+    pub fn related_by<R: Relation>(&self) -> &[Entity] {
+        self.get::<RelatedBy<R>>()
+            .map_or(&[], |related_by| related_by)
+    }
+
+    // Adds a link to target, the target gets a reverse link in RelatedBy<R>
+    pub fn relate<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        relation::relate::<R>(self.world, self.entity, target);
+        self.refresh_location();
+        self
+    }
+
+    pub fn unrelate<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        relation::unrelate::<R>(self.world, self.entity, target);
+        self.refresh_location();
+        self
+    }
+
+    pub fn unrelate_all<R: Relation>(&mut self) -> &mut Self {
+        relation::unrelate_all::<R>(self.world, self.entity);
+        self.refresh_location();
+        self
+    }
+
     pub fn push_child(&mut self, child: Entity) -> &mut Self {
         // TODO: What if child already has a parent?
         // TODO: Uniqueness
@@ -418,8 +662,66 @@ impl<'w> EntityMut<'w> {
         self
     }
 
+    // Runs for the given event of component T on this entity, the observers are dropped when the
+    // entity is despawned
+    pub fn observe<T: Component>(
+        &mut self,
+        event: ComponentEvent,
+        observer: impl FnMut(&mut World, Trigger) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let component_id = self.world.register_component::<T>();
+        self.world
+            .observers
+            .add(Some(self.entity), event, component_id, Box::new(observer));
+        self
+    }
+
+    // Every component of the bundle and whether the entity has it, empty when nothing listens to
+    // component events
+    fn bundle_presence<T: Bundle>(&mut self) -> Vec<(ComponentId, bool)> {
+        if !self.world.has_component_listeners() {
+            return Vec::new();
+        }
+
+        let components = &mut self.world.components;
+        let bundle_info = self.world.bundles.init_info::<T>(components);
+        let component_ids = bundle_info.component_ids.clone();
+        self.component_presence(component_ids)
+    }
+
+    fn component_presence(&self, component_ids: Vec<ComponentId>) -> Vec<(ComponentId, bool)> {
+        if !self.world.has_component_listeners() {
+            return Vec::new();
+        }
+
+        component_ids
+            .into_iter()
+            .map(|component_id| {
+                let present = self.world.has_component(self.location, component_id);
+                (component_id, present)
+            })
+            .collect()
+    }
+
+    fn trigger_remove(&mut self, presence: &[(ComponentId, bool)]) {
+        let removed = presence
+            .iter()
+            .filter(|(_, present)| *present)
+            .map(|(component_id, _)| *component_id)
+            .collect::<Vec<_>>();
+        if !removed.is_empty() {
+            self.world
+                .trigger(ComponentEvent::Remove, self.entity, &removed);
+            self.refresh_location();
+        }
+    }
+
     fn refresh_location(&mut self) {
-        self.location = self.world.entities.get(self.entity).unwrap();
+        self.location = self
+            .world
+            .entities
+            .get(self.entity)
+            .expect("Entity was despawned while it was being modified");
     }
 }
 
@@ -433,23 +735,67 @@ fn despawn_recursive(world: &mut World, entity: Entity) {
 }
 
 fn despawn_self(world: &mut World, entity: Entity) {
-    let location = world.entities.free(entity).unwrap();
+    if world.has_component_listeners() {
+        let location = world.entities.get(entity).unwrap();
+        let component_ids = world.archetypes[location.archetype_id]
+            .components()
+            .to_vec();
+        world.trigger(ComponentEvent::Remove, entity, &component_ids);
+        // An observer may have despawned the entity already
+        if !world.has_entity(entity) {
+            return;
+        }
+        world.observers.remove_entity(entity);
+    }
+
+    let location = world
+        .entities
+        .free(entity)
+        .expect("Entity to despawn does not exist");
 
-    let archetype = &mut world.archetypes[location.archetype_id];
-    let remove_result = archetype.swap_remove(location.index);
-    if let Some(swapped_entity) = remove_result {
-        world.entities.update_location(swapped_entity, location);
+    let archetypes = &mut world.archetypes;
+    let archetype = &mut archetypes[location.archetype_id];
+    if let Some(swapped_entity) = archetype.swap_remove(location.index) {
+        world.entities.update_index(swapped_entity, location.index);
     }
 
-    let table_row = location.index;
-    unsafe { world.storages.tables[archetype.table_id()].swap_remove_unchecked(table_row) };
+    let table_id = archetype.table_id();
+    let swapped_entity =
+        unsafe { world.storages.tables[table_id].swap_remove_unchecked(location.table_row) };
+    update_table_row(
+        archetypes,
+        &mut world.entities,
+        swapped_entity,
+        location.table_row,
+    );
 
     let removed_components = &mut world.removed_components;
-    for &component_id in archetype.components() {
+    for &component_id in archetypes[location.archetype_id].components() {
         removed_components
             .entry(component_id)
             .or_default()
             .push(entity);
+        let info = unsafe { world.components.get_info_unchecked(component_id) };
+        if info.storage_type() == StorageType::SparseSet
+            && let Some(set) = world.storages.sparse_sets.get_mut(component_id)
+        {
+            set.remove(entity);
+        }
+    }
+}
+
+// Entities of different archetypes can share a table, so the archetype of the entity moved into a
+// removed table row has to be updated too
+fn update_table_row(
+    archetypes: &mut Archetypes,
+    entities: &mut Entities,
+    swapped_entity: Option<Entity>,
+    table_row: usize,
+) {
+    if let Some(swapped_entity) = swapped_entity {
+        let location = entities.get(swapped_entity).unwrap();
+        archetypes[location.archetype_id].set_table_row(location.index, table_row);
+        entities.update_table_row(swapped_entity, table_row);
     }
 }
 
@@ -475,22 +821,30 @@ unsafe fn get_insert_bundle_info(
         current_location
     } else {
         let old_archetype = &mut archetypes[current_archetype_id];
-        let result = old_archetype.swap_remove(current_location.index);
-        if let Some(swapped_entity) = result {
-            entities.update_location(swapped_entity, current_location)
+        if let Some(swapped_entity) = old_archetype.swap_remove(current_location.index) {
+            entities.update_index(swapped_entity, current_location.index);
         }
 
+        // Adding only sparse set components keeps the entity in its table
         let old_table_id = old_archetype.table_id();
         let new_table_id = archetypes[new_archetype_id].table_id();
-        let (old_table, new_table) = storages.tables.get_2_mut(old_table_id, new_table_id);
-        old_table.move_to_superset_unchecked(current_location.index, new_table);
-
-        let new_archetype = &mut archetypes[new_archetype_id];
-        let new_location = new_archetype.next_location();
-        new_archetype.allocate(entity);
+        let table_row = if old_table_id == new_table_id {
+            current_location.table_row
+        } else {
+            let (old_table, new_table) = storages.tables.get_2_mut(old_table_id, new_table_id);
+            let result =
+                old_table.move_to_superset_unchecked(current_location.table_row, new_table);
+            update_table_row(
+                archetypes,
+                entities,
+                result.swapped_entity,
+                current_location.table_row,
+            );
+            result.new_row
+        };
 
+        let new_location = archetypes[new_archetype_id].allocate(entity, table_row);
         entities.update_location(entity, new_location);
-
         new_location
     }
 }
@@ -512,7 +866,12 @@ unsafe fn add_bundle_to_archetype(
     let mut bundle_status = Vec::with_capacity(bundle_info.component_ids.len());
 
     let current_archetype = &mut archetypes[archetype_id];
-    for component_id in bundle_info.component_ids.iter().cloned() {
+    let bundle_components = bundle_info
+        .component_ids
+        .iter()
+        .cloned()
+        .zip(bundle_info.storage_types.iter());
+    for (component_id, _) in bundle_components {
         if current_archetype.contains(component_id) {
             bundle_status.push(ComponentStatus::Mutated);
         } else {
@@ -532,7 +891,7 @@ unsafe fn add_bundle_to_archetype(
 
         let table_id = storages
             .tables
-            .get_id_or_insert(&new_components, components);
+            .get_id_or_insert(&table_components(components, &new_components), components);
 
         let new_archetype_id = archetypes.get_id_or_insert(table_id, &new_components);
 
@@ -563,7 +922,7 @@ fn remove_bundle_from_archetype(
     }
 
     let mut removed_components = Vec::new();
-    for component_id in bundle_info.component_ids.iter().cloned() {
+    for component_id in bundle_info.component_ids.iter().copied() {
         if current_archetype.contains(component_id) {
             removed_components.push(component_id);
         } else if !intersection {
@@ -576,13 +935,16 @@ fn remove_bundle_from_archetype(
     let mut next_components = current_archetype.components().to_vec();
     sorted_remove(&mut next_components, &removed_components);
 
-    let next_table_id = if removed_components.is_empty() {
+    let next_table_id = if bundle_info
+        .table_components()
+        .all(|id| !removed_components.contains(&id))
+    {
         current_archetype.table_id()
     } else {
         unsafe {
             storages
                 .tables
-                .get_id_or_insert(&next_components, components)
+                .get_id_or_insert(&table_components(components, &next_components), components)
         }
     };
 
@@ -592,6 +954,16 @@ fn remove_bundle_from_archetype(
     new_archetype_id
 }
 
+fn table_components(components: &Components, component_ids: &[ComponentId]) -> Vec<ComponentId> {
+    component_ids
+        .iter()
+        .copied()
+        .filter(|id| {
+            unsafe { components.get_info_unchecked(*id) }.storage_type() == StorageType::Table
+        })
+        .collect()
+}
+
 fn sorted_remove<T: Eq + Ord + Copy>(source: &mut Vec<T>, remove: &[T]) {
     let mut remove_index = 0;
     source.retain(|value| {
@@ -617,31 +989,35 @@ fn move_entity_after_remove(
     entities: &mut Entities,
 ) -> EntityLocation {
     let old_archetype = &mut archetypes[old_location.archetype_id];
-    let remove_result = old_archetype.swap_remove(old_location.index);
-    if let Some(swapped_entity) = remove_result {
-        entities.update_location(swapped_entity, old_location);
+    if let Some(swapped_entity) = old_archetype.swap_remove(old_location.index) {
+        entities.update_index(swapped_entity, old_location.index);
     }
-    let old_table_row = old_location.index;
-    let old_table_id = old_archetype.table_id();
 
-    let new_archetype = &mut archetypes[new_archetype_id];
-    let (old_table, new_table) = storages
-        .tables
-        .get_2_mut(old_table_id, new_archetype.table_id());
-
-    unsafe {
-        if drop {
-            old_table.move_to_and_drop_missing_unchecked(old_table_row, new_table);
-        } else {
-            old_table.move_to_and_forget_missing_unchecked(old_table_row, new_table);
-        }
+    // Removing only sparse set components keeps the entity in its table
+    let old_table_id = old_archetype.table_id();
+    let new_table_id = archetypes[new_archetype_id].table_id();
+    let table_row = if old_table_id == new_table_id {
+        old_location.table_row
+    } else {
+        let (old_table, new_table) = storages.tables.get_2_mut(old_table_id, new_table_id);
+        let result = unsafe {
+            if drop {
+                old_table.move_to_and_drop_missing_unchecked(old_location.table_row, new_table)
+            } else {
+                old_table.move_to_and_forget_missing_unchecked(old_location.table_row, new_table)
+            }
+        };
+        update_table_row(
+            archetypes,
+            entities,
+            result.swapped_entity,
+            old_location.table_row,
+        );
+        result.new_row
     };
 
-    let new_location = new_archetype.next_location();
-    new_archetype.allocate(entity);
-
+    let new_location = archetypes[new_archetype_id].allocate(entity, table_row);
     entities.update_location(entity, new_location);
-
     new_location
 }
 
@@ -14,7 +14,7 @@ pub use mouse::*;
 pub use touch::*;
 
 use crate::{
-    app::{App, MainStage},
+    app::{App, MainStage, Plugin},
     ecs::ResMut,
 };
 
@@ -25,6 +25,15 @@ pub mod prelude {
     };
 }
 
+#[derive(Default)]
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        input_plugin(app);
+    }
+}
+
 pub fn input_plugin(app: &mut App) {
     app.init_resource::<KeyboardInput>()
         .init_resource::<MouseInput>()
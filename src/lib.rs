@@ -11,10 +11,14 @@ pub mod ecs;
 pub mod input;
 pub mod logging;
 pub mod pipeline;
+pub mod reflect;
 pub mod render;
 pub mod run;
+pub mod scene;
 pub mod sprite;
 pub mod tasks;
+#[cfg(test)]
+mod test_util;
 pub mod text;
 pub mod timing;
 pub mod transform;
@@ -26,8 +30,9 @@ pub mod windowing;
 pub mod prelude {
     pub use crate::{
         app::prelude::*, asset::prelude::*, audio::prelude::*, ecs::prelude::*, input::prelude::*,
-        pipeline::prelude::*, render::prelude::*, run::prelude::*, sprite::prelude::*,
-        tasks::prelude::*, text::prelude::*, timing::prelude::*, transform::prelude::*,
-        ty::prelude::*, ui::prelude::*, windowing::prelude::*,
+        pipeline::prelude::*, reflect::prelude::*, render::prelude::*, run::prelude::*,
+        scene::prelude::*, sprite::prelude::*, tasks::prelude::*, text::prelude::*,
+        timing::prelude::*, transform::prelude::*, ty::prelude::*, ui::prelude::*,
+        windowing::prelude::*,
     };
 }
@@ -11,7 +11,7 @@ pub use main_pass_driver::*;
 use std::{collections::HashMap, ops::Range};
 
 use crate::{
-    app::{App, RenderStage},
+    app::{App, Plugin, RenderStage},
     ecs::{Commands, Entity, Res, Resource},
     render::{
         cameras::{ActiveCamera, Camera2d, RenderTarget},
@@ -86,6 +86,15 @@ pub mod clear_graph {
     }
 }
 
+#[derive(Default)]
+pub struct CorePipelinePlugin;
+
+impl Plugin for CorePipelinePlugin {
+    fn build(&self, app: &mut App, render_app: &mut App) {
+        core_pipeline_plugin(app, render_app);
+    }
+}
+
 pub fn core_pipeline_plugin(app: &mut App, render_app: &mut App) {
     app.init_resource::<ClearColor>()
         .init_resource::<RenderTargetClearColors>();
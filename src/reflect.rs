@@ -0,0 +1,205 @@
+mod impls;
+mod path;
+#[allow(clippy::module_inception)]
+mod reflect;
+mod registry;
+mod type_info;
+
+pub use path::GetPath;
+pub use quad_macros::Reflect;
+pub use reflect::{Reflect, ReflectError};
+pub use registry::{ReflectComponent, ReflectResource, TypeRegistration, TypeRegistry};
+pub use type_info::{FieldInfo, TypeInfo, TypeKind};
+
+use crate::{
+    app::{App, Plugin},
+    ecs::Entity,
+    transform::{Children, GlobalTransform, Parent, Transform},
+    ty::{Deg, Rad, Vec2, Vec3, Vec4},
+};
+
+pub mod prelude {
+    pub use crate::reflect::{GetPath, Reflect, TypeRegistry};
+}
+
+#[derive(Default)]
+pub struct ReflectPlugin;
+
+impl Plugin for ReflectPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        reflect_plugin(app);
+    }
+}
+
+pub fn reflect_plugin(app: &mut App) {
+    app.init_resource::<TypeRegistry>();
+    app.resource_mut::<TypeRegistry>()
+        .register::<bool>()
+        .register::<u32>()
+        .register::<i32>()
+        .register::<f32>()
+        .register::<String>()
+        .register::<Entity>()
+        .register::<Vec<Entity>>()
+        .register::<Vec2>()
+        .register::<Vec3>()
+        .register::<Vec4>()
+        .register::<Rad>()
+        .register::<Deg>()
+        .register_component::<Transform>()
+        .register_component::<GlobalTransform>()
+        .register_component::<Parent>()
+        .register_component::<Children>();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ecs::{Component, DetectChanges, Entity, Resource, World};
+
+    use super::{GetPath, Reflect, ReflectError, TypeKind, TypeRegistry};
+
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    struct Point {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Component, Reflect, Debug, PartialEq)]
+    struct Body {
+        position: Point,
+        mass: f32,
+        #[reflect(ignore)]
+        cache: Vec<u8>,
+    }
+
+    #[derive(Component, Reflect)]
+    struct Target(Entity, String);
+
+    #[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+    #[reflect(value)]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    #[derive(Resource, Reflect)]
+    struct Settings {
+        mode: Mode,
+        scale: u32,
+    }
+
+    fn new_body() -> Body {
+        Body {
+            position: Point { x: 1.0, y: 2.0 },
+            mass: 3.0,
+            cache: vec![1],
+        }
+    }
+
+    #[test]
+    fn type_info_and_paths() {
+        let info = Body::type_info();
+        let fields = info.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(fields, ["position", "mass"]);
+        assert_eq!(
+            info.field("mass").unwrap().type_name(),
+            std::any::type_name::<f32>()
+        );
+        assert!(matches!(
+            Target::type_info().kind(),
+            TypeKind::TupleStruct(_)
+        ));
+        assert_eq!(Mode::type_info().kind(), &TypeKind::Value);
+
+        let mut body = new_body();
+        assert_eq!(body.get_path::<f32>("position.y"), Ok(&2.0));
+        body.set_path("position.x", 5.0f32).unwrap();
+        assert_eq!(body.position.x, 5.0);
+        assert_eq!(
+            body.get_path::<u32>("mass"),
+            Err(ReflectError::TypeMismatch {
+                expected: "u32",
+                found: "f32"
+            })
+        );
+        assert!(matches!(
+            body.path("cache"),
+            Err(ReflectError::MissingField { .. })
+        ));
+
+        let mut target = Target(Entity::new(3), "enemy".into());
+        let target = target.as_reflect_mut();
+        target.set_path("1", String::from("friend")).unwrap();
+        assert_eq!(target.get_path::<String>("1").unwrap(), "friend");
+        assert_eq!(target.get_path::<Entity>("0"), Ok(&Entity::new(3)));
+
+        let mut other = new_body();
+        other
+            .apply(&Body {
+                position: Point { x: 7.0, y: 8.0 },
+                mass: 9.0,
+                cache: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(other.position, Point { x: 7.0, y: 8.0 });
+        assert_eq!(other.cache, vec![1]);
+        assert!(other.apply(&Point { x: 0.0, y: 0.0 }).is_err());
+    }
+
+    #[test]
+    fn reflect_components_and_resources() {
+        let mut registry = TypeRegistry::default();
+        registry
+            .register::<Point>()
+            .register_component::<Body>()
+            .register_component::<Target>()
+            .register_resource::<Settings>();
+
+        let mut world = World::new();
+        world.insert_resource(Settings {
+            mode: Mode::Fast,
+            scale: 1,
+        });
+        let entity = world.spawn().insert(new_body()).id();
+
+        let registration = registry.get_by_name(std::any::type_name::<Body>()).unwrap();
+        assert!(registration.resource().is_none());
+        let component = registration.component().unwrap();
+        assert_eq!(
+            component
+                .get(&world, entity)
+                .unwrap()
+                .get_path::<f32>("mass"),
+            Ok(&3.0)
+        );
+
+        world.clear_trackers();
+        component
+            .get_mut(&mut world, entity)
+            .unwrap()
+            .set_path("mass", 4.0f32)
+            .unwrap();
+        assert_eq!(world.get::<Body>(entity).unwrap().mass, 4.0);
+        assert!(world.get_mut::<Body>(entity).unwrap().is_changed());
+
+        let components = registry.entity_components(&world, entity);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].0.type_name(), std::any::type_name::<Body>());
+
+        let resource = registry
+            .get(std::any::TypeId::of::<Settings>())
+            .and_then(|registration| registration.resource())
+            .unwrap();
+        let settings = resource.get_mut(&mut world).unwrap();
+        settings
+            .field_mut("mode")
+            .unwrap()
+            .apply(&Mode::Slow)
+            .unwrap();
+        assert_eq!(
+            resource.get(&world).unwrap().get_path("mode"),
+            Ok(&Mode::Slow)
+        );
+        assert!(world.is_resource_changed::<Settings>());
+    }
+}
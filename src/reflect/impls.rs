@@ -0,0 +1,173 @@
+use std::any::Any;
+
+use crate::{
+    ecs::Entity,
+    ty::{Deg, Rad, Vec2, Vec3, Vec4},
+};
+
+use super::{FieldInfo, Reflect, ReflectError, TypeInfo, TypeKind};
+
+fn apply_value<T: Reflect + Clone>(
+    target: &mut T,
+    value: &dyn Reflect,
+) -> Result<(), ReflectError> {
+    let value = value
+        .downcast_ref::<T>()
+        .ok_or(ReflectError::TypeMismatch {
+            expected: target.type_name(),
+            found: value.type_name(),
+        })?;
+    *target = value.clone();
+    Ok(())
+}
+
+macro_rules! impl_reflect_value {
+    ($($ty:ty),*) => {
+        $(
+            impl Reflect for $ty {
+                fn type_info() -> TypeInfo {
+                    TypeInfo::new::<Self>(TypeKind::Value)
+                }
+
+                #[inline]
+                fn type_name(&self) -> &'static str {
+                    std::any::type_name::<Self>()
+                }
+
+                #[inline]
+                fn as_any(&self) -> &dyn Any {
+                    self
+                }
+
+                #[inline]
+                fn as_any_mut(&mut self) -> &mut dyn Any {
+                    self
+                }
+
+                #[inline]
+                fn as_reflect(&self) -> &dyn Reflect {
+                    self
+                }
+
+                #[inline]
+                fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+                    self
+                }
+
+                fn apply(&mut self, value: &dyn Reflect) -> Result<(), ReflectError> {
+                    apply_value(self, value)
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_value!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, String,
+    Entity, Rad, Deg
+);
+
+// Math types come from an external crate, so their fields are listed here
+macro_rules! impl_reflect_struct {
+    ($ty:ty, $field_ty:ty, $($field:ident),*) => {
+        impl Reflect for $ty {
+            fn type_info() -> TypeInfo {
+                TypeInfo::new::<Self>(TypeKind::Struct(vec![
+                    $(FieldInfo::new::<$field_ty>(stringify!($field))),*
+                ]))
+            }
+
+            #[inline]
+            fn type_name(&self) -> &'static str {
+                std::any::type_name::<Self>()
+            }
+
+            #[inline]
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            #[inline]
+            fn as_any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+
+            #[inline]
+            fn as_reflect(&self) -> &dyn Reflect {
+                self
+            }
+
+            #[inline]
+            fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+                self
+            }
+
+            fn field_names(&self) -> &'static [&'static str] {
+                &[$(stringify!($field)),*]
+            }
+
+            fn field(&self, name: &str) -> Option<&dyn Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_reflect_struct!(Vec2, f32, x, y);
+impl_reflect_struct!(Vec3, f32, x, y, z);
+impl_reflect_struct!(Vec4, f32, x, y, z, w);
+
+impl<T: Reflect + Clone> Reflect for Vec<T> {
+    fn type_info() -> TypeInfo {
+        TypeInfo::new::<Self>(TypeKind::List(FieldInfo::new::<T>("item")))
+    }
+
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_reflect(&self) -> &dyn Reflect {
+        self
+    }
+
+    #[inline]
+    fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+        self
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let index = name.parse::<usize>().ok()?;
+        self.get(index).map(|item| item as &dyn Reflect)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let index = name.parse::<usize>().ok()?;
+        self.get_mut(index).map(|item| item as &mut dyn Reflect)
+    }
+
+    fn apply(&mut self, value: &dyn Reflect) -> Result<(), ReflectError> {
+        apply_value(self, value)
+    }
+}
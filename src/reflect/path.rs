@@ -0,0 +1,70 @@
+use super::{Reflect, ReflectError};
+
+// Fields are separated by dots, e.g. "translation.x" or "0.1". An empty path is the value itself.
+pub trait GetPath {
+    fn path(&self, path: &str) -> Result<&dyn Reflect, ReflectError>;
+
+    fn path_mut(&mut self, path: &str) -> Result<&mut dyn Reflect, ReflectError>;
+
+    fn get_path<T: Reflect>(&self, path: &str) -> Result<&T, ReflectError> {
+        let value = self.path(path)?;
+        let found = value.type_name();
+        value
+            .downcast_ref::<T>()
+            .ok_or_else(|| type_mismatch::<T>(found))
+    }
+
+    fn get_path_mut<T: Reflect>(&mut self, path: &str) -> Result<&mut T, ReflectError> {
+        let value = self.path_mut(path)?;
+        let found = value.type_name();
+        value
+            .downcast_mut::<T>()
+            .ok_or_else(|| type_mismatch::<T>(found))
+    }
+
+    fn set_path<T: Reflect>(&mut self, path: &str, value: T) -> Result<(), ReflectError> {
+        *self.get_path_mut::<T>(path)? = value;
+        Ok(())
+    }
+}
+
+impl<R: Reflect + ?Sized> GetPath for R {
+    fn path(&self, path: &str) -> Result<&dyn Reflect, ReflectError> {
+        let mut current = self.as_reflect();
+        for name in split_path(path) {
+            current = current
+                .field(name)
+                .ok_or_else(|| missing_field(current, name))?;
+        }
+        Ok(current)
+    }
+
+    fn path_mut(&mut self, path: &str) -> Result<&mut dyn Reflect, ReflectError> {
+        let mut current = self.as_reflect_mut();
+        for name in split_path(path) {
+            if current.field(name).is_none() {
+                return Err(missing_field(current, name));
+            }
+            current = current.field_mut(name).unwrap();
+        }
+        Ok(current)
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('.').filter(|name| !name.is_empty())
+}
+
+fn missing_field(value: &dyn Reflect, name: &str) -> ReflectError {
+    ReflectError::MissingField {
+        type_name: value.type_name(),
+        field: name.to_string(),
+    }
+}
+
+fn type_mismatch<T>(found: &'static str) -> ReflectError {
+    ReflectError::TypeMismatch {
+        expected: std::any::type_name::<T>(),
+        found,
+    }
+}
@@ -0,0 +1,82 @@
+use std::any::Any;
+
+use thiserror::Error;
+
+use super::TypeInfo;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReflectError {
+    #[error("type mismatch, expected `{expected}`, found `{found}`")]
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("`{type_name}` has no field `{field}`")]
+    MissingField {
+        type_name: &'static str,
+        field: String,
+    },
+}
+
+// Implemented by #[derive(Reflect)]. Structs expose their fields by name, tuple struct fields are
+// named by their index. Other types are opaque values.
+pub trait Reflect: Any + Send + Sync {
+    fn type_info() -> TypeInfo
+    where
+        Self: Sized;
+
+    fn type_name(&self) -> &'static str;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn as_reflect(&self) -> &dyn Reflect;
+
+    fn as_reflect_mut(&mut self) -> &mut dyn Reflect;
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn field(&self, _name: &str) -> Option<&dyn Reflect> {
+        None
+    }
+
+    fn field_mut(&mut self, _name: &str) -> Option<&mut dyn Reflect> {
+        None
+    }
+
+    // Copies the value field by field, both values have to be of the same type
+    fn apply(&mut self, value: &dyn Reflect) -> Result<(), ReflectError> {
+        if self.as_any().type_id() != value.as_any().type_id() {
+            return Err(ReflectError::TypeMismatch {
+                expected: self.type_name(),
+                found: value.type_name(),
+            });
+        }
+        for &name in self.field_names() {
+            if let (Some(field), Some(value)) = (self.field_mut(name), value.field(name)) {
+                field.apply(value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl dyn Reflect {
+    #[inline]
+    pub fn is<T: Reflect>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    #[inline]
+    pub fn downcast_ref<T: Reflect>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    #[inline]
+    pub fn downcast_mut<T: Reflect>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+}
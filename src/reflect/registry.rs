@@ -0,0 +1,180 @@
+use std::{any::TypeId, collections::HashMap};
+
+use crate::ecs::{Component, DetectChanges, Entity, Resource, World};
+
+use super::{Reflect, TypeInfo};
+
+type GetComponentFn = fn(&World, Entity) -> Option<&dyn Reflect>;
+type GetComponentMutFn = fn(&mut World, Entity) -> Option<&mut dyn Reflect>;
+type GetResourceFn = fn(&World) -> Option<&dyn Reflect>;
+type GetResourceMutFn = fn(&mut World) -> Option<&mut dyn Reflect>;
+
+// Accesses a component of a registered type without knowing the type statically
+#[derive(Clone, Copy)]
+pub struct ReflectComponent {
+    get: GetComponentFn,
+    get_mut: GetComponentMutFn,
+}
+
+impl ReflectComponent {
+    fn of<T: Reflect + Component>() -> Self {
+        Self {
+            get: |world, entity| world.get::<T>(entity).map(|value| value as &dyn Reflect),
+            get_mut: |world, entity| {
+                let mut value = world.get_mut::<T>(entity)?;
+                value.set_changed();
+                Some(value.into_inner())
+            },
+        }
+    }
+
+    #[inline]
+    pub fn get<'w>(&self, world: &'w World, entity: Entity) -> Option<&'w dyn Reflect> {
+        (self.get)(world, entity)
+    }
+
+    // Marks the component as changed
+    #[inline]
+    pub fn get_mut<'w>(&self, world: &'w mut World, entity: Entity) -> Option<&'w mut dyn Reflect> {
+        (self.get_mut)(world, entity)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ReflectResource {
+    get: GetResourceFn,
+    get_mut: GetResourceMutFn,
+}
+
+impl ReflectResource {
+    fn of<T: Reflect + Resource>() -> Self {
+        Self {
+            get: |world| {
+                let value = world.get_resource::<T>()?;
+                Some(value.into_inner())
+            },
+            get_mut: |world| {
+                let mut value = world.get_resource_mut::<T>()?;
+                value.set_changed();
+                Some(value.into_inner())
+            },
+        }
+    }
+
+    #[inline]
+    pub fn get<'w>(&self, world: &'w World) -> Option<&'w dyn Reflect> {
+        (self.get)(world)
+    }
+
+    // Marks the resource as changed
+    #[inline]
+    pub fn get_mut<'w>(&self, world: &'w mut World) -> Option<&'w mut dyn Reflect> {
+        (self.get_mut)(world)
+    }
+}
+
+pub struct TypeRegistration {
+    info: TypeInfo,
+    component: Option<ReflectComponent>,
+    resource: Option<ReflectResource>,
+}
+
+impl TypeRegistration {
+    fn of<T: Reflect>() -> Self {
+        Self {
+            info: T::type_info(),
+            component: None,
+            resource: None,
+        }
+    }
+
+    #[inline]
+    pub fn info(&self) -> &TypeInfo {
+        &self.info
+    }
+
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.info.type_id()
+    }
+
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.info.type_name()
+    }
+
+    #[inline]
+    pub fn component(&self) -> Option<&ReflectComponent> {
+        self.component.as_ref()
+    }
+
+    #[inline]
+    pub fn resource(&self) -> Option<&ReflectResource> {
+        self.resource.as_ref()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TypeRegistry {
+    registrations: HashMap<TypeId, TypeRegistration>,
+    names: HashMap<&'static str, TypeId>,
+}
+
+impl TypeRegistry {
+    pub fn register<T: Reflect>(&mut self) -> &mut Self {
+        self.entry::<T>();
+        self
+    }
+
+    pub fn register_component<T: Reflect + Component>(&mut self) -> &mut Self {
+        self.entry::<T>().component = Some(ReflectComponent::of::<T>());
+        self
+    }
+
+    pub fn register_resource<T: Reflect + Resource>(&mut self) -> &mut Self {
+        self.entry::<T>().resource = Some(ReflectResource::of::<T>());
+        self
+    }
+
+    fn entry<T: Reflect>(&mut self) -> &mut TypeRegistration {
+        let type_id = TypeId::of::<T>();
+        self.names.insert(std::any::type_name::<T>(), type_id);
+        self.registrations
+            .entry(type_id)
+            .or_insert_with(TypeRegistration::of::<T>)
+    }
+
+    #[inline]
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.registrations.contains_key(&type_id)
+    }
+
+    #[inline]
+    pub fn get(&self, type_id: TypeId) -> Option<&TypeRegistration> {
+        self.registrations.get(&type_id)
+    }
+
+    #[inline]
+    pub fn get_by_name(&self, type_name: &str) -> Option<&TypeRegistration> {
+        self.get(*self.names.get(type_name)?)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration> {
+        self.registrations.values()
+    }
+
+    // Reflected components of the entity, e.g. for an inspector
+    pub fn entity_components<'w>(
+        &self,
+        world: &'w World,
+        entity: Entity,
+    ) -> Vec<(&TypeRegistration, &'w dyn Reflect)> {
+        self.iter()
+            .filter_map(|registration| {
+                let value = registration.component()?.get(world, entity)?;
+                Some((registration, value))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+use std::any::TypeId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    name: &'static str,
+    type_name: &'static str,
+    type_id: TypeId,
+}
+
+impl FieldInfo {
+    pub fn new<T: 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeKind {
+    Struct(Vec<FieldInfo>),
+    TupleStruct(Vec<FieldInfo>),
+    // Items are accessed by their index
+    List(FieldInfo),
+    Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    kind: TypeKind,
+}
+
+impl TypeInfo {
+    pub fn new<T: 'static>(kind: TypeKind) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            kind,
+        }
+    }
+
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &TypeKind {
+        &self.kind
+    }
+
+    #[inline]
+    pub fn fields(&self) -> &[FieldInfo] {
+        match &self.kind {
+            TypeKind::Struct(fields) | TypeKind::TupleStruct(fields) => fields,
+            TypeKind::List(_) | TypeKind::Value => &[],
+        }
+    }
+
+    #[inline]
+    pub fn field(&self, name: &str) -> Option<&FieldInfo> {
+        self.fields().iter().find(|field| field.name == name)
+    }
+}
@@ -15,9 +15,9 @@ pub mod view;
 use derive_deref::{Deref, DerefMut};
 
 use crate::{
-    app::{App, RenderStage},
+    app::{App, Plugin, RenderStage},
     asset::AssetServer,
-    ecs::{Resource, World},
+    ecs::{EcsDiagnostics, Resource, World},
     render::{
         cameras::camera_plugin,
         render_graph::RenderGraph,
@@ -56,6 +56,15 @@ pub struct RenderApp;
 #[derive(Default, Resource)]
 struct ScratchMainWorld(World);
 
+#[derive(Default)]
+pub struct RenderPlugin;
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App, render_app: &mut App) {
+        render_plugin(app, render_app);
+    }
+}
+
 // TODO Maybe this should create the render app and return it
 pub fn render_plugin(app: &mut App, render_app: &mut App) {
     let options = app
@@ -110,10 +119,12 @@ pub fn render_plugin(app: &mut App, render_app: &mut App) {
 
     render_app.add_system_to_stage(RenderStage::Extract, RenderPipelineCache::extract_shaders);
 
-    render_app.add_system_to_stage(
-        RenderStage::Render,
-        RenderPipelineCache::process_pipeline_queue_system,
-    );
+    render_app
+        .add_system_to_stage(
+            RenderStage::Render,
+            RenderPipelineCache::process_pipeline_queue_system,
+        )
+        .add_system_to_stage(RenderStage::Render, render_system);
 
     window_render_plugin(app, render_app);
     camera_plugin(app, render_app);
@@ -122,6 +133,16 @@ pub fn render_plugin(app: &mut App, render_app: &mut App) {
 }
 
 pub fn update_render_app(app_world: &mut World, render_app: &mut App) {
+    // Render stages are measured while the main world has diagnostics, and recorded there
+    let diagnostics = app_world.get_resource::<EcsDiagnostics>().is_some();
+    if diagnostics != render_app.world.get_resource::<EcsDiagnostics>().is_some() {
+        if diagnostics {
+            render_app.world.insert_resource(EcsDiagnostics::default());
+        } else {
+            render_app.world.remove_resource::<EcsDiagnostics>();
+        }
+    }
+
     // reserve all existing app entities for use in render_app
     // they can only be spawned using `get_or_spawn()`
     let meta_len = app_world.entities().meta_len();
@@ -158,13 +179,19 @@ pub fn update_render_app(app_world: &mut World, render_app: &mut App) {
         .systems
         .run(RenderStage::Render, &mut render_app.world);
 
-    render_system(&mut render_app.world);
-
     // cleanup
     render_app
         .systems
         .run(RenderStage::Cleanup, &mut render_app.world);
 
+    if diagnostics {
+        let stages = std::mem::take(&mut render_app.world.resource_mut::<EcsDiagnostics>().stages);
+        let mut app_diagnostics = app_world.resource_mut::<EcsDiagnostics>();
+        for stage in stages {
+            app_diagnostics.record_stage(stage);
+        }
+    }
+
     render_app.world.clear_entities();
 }
 
@@ -177,11 +204,16 @@ fn extract(app_world: &mut World, render_app: &mut App) {
     let running_world = &mut render_app.world;
     running_world.insert_resource(MainWorld(inserted_world));
 
+    // Custom stages next to Extract can access the main world as well
     render_app
         .systems
-        .get(RenderStage::Extract)
-        .unwrap()
-        .run(running_world);
+        .run_before(RenderStage::Extract, running_world);
+    render_app
+        .systems
+        .run_systems(RenderStage::Extract, running_world);
+    render_app
+        .systems
+        .run_after(RenderStage::Extract, running_world);
 
     // move the app world back, as if nothing happened.
     let inserted_world = running_world.remove_resource::<MainWorld>().unwrap();
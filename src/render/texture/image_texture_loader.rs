@@ -1,5 +1,7 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use wgpu::{FilterMode, TextureFormat};
 
 use crate::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
@@ -14,6 +16,46 @@ pub struct ImageTextureLoader;
 
 const FILE_EXTENSIONS: &[&str] = &["png", "tga", "jpg", "jpeg", "bmp"];
 
+/// Texture filtering used by the sampler of a loaded image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFilter {
+    /// Sharp pixels, suited for pixel art.
+    #[default]
+    Nearest,
+    Linear,
+}
+
+impl From<ImageFilter> for FilterMode {
+    fn from(filter: ImageFilter) -> Self {
+        match filter {
+            ImageFilter::Nearest => FilterMode::Nearest,
+            ImageFilter::Linear => FilterMode::Linear,
+        }
+    }
+}
+
+/// Settings of the [`ImageTextureLoader`], for example in a `texture.png.meta` file:
+/// `(is_srgb: false, filter: Linear)`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageLoaderSettings {
+    /// Color data is sRGB encoded, disable for normal maps and other linear data.
+    pub is_srgb: bool,
+    pub filter: ImageFilter,
+    /// Multiplies the color channels of 8-bit RGBA images by their alpha.
+    pub premultiply_alpha: bool,
+}
+
+impl Default for ImageLoaderSettings {
+    fn default() -> Self {
+        Self {
+            is_srgb: true,
+            filter: ImageFilter::Nearest,
+            premultiply_alpha: false,
+        }
+    }
+}
+
 impl AssetLoader for ImageTextureLoader {
     fn load<'a>(
         &'a self,
@@ -21,16 +63,26 @@ impl AssetLoader for ImageTextureLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
+            let settings = load_context.settings::<ImageLoaderSettings>()?;
+
             // use the file extension for the image type
             let ext = load_context.path().extension().unwrap().to_str().unwrap();
 
-            let dyn_img =
-                Image::from_buffer(bytes, ImageType::Extension(ext), true).map_err(|err| {
-                    FileTextureError {
+            let mut dyn_img =
+                Image::from_buffer(bytes, ImageType::Extension(ext), settings.is_srgb).map_err(
+                    |err| FileTextureError {
                         error: err,
                         path: format!("{}", load_context.path().display()),
-                    }
-                })?;
+                    },
+                )?;
+
+            let filter = settings.filter.into();
+            dyn_img.sampler_descriptor.mag_filter = filter;
+            dyn_img.sampler_descriptor.min_filter = filter;
+            dyn_img.sampler_descriptor.mipmap_filter = filter;
+            if settings.premultiply_alpha {
+                premultiply_alpha(&mut dyn_img, load_context);
+            }
 
             load_context.set_default_asset(LoadedAsset::new(dyn_img));
             Ok(())
@@ -42,6 +94,24 @@ impl AssetLoader for ImageTextureLoader {
     }
 }
 
+fn premultiply_alpha(image: &mut Image, load_context: &LoadContext) {
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            for pixel in image.data.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u16;
+                for channel in &mut pixel[..3] {
+                    *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+                }
+            }
+        }
+        format => log::warn!(
+            "Cannot premultiply alpha of {} in format {:?}",
+            load_context.path().display(),
+            format
+        ),
+    }
+}
+
 /// An error that occurs when loading a texture from a file.
 #[derive(Error, Debug)]
 pub struct FileTextureError {
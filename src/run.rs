@@ -1,11 +1,13 @@
 mod context;
+mod default_plugins;
 mod quad;
 mod runner;
 mod scene;
 
-pub use self::quad::{Quad, QuadConfig};
+pub use self::quad::{HeadlessConfig, Quad, QuadConfig};
+pub use default_plugins::DefaultPlugins;
 pub use scene::{Scene, SceneResult, SceneStage};
 
 pub mod prelude {
-    pub use crate::run::{Quad, QuadConfig, Scene, SceneResult, SceneStage};
+    pub use crate::run::{HeadlessConfig, Quad, QuadConfig, Scene, SceneResult, SceneStage};
 }
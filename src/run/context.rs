@@ -18,7 +18,7 @@ use super::{Scene, SceneResult, SceneStage};
 
 pub struct RunContext {
     app: App,
-    render_app: App,
+    render_app: Option<App>,
     _audio_device: AudioDevice,
     stage: SceneStage,
     scene: Vec<Box<dyn Scene>>,
@@ -27,7 +27,7 @@ pub struct RunContext {
 impl RunContext {
     pub fn new(
         app: App,
-        render_app: App,
+        render_app: Option<App>,
         audio_device: AudioDevice,
         scene: Box<dyn Scene>,
     ) -> Self {
@@ -42,9 +42,9 @@ impl RunContext {
 
     pub fn update(&mut self) -> bool {
         if let Some(scene) = self.scene.last_mut() {
-            let result = self
-                .app
-                .update_main_app(&mut self.render_app, scene.as_mut(), self.stage);
+            let result =
+                self.app
+                    .update_main_app(self.render_app.as_mut(), scene.as_mut(), self.stage);
 
             match result {
                 SceneResult::Ok(stage) => {
@@ -0,0 +1,66 @@
+use crate::{
+    app::{PluginGroup, PluginGroupBuilder},
+    asset::{AssetPlugin, AssetServerSettings},
+    audio::AudioPlugin,
+    input::InputPlugin,
+    pipeline::CorePipelinePlugin,
+    reflect::ReflectPlugin,
+    render::RenderPlugin,
+    scene::ScenePlugin,
+    sprite::SpritePlugin,
+    text::TextPlugin,
+    timing::TimingPlugin,
+    transform::TransformPlugin,
+    ui::UiPlugin,
+    windowing::WindowingPlugin,
+};
+
+// The plugins added by Quad, in the order they are built
+pub struct DefaultPlugins {
+    pub asset_server_settings: AssetServerSettings,
+    pub audio: AudioPlugin,
+    // Render plugins are disabled if false, they can still be enabled on the built group
+    pub render: bool,
+}
+
+impl Default for DefaultPlugins {
+    fn default() -> Self {
+        Self {
+            asset_server_settings: Default::default(),
+            audio: Default::default(),
+            render: true,
+        }
+    }
+}
+
+impl PluginGroup for DefaultPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let plugins = PluginGroupBuilder::new()
+            .add(WindowingPlugin)
+            .add(ReflectPlugin)
+            .add(TimingPlugin)
+            .add(InputPlugin)
+            .add(AssetPlugin {
+                settings: self.asset_server_settings,
+            })
+            .add(self.audio)
+            .add(TransformPlugin)
+            .add(ScenePlugin)
+            .add(RenderPlugin)
+            .add(CorePipelinePlugin)
+            .add(SpritePlugin)
+            .add(TextPlugin)
+            .add(UiPlugin);
+
+        if self.render {
+            plugins
+        } else {
+            plugins
+                .disable::<RenderPlugin>()
+                .disable::<CorePipelinePlugin>()
+                .disable::<SpritePlugin>()
+                .disable::<TextPlugin>()
+                .disable::<UiPlugin>()
+        }
+    }
+}
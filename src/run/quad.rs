@@ -1,16 +1,36 @@
+use std::{any::TypeId, time::Duration};
+
 use log::LevelFilter;
 use winit::event_loop::EventLoop;
 
 use crate::{
-    app::{App, StageLabel, TaskPoolOptions},
+    app::{
+        App, IntoSystemDescriptor, Plugin, PluginGroup, PluginGroupBuilder, ScheduleError,
+        StageLabel, StageSettings, SystemSet, TaskPoolOptions,
+    },
     asset::{Asset, AssetLoader, AssetServerSettings},
-    audio::AudioDevice,
-    ecs::{Event, FromWorld, IntoSystem, Resource},
+    audio::{AudioDevice, AudioPlugin},
+    ecs::{Event, EventRetention, FromWorld, Resource, World},
     logging::init_logging,
-    windowing::{Window, WindowDescriptor, WindowId},
+    render::update_render_app,
+    tasks::ComputeTaskPool,
+    windowing::{Window, WindowDescriptor, WindowId, Windows},
+};
+
+use super::{
+    DefaultPlugins, Scene,
+    context::RunContext,
+    runner::{headless_runner, winit_runner},
 };
 
-use super::{Scene, context::RunContext, runner::winit_runner};
+// Runs without an event loop, window or audio device, e.g. for tests and dedicated servers
+#[derive(Clone, Debug, Default)]
+pub struct HeadlessConfig {
+    // Render plugins need a GPU adapter but no display
+    pub render: bool,
+    // Minimum duration of a frame when running a scene, frames run back to back if None
+    pub frame_duration: Option<Duration>,
+}
 
 #[derive(Default)]
 pub struct QuadConfig {
@@ -18,6 +38,7 @@ pub struct QuadConfig {
     pub asset_server_settings: AssetServerSettings,
     pub main_window: WindowDescriptor,
     pub log_level: Option<LevelFilter>,
+    pub headless: Option<HeadlessConfig>,
 }
 
 pub struct Quad {
@@ -25,22 +46,122 @@ pub struct Quad {
     render_app: App,
     audio_device: AudioDevice,
     event_loop: Option<EventLoop<()>>,
+    headless: Option<HeadlessConfig>,
+    plugins: Vec<TypeId>,
 }
 
 impl Quad {
+    #[inline]
     pub fn new(config: QuadConfig) -> Self {
+        Self::with_plugins(config, |plugins| plugins)
+    }
+
+    // Allows disabling or replacing the default plugins before they are built, e.g.
+    // `plugins.disable::<UiPlugin>().replace::<TextPlugin, _>(MyTextPlugin)`
+    pub fn with_plugins<F>(config: QuadConfig, customize: F) -> Self
+    where
+        F: FnOnce(PluginGroupBuilder) -> PluginGroupBuilder,
+    {
         init_logging(config.log_level);
+        let headless = config.headless.is_some();
         let mut quad = Self {
             app: App::default(),
             render_app: App::default(),
-            audio_device: AudioDevice::default(),
-            event_loop: Some(EventLoop::new().unwrap()),
+            audio_device: if headless {
+                AudioDevice::empty()
+            } else {
+                AudioDevice::default()
+            },
+            event_loop: (!headless).then(|| EventLoop::new().unwrap()),
+            headless: config.headless.clone(),
+            plugins: Vec::new(),
         };
         quad.add_pools(&config);
-        quad.add_plugins(&config);
+        quad.add_main_window(&config);
+
+        let plugins = DefaultPlugins {
+            asset_server_settings: config.asset_server_settings.clone(),
+            audio: AudioPlugin::new(&quad.audio_device),
+            render: config
+                .headless
+                .as_ref()
+                .is_none_or(|headless| headless.render),
+        };
+        quad.add_plugins(customize(plugins.build()));
         quad
     }
 
+    #[inline]
+    pub fn headless() -> Self {
+        Self::new(QuadConfig {
+            headless: Some(HeadlessConfig::default()),
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    #[inline]
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    // Steps all main stages once without a scene, the render app is updated too if it exists
+    pub fn update(&mut self) {
+        self.app.update();
+        if self.has_render_app() {
+            update_render_app(&mut self.app.world, &mut self.render_app);
+        }
+    }
+
+    pub fn add_plugin<P: Plugin>(&mut self, plugin: P) -> &mut Self {
+        self.build_plugin(TypeId::of::<P>(), &plugin);
+        self
+    }
+
+    pub fn add_plugins<G: PluginGroup>(&mut self, group: G) -> &mut Self {
+        for (type_id, plugin) in group.build().into_plugins() {
+            self.build_plugin(type_id, plugin.as_ref());
+        }
+        self
+    }
+
+    #[inline]
+    pub fn is_plugin_added<P: Plugin>(&self) -> bool {
+        self.plugins.contains(&TypeId::of::<P>())
+    }
+
+    // The render app exists once a plugin, the built-in render plugin or a replacement, added
+    // systems to it
+    #[inline]
+    pub fn has_render_app(&self) -> bool {
+        !self.render_app.systems.is_empty()
+    }
+
+    // Checks the main and render app, run does this before the first frame
+    pub fn validate_schedule(&self) -> Result<(), ScheduleError> {
+        self.app.validate_schedule()?;
+        self.render_app.validate_schedule()
+    }
+
+    fn build_plugin(&mut self, type_id: TypeId, plugin: &dyn Plugin) {
+        assert!(
+            !self.plugins.contains(&type_id),
+            "Plugin {} already added",
+            plugin.name()
+        );
+        self.plugins.push(type_id);
+        plugin.build(&mut self.app, &mut self.render_app);
+    }
+
     pub fn init_resource<T: Resource + FromWorld>(&mut self) -> &mut Self {
         self.app.init_resource::<T>();
         self
@@ -51,20 +172,99 @@ impl Quad {
         self
     }
 
+    pub fn add_stage_before<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.app.add_stage_before(target, label, settings);
+        self
+    }
+
+    pub fn add_stage_after<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.app.add_stage_after(target, label, settings);
+        self
+    }
+
     pub fn add_system_to_stage<L, S, Params>(&mut self, stage: L, system: S) -> &mut Self
     where
         L: StageLabel,
-        S: IntoSystem<(), (), Params>,
+        S: IntoSystemDescriptor<Params>,
     {
         self.app.add_system_to_stage(stage, system);
         self
     }
 
+    pub fn add_system_set_to_stage<L>(&mut self, stage: L, system_set: SystemSet) -> &mut Self
+    where
+        L: StageLabel + Copy,
+    {
+        self.app.add_system_set_to_stage(stage, system_set);
+        self
+    }
+
+    // Render stages can be anchored to RenderStage values or to other custom render stages
+    pub fn add_render_stage_before<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.render_app.add_stage_before(target, label, settings);
+        self
+    }
+
+    pub fn add_render_stage_after<A, L>(
+        &mut self,
+        target: A,
+        label: L,
+        settings: StageSettings,
+    ) -> &mut Self
+    where
+        A: StageLabel,
+        L: StageLabel,
+    {
+        self.render_app.add_stage_after(target, label, settings);
+        self
+    }
+
+    pub fn add_render_system_to_stage<L, S, Params>(&mut self, stage: L, system: S) -> &mut Self
+    where
+        L: StageLabel,
+        S: IntoSystemDescriptor<Params>,
+    {
+        self.render_app.add_system_to_stage(stage, system);
+        self
+    }
+
     pub fn add_event<T: Event>(&mut self) -> &mut Self {
         self.app.add_event::<T>();
         self
     }
 
+    pub fn add_event_with_retention<T: Event>(&mut self, retention: EventRetention) -> &mut Self {
+        self.app.add_event_with_retention::<T>(retention);
+        self
+    }
+
     pub fn add_asset<T: Asset>(&mut self) -> &mut Self {
         self.app.add_asset::<T>();
         self
@@ -81,34 +281,77 @@ impl Quad {
     }
 
     pub fn run(&mut self, scene: Box<dyn Scene>) {
+        if let Err(error) = self.validate_schedule() {
+            panic!("Invalid schedule: {error}");
+        }
         let app = std::mem::take(&mut self.app);
+        let has_render_app = self.has_render_app();
         let render_app = std::mem::take(&mut self.render_app);
+        let render_app = has_render_app.then_some(render_app);
         let audio_device = std::mem::replace(&mut self.audio_device, AudioDevice::empty());
-        let event_loop = self.event_loop.take().unwrap();
         let context = RunContext::new(app, render_app, audio_device, scene);
-        winit_runner(context, event_loop).expect("Event loop failed");
+        match self.event_loop.take() {
+            Some(event_loop) => winit_runner(context, event_loop).expect("Event loop failed"),
+            None => headless_runner(
+                context,
+                self.headless
+                    .as_ref()
+                    .and_then(|headless| headless.frame_duration),
+            ),
+        }
     }
 
     fn add_pools(&mut self, config: &QuadConfig) {
         self.app.create_pools(&config.task_pool_options);
+        let compute_task_pool = self.app.resource::<ComputeTaskPool>().clone();
+        self.render_app.insert_resource(compute_task_pool);
+    }
+
+    // Added before the plugins are built, so that the render plugin can create its surface
+    fn add_main_window(&mut self, config: &QuadConfig) {
+        if let Some(event_loop) = self.event_loop.as_ref() {
+            let main_window = Window::new(WindowId::primary(), &config.main_window, event_loop);
+            self.app.init_resource::<Windows>().add_window(main_window);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        app::MainStage,
+        ecs::{Component, Query},
+        render::RenderPlugin,
+        windowing::Windows,
+    };
+
+    use super::Quad;
+
+    #[derive(Component)]
+    struct Counter(u32);
+
+    fn count(mut query: Query<&mut Counter>) {
+        for mut counter in query.iter_mut() {
+            counter.0 += 1;
+        }
     }
 
-    // TODO Wrap all of this in the MainApp and RenderApp traits
-    fn add_plugins(&mut self, config: &QuadConfig) {
-        let app = &mut self.app;
-        app.add_windowing_plugin();
-        let main_window = Window::new(
-            WindowId::primary(),
-            &config.main_window,
-            self.event_loop.as_ref().unwrap(),
+    #[test]
+    fn headless_update() {
+        let mut quad = Quad::headless();
+        let entity = quad.world_mut().spawn().insert(Counter(0)).id();
+        quad.add_system_to_stage(MainStage::PreUpdate, count);
+        for _ in 0..3 {
+            quad.update();
+        }
+
+        assert_eq!(quad.world().get::<Counter>(entity).unwrap().0, 3);
+        assert!(!quad.is_plugin_added::<RenderPlugin>());
+        assert!(!quad.has_render_app());
+        assert!(
+            quad.world()
+                .get_resource::<Windows>()
+                .is_none_or(|windows| windows.get_primary().is_none())
         );
-        app.add_window(main_window);
-
-        app.add_timing_plugin();
-        app.add_input_plugin();
-        app.add_asset_plugin(&config.asset_server_settings);
-        app.add_audio_plugin(&self.audio_device);
-        app.add_transform_plugin();
-        app.add_render_plugin(&mut self.render_app);
     }
 }
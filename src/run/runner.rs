@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use winit::{
     error::EventLoopError,
     event::{DeviceEvent, Event, WindowEvent},
@@ -101,3 +103,18 @@ pub fn winit_runner(
         }
     })
 }
+
+// Updates the app until the scene quits, waiting between frames if a frame duration is given
+pub fn headless_runner(mut context: RunContext, frame_duration: Option<Duration>) {
+    loop {
+        let frame_start = Instant::now();
+        if context.update() {
+            break;
+        }
+        if let Some(remaining) =
+            frame_duration.and_then(|duration| duration.checked_sub(frame_start.elapsed()))
+        {
+            std::thread::sleep(remaining);
+        }
+    }
+}
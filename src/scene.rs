@@ -0,0 +1,181 @@
+mod dynamic_scene;
+mod loader;
+mod prefab;
+mod registry;
+mod serializer;
+mod spawner;
+
+pub use dynamic_scene::{DynamicComponent, DynamicEntity, DynamicScene, SceneError};
+pub use loader::SceneLoader;
+pub use prefab::Prefab;
+pub use registry::{AppSceneRegistry, SceneComponent, SceneRegistry, SceneValue};
+pub use serializer::SceneDeserializer;
+pub use spawner::{SceneSpawner, SpawnPrefab, WriteScene, scene_spawner_system};
+
+use crate::{
+    app::{App, MainStage, Plugin},
+    transform::{Children, Parent},
+};
+
+pub mod prelude {
+    pub use crate::scene::{AppSceneRegistry, DynamicScene, Prefab, SceneSpawner};
+}
+
+#[derive(Default)]
+pub struct ScenePlugin;
+
+impl Plugin for ScenePlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        scene_plugin(app);
+    }
+}
+
+pub fn scene_plugin(app: &mut App) {
+    app.init_resource::<AppSceneRegistry>()
+        .init_resource::<SceneSpawner>()
+        .add_asset::<DynamicScene>()
+        .add_asset::<Prefab>()
+        .init_asset_loader::<SceneLoader>()
+        .register_scene_component_mapped::<Parent>("quad::Parent")
+        .register_scene_component_mapped::<Children>("quad::Children")
+        .add_system_to_stage(MainStage::PreUpdate, scene_spawner_system);
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        ecs::{Component, Entity, With, World},
+        transform::{Children, Parent},
+    };
+
+    use super::{DynamicScene, Prefab, SceneError, SceneRegistry};
+
+    #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Name(String);
+
+    #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Health(u32);
+
+    #[derive(Component, Clone)]
+    struct Unregistered;
+
+    fn registry() -> SceneRegistry {
+        let mut registry = SceneRegistry::default();
+        registry
+            .register::<Name>("test::Name")
+            .register::<Health>("test::Health")
+            .register_mapped::<Parent>("quad::Parent")
+            .register_mapped::<Children>("quad::Children");
+        registry
+    }
+
+    fn populate(world: &mut World) -> (Entity, Entity) {
+        let filler = world.spawn().insert(Health(0)).id();
+        let parent = world
+            .spawn()
+            .insert_bundle((Name("parent".into()), Unregistered))
+            .id();
+        let child = world
+            .spawn()
+            .insert_bundle((Name("child".into()), Health(7)))
+            .id();
+        world.entity_mut(parent).push_child(child);
+        world.despawn(filler);
+        (parent, child)
+    }
+
+    fn check_loaded(world: &mut World, scene: &DynamicScene, registry: &SceneRegistry) {
+        let entity_map = scene.write_to_world(world, registry).unwrap();
+        assert_eq!(entity_map.len(), 2);
+
+        let mut query = world.query::<(Entity, &Name, Option<&Health>, Option<&Parent>)>();
+        let mut loaded = query.iter(world).collect::<Vec<_>>();
+        loaded.sort_by(|a, b| a.1.0.cmp(&b.1.0));
+        let (child, _, health, parent) = loaded[0];
+        assert_eq!(health, Some(&Health(7)));
+        let parent = parent.unwrap().get();
+        assert_eq!(loaded[1].0, parent);
+        assert_eq!(world.entity(parent).children(), Some(&[child][..]));
+        assert!(!world.entity(parent).contains::<Unregistered>());
+    }
+
+    #[test]
+    fn round_trip_all_formats() {
+        let registry = registry();
+        let mut world = World::new();
+        populate(&mut world);
+        let scene = DynamicScene::from_world(&world, &registry);
+        assert_eq!(scene.entities.len(), 2);
+
+        let ron = scene.to_ron().unwrap();
+        let json = scene.to_json().unwrap();
+        let bytes = scene.to_bytes().unwrap();
+        let loaded = [
+            DynamicScene::from_ron(&registry, &ron).unwrap(),
+            DynamicScene::from_json(&registry, &json).unwrap(),
+            DynamicScene::from_bytes(&registry, &bytes).unwrap(),
+        ];
+        for scene in loaded.iter() {
+            check_loaded(&mut World::new(), scene, &registry);
+        }
+
+        // Ids of the loaded entities differ from the ids stored in the scene
+        let mut world = World::new();
+        world.spawn();
+        check_loaded(&mut world, &loaded[0], &registry);
+    }
+
+    #[test]
+    fn subset_and_errors() {
+        let registry = registry();
+        let mut world = World::new();
+        let (parent, child) = populate(&mut world);
+
+        let scene = DynamicScene::from_entities(&world, &registry, [child]);
+        let mut target = World::new();
+        assert!(matches!(
+            scene.write_to_world(&mut target, &registry),
+            Err(SceneError::MapEntities(_))
+        ));
+        assert_eq!(target.query::<Entity>().iter(&target).count(), 0);
+
+        world.entity_mut(child).remove::<Parent>();
+        let scene = DynamicScene::from_query::<With<Health>>(&mut world, &registry);
+        assert_eq!(scene.entities.len(), 1);
+        assert_eq!(scene.entities[0].entity, child);
+        assert!(world.entity(parent).children().is_some());
+
+        let mut empty = SceneRegistry::default();
+        assert!(DynamicScene::from_ron(&empty, &scene.to_ron().unwrap()).is_err());
+        empty.register::<Name>("test::Name");
+        assert!(matches!(
+            scene.write_to_world(&mut World::new(), &empty),
+            Err(SceneError::UnregisteredComponent(_))
+        ));
+    }
+
+    #[test]
+    fn spawn_prefab() {
+        let mut world = World::new();
+        world
+            .register_cloneable::<Name>()
+            .register_cloneable::<Health>();
+        let (parent, _) = populate(&mut world);
+        let prefab = Prefab::from_entity(&world, parent);
+        world.despawn_recursive(parent);
+
+        let mut target = World::new();
+        let first = prefab.spawn(&mut target);
+        let second = prefab.spawn(&mut target);
+        assert_ne!(first, second);
+        assert!(!target.entity(first).contains::<Unregistered>());
+        for root in [first, second] {
+            assert_eq!(target.get::<Name>(root), Some(&Name("parent".into())));
+            let child = target.entity(root).children().unwrap()[0];
+            assert_eq!(target.get::<Health>(child), Some(&Health(7)));
+            assert_eq!(target.entity(child).parent(), Some(root));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use bincode::Options;
+use serde::de::DeserializeSeed;
+use thiserror::Error;
+
+use crate::ecs::{Entity, EntityMap, MapEntitiesError, ReadOnlyWorldQuery, World};
+
+use super::{
+    registry::{SceneComponent, SceneRegistry, SceneValue},
+    serializer::SceneDeserializer,
+};
+
+#[derive(Error, Debug)]
+pub enum SceneError {
+    #[error("component `{0}` is not registered in the scene registry")]
+    UnregisteredComponent(String),
+    #[error(transparent)]
+    MapEntities(#[from] MapEntitiesError),
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    #[error(transparent)]
+    RonSpanned(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+}
+
+pub struct DynamicComponent {
+    // Name the component was registered with in the SceneRegistry
+    pub name: &'static str,
+    pub value: Box<dyn SceneValue>,
+}
+
+impl Clone for DynamicComponent {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            value: self.value.as_ref().clone_value(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DynamicEntity {
+    // Id of the entity when the scene was created, referenced by entity-mapped components
+    pub entity: Entity,
+    pub components: Vec<DynamicComponent>,
+}
+
+// Only components registered in the SceneRegistry are stored in the scene
+#[derive(Clone, Default)]
+pub struct DynamicScene {
+    pub entities: Vec<DynamicEntity>,
+}
+
+impl DynamicScene {
+    pub fn from_world(world: &World, registry: &SceneRegistry) -> Self {
+        let mut entities = world
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.entities().iter().copied())
+            .collect::<Vec<_>>();
+        entities.sort_by_key(|entity| entity.id());
+        Self::from_entities(world, registry, entities)
+    }
+
+    pub fn from_entities(
+        world: &World,
+        registry: &SceneRegistry,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> Self {
+        let entities = entities
+            .into_iter()
+            .map(|entity| DynamicEntity {
+                entity,
+                components: registry
+                    .iter()
+                    .filter_map(|component| {
+                        Some(DynamicComponent {
+                            name: component.name(),
+                            value: component.extract(world, entity)?,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { entities }
+    }
+
+    // Stores entities matching the query filter, e.g. With<Player>
+    pub fn from_query<F: ReadOnlyWorldQuery>(world: &mut World, registry: &SceneRegistry) -> Self {
+        let mut entities = world
+            .query_filtered::<Entity, F>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        entities.sort_by_key(|entity| entity.id());
+        Self::from_entities(world, registry, entities)
+    }
+
+    // Spawns a new entity for every scene entity. Returns the mapping from scene entities to the
+    // spawned ones.
+    pub fn write_to_world(
+        &self,
+        world: &mut World,
+        registry: &SceneRegistry,
+    ) -> Result<EntityMap, SceneError> {
+        let mut components: Vec<&SceneComponent> = Vec::new();
+        for entity in self.entities.iter() {
+            for component in entity.components.iter() {
+                let registered = registry
+                    .get_by_name(component.name)
+                    .ok_or_else(|| SceneError::UnregisteredComponent(component.name.into()))?;
+                components.push(registered);
+            }
+        }
+
+        let mut entity_map = EntityMap::default();
+        for entity in self.entities.iter() {
+            entity_map.insert(entity.entity, world.spawn().id());
+        }
+
+        // Entities referenced from outside of the scene cannot be mapped, the spawned entities are
+        // removed again in that case
+        let mut components = components.into_iter();
+        for entity in self.entities.iter() {
+            let target = entity_map.get(entity.entity)?;
+            for component in entity.components.iter() {
+                let registered = components.next().unwrap();
+                let value = component.value.as_ref();
+                if let Err(error) = registered.insert(value, world, target, &entity_map) {
+                    for spawned in entity_map.values() {
+                        world.despawn(spawned);
+                    }
+                    return Err(error.into());
+                }
+            }
+        }
+
+        Ok(entity_map)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SceneError> {
+        let config = ron::ser::PrettyConfig::default().indentor("  ".to_string());
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, SceneError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SceneError> {
+        Ok(bincode::options().serialize(self)?)
+    }
+
+    pub fn from_ron(registry: &SceneRegistry, text: &str) -> Result<Self, SceneError> {
+        let mut deserializer = ron::de::Deserializer::from_str(text)?;
+        let scene = SceneDeserializer { registry }
+            .deserialize(&mut deserializer)
+            .map_err(|error| deserializer.span_error(error))?;
+        deserializer
+            .end()
+            .map_err(|error| deserializer.span_error(error))?;
+        Ok(scene)
+    }
+
+    pub fn from_json(registry: &SceneRegistry, text: &str) -> Result<Self, SceneError> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let scene = SceneDeserializer { registry }.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(scene)
+    }
+
+    pub fn from_bytes(registry: &SceneRegistry, bytes: &[u8]) -> Result<Self, SceneError> {
+        Ok(bincode::options().deserialize_seed(SceneDeserializer { registry }, bytes)?)
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+
+use crate::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::{FromWorld, World},
+    ty::BoxedFuture,
+};
+
+use super::{DynamicScene, registry::AppSceneRegistry};
+
+pub struct SceneLoader {
+    registry: AppSceneRegistry,
+}
+
+impl FromWorld for SceneLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            registry: world.resource::<AppSceneRegistry>().clone(),
+        }
+    }
+}
+
+impl AssetLoader for SceneLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let file_name = load_context
+                .path()
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.to_lowercase())
+                .unwrap_or_default();
+            let scene = {
+                let registry = self.registry.read();
+                if file_name.ends_with(".scn.bin") {
+                    DynamicScene::from_bytes(&registry, bytes)?
+                } else if file_name.ends_with(".scn.json") {
+                    DynamicScene::from_json(&registry, std::str::from_utf8(bytes)?)?
+                } else {
+                    DynamicScene::from_ron(&registry, std::str::from_utf8(bytes)?)?
+                }
+            };
+            load_context.set_default_asset(LoadedAsset::new(scene));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scn.ron", "scn.json", "scn.bin"]
+    }
+}
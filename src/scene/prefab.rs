@@ -0,0 +1,55 @@
+use crate::ecs::{Entity, World};
+
+// Entity hierarchy kept in its own world, every spawn clones the cloneable components of the root
+// and its descendants
+pub struct Prefab {
+    world: World,
+    root: Entity,
+}
+
+impl Prefab {
+    pub fn new(world: World, root: Entity) -> Self {
+        assert!(
+            world.has_entity(root),
+            "Prefab root {:?} does not exist",
+            root
+        );
+        Self { world, root }
+    }
+
+    // Copies the entity and its Children subtree out of the world, mapped components referencing
+    // entities outside of the subtree are not copied
+    pub fn from_entity(world: &World, entity: Entity) -> Self {
+        let clones = world.collect_clones(entity, true);
+        let mut prefab_world = World::new();
+        let root = prefab_world.spawn().id();
+        prefab_world.write_clones(clones, root, false);
+        Self::new(prefab_world, root)
+    }
+
+    #[inline]
+    pub fn root(&self) -> Entity {
+        self.root
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn spawn(&self, world: &mut World) -> Entity {
+        let root = world.spawn().id();
+        self.spawn_to(world, root);
+        root
+    }
+
+    pub(crate) fn spawn_to(&self, world: &mut World, root: Entity) {
+        let clones = self.world.collect_clones(self.root, true);
+        world.write_clones(clones, root, false);
+    }
+}
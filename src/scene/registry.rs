@@ -0,0 +1,216 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::ecs::{Component, Entity, EntityMap, MapEntities, MapEntitiesError, Resource, World};
+
+// A type-erased component value stored in a scene
+pub trait SceneValue: erased_serde::Serialize + Send + Sync + 'static {
+    fn type_name(&self) -> &'static str;
+    fn as_any(&self) -> &dyn Any;
+    fn clone_value(&self) -> Box<dyn SceneValue>;
+}
+
+erased_serde::serialize_trait_object!(SceneValue);
+
+impl<T> SceneValue for T
+where
+    T: Serialize + Clone + Send + Sync + 'static,
+{
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn clone_value(&self) -> Box<dyn SceneValue> {
+        Box::new(self.clone())
+    }
+}
+
+type ExtractFn = fn(&World, Entity) -> Option<Box<dyn SceneValue>>;
+type DeserializeFn =
+    fn(&mut dyn erased_serde::Deserializer) -> Result<Box<dyn SceneValue>, erased_serde::Error>;
+type InsertFn = fn(&dyn SceneValue, &mut World, Entity, &EntityMap) -> Result<(), MapEntitiesError>;
+
+#[derive(Clone, Copy)]
+pub struct SceneComponent {
+    name: &'static str,
+    extract: ExtractFn,
+    deserialize: DeserializeFn,
+    insert: InsertFn,
+}
+
+impl SceneComponent {
+    fn new<T>(name: &'static str, insert: InsertFn) -> Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        Self {
+            name,
+            extract: |world, entity| {
+                let entity = world.entity(entity);
+                Some(Box::new(entity.get::<T>()?.clone()))
+            },
+            deserialize: |deserializer| {
+                let value = erased_serde::deserialize::<T>(deserializer)?;
+                Ok(Box::new(value))
+            },
+            insert,
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[inline]
+    pub(crate) fn extract(&self, world: &World, entity: Entity) -> Option<Box<dyn SceneValue>> {
+        (self.extract)(world, entity)
+    }
+
+    #[inline]
+    pub(crate) fn deserialize(
+        &self,
+        deserializer: &mut dyn erased_serde::Deserializer,
+    ) -> Result<Box<dyn SceneValue>, erased_serde::Error> {
+        (self.deserialize)(deserializer)
+    }
+
+    #[inline]
+    pub(crate) fn insert(
+        &self,
+        value: &dyn SceneValue,
+        world: &mut World,
+        entity: Entity,
+        entity_map: &EntityMap,
+    ) -> Result<(), MapEntitiesError> {
+        (self.insert)(value, world, entity, entity_map)
+    }
+}
+
+fn downcast_value<T: Clone + 'static>(value: &dyn SceneValue) -> T {
+    value
+        .as_any()
+        .downcast_ref::<T>()
+        .unwrap_or_else(|| panic!("Scene value is not a {}", std::any::type_name::<T>()))
+        .clone()
+}
+
+// Components are identified by their registered name in scene files. Unlike type names the name
+// stays the same across compiler versions and refactorings, e.g. "game::Health".
+#[derive(Default)]
+pub struct SceneRegistry {
+    components: Vec<SceneComponent>,
+    indices: HashMap<TypeId, usize>,
+    names: HashMap<&'static str, usize>,
+}
+
+impl SceneRegistry {
+    pub fn register<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        let component = SceneComponent::new::<T>(name, |value, world, entity, _| {
+            world.entity_mut(entity).insert(downcast_value::<T>(value));
+            Ok(())
+        });
+        self.add::<T>(component)
+    }
+
+    // Entity references inside the component are remapped when the scene is spawned
+    pub fn register_mapped<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+    {
+        let component = SceneComponent::new::<T>(name, |value, world, entity, entity_map| {
+            let mut value = downcast_value::<T>(value);
+            value.map_entities(entity_map)?;
+            world.entity_mut(entity).insert(value);
+            Ok(())
+        });
+        self.add::<T>(component)
+    }
+
+    fn add<T: 'static>(&mut self, component: SceneComponent) -> &mut Self {
+        let index = self.indices.get(&TypeId::of::<T>()).copied();
+        if let Some(&other) = self.names.get(component.name) {
+            assert!(
+                Some(other) == index,
+                "Scene component name {} is already used by another component",
+                component.name
+            );
+        }
+
+        if let Some(index) = index {
+            self.names.remove(self.components[index].name);
+            self.names.insert(component.name, index);
+            self.components[index] = component;
+        } else {
+            let index = self.components.len();
+            self.indices.insert(TypeId::of::<T>(), index);
+            self.names.insert(component.name, index);
+            self.components.push(component);
+        }
+        self
+    }
+
+    #[inline]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.indices.contains_key(&TypeId::of::<T>())
+    }
+
+    #[inline]
+    pub fn get<T: 'static>(&self) -> Option<&SceneComponent> {
+        self.indices
+            .get(&TypeId::of::<T>())
+            .map(|&index| &self.components[index])
+    }
+
+    #[inline]
+    pub fn get_by_name(&self, name: &str) -> Option<&SceneComponent> {
+        self.names.get(name).map(|&index| &self.components[index])
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &SceneComponent> {
+        self.components.iter()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+// Shared between the world and the scene loader, which runs on the IO task pool
+#[derive(Resource, Clone, Default)]
+pub struct AppSceneRegistry(Arc<RwLock<SceneRegistry>>);
+
+impl AppSceneRegistry {
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<'_, SceneRegistry> {
+        self.0.read()
+    }
+
+    #[inline]
+    pub fn write(&self) -> RwLockWriteGuard<'_, SceneRegistry> {
+        self.0.write()
+    }
+}
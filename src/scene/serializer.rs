@@ -0,0 +1,229 @@
+use std::fmt;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeStruct},
+};
+
+use crate::ecs::Entity;
+
+use super::{
+    dynamic_scene::{DynamicComponent, DynamicEntity, DynamicScene},
+    registry::{SceneComponent, SceneRegistry, SceneValue},
+};
+
+const SCENE_STRUCT: &str = "DynamicScene";
+const SCENE_FIELDS: &[&str] = &["entities"];
+const ENTITY_STRUCT: &str = "DynamicEntity";
+const ENTITY_FIELDS: &[&str] = &["entity", "components"];
+
+// Components are written as a map from the registered component name to its value:
+// (entities: [(entity: 0, components: {"quad::Parent": (1)})])
+impl Serialize for DynamicScene {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct(SCENE_STRUCT, SCENE_FIELDS.len())?;
+        state.serialize_field("entities", &self.entities)?;
+        state.end()
+    }
+}
+
+impl Serialize for DynamicEntity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct(ENTITY_STRUCT, ENTITY_FIELDS.len())?;
+        state.serialize_field("entity", &self.entity)?;
+        state.serialize_field("components", &ComponentsSerializer(&self.components))?;
+        state.end()
+    }
+}
+
+struct ComponentsSerializer<'a>(&'a [DynamicComponent]);
+
+impl Serialize for ComponentsSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_map(Some(self.0.len()))?;
+        for component in self.0.iter() {
+            state.serialize_entry(component.name, component.value.as_ref())?;
+        }
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneField {
+    Entities,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum EntityField {
+    Entity,
+    Components,
+}
+
+pub struct SceneDeserializer<'a> {
+    pub registry: &'a SceneRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for SceneDeserializer<'_> {
+    type Value = DynamicScene;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct(SCENE_STRUCT, SCENE_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for SceneDeserializer<'_> {
+    type Value = DynamicScene;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a scene")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let entities = seq
+            .next_element_seed(EntitiesDeserializer(self.registry))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        Ok(DynamicScene { entities })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entities = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                SceneField::Entities => {
+                    if entities.is_some() {
+                        return Err(de::Error::duplicate_field("entities"));
+                    }
+                    entities = Some(map.next_value_seed(EntitiesDeserializer(self.registry))?);
+                }
+            }
+        }
+        let entities = entities.ok_or_else(|| de::Error::missing_field("entities"))?;
+        Ok(DynamicScene { entities })
+    }
+}
+
+struct EntitiesDeserializer<'a>(&'a SceneRegistry);
+
+impl<'de> DeserializeSeed<'de> for EntitiesDeserializer<'_> {
+    type Value = Vec<DynamicEntity>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntitiesDeserializer<'_> {
+    type Value = Vec<DynamicEntity>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of entities")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut entities = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(entity) = seq.next_element_seed(EntityDeserializer(self.0))? {
+            entities.push(entity);
+        }
+        Ok(entities)
+    }
+}
+
+struct EntityDeserializer<'a>(&'a SceneRegistry);
+
+impl<'de> DeserializeSeed<'de> for EntityDeserializer<'_> {
+    type Value = DynamicEntity;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct(ENTITY_STRUCT, ENTITY_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntityDeserializer<'_> {
+    type Value = DynamicEntity;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an entity")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let entity = seq
+            .next_element::<Entity>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let components = seq
+            .next_element_seed(ComponentsDeserializer(self.0))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(DynamicEntity { entity, components })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entity = None;
+        let mut components = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                EntityField::Entity => {
+                    if entity.is_some() {
+                        return Err(de::Error::duplicate_field("entity"));
+                    }
+                    entity = Some(map.next_value::<Entity>()?);
+                }
+                EntityField::Components => {
+                    if components.is_some() {
+                        return Err(de::Error::duplicate_field("components"));
+                    }
+                    components = Some(map.next_value_seed(ComponentsDeserializer(self.0))?);
+                }
+            }
+        }
+        let entity = entity.ok_or_else(|| de::Error::missing_field("entity"))?;
+        let components = components.ok_or_else(|| de::Error::missing_field("components"))?;
+        Ok(DynamicEntity { entity, components })
+    }
+}
+
+struct ComponentsDeserializer<'a>(&'a SceneRegistry);
+
+impl<'de> DeserializeSeed<'de> for ComponentsDeserializer<'_> {
+    type Value = Vec<DynamicComponent>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsDeserializer<'_> {
+    type Value = Vec<DynamicComponent>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(name) = map.next_key::<String>()? {
+            let component = self.0.get_by_name(&name).ok_or_else(|| {
+                de::Error::custom(format!("component `{name}` is not registered"))
+            })?;
+            components.push(DynamicComponent {
+                name: component.name(),
+                value: map.next_value_seed(ComponentDeserializer(component))?,
+            });
+        }
+        Ok(components)
+    }
+}
+
+struct ComponentDeserializer<'a>(&'a SceneComponent);
+
+impl<'de> DeserializeSeed<'de> for ComponentDeserializer<'_> {
+    type Value = Box<dyn SceneValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        self.0
+            .deserialize(&mut deserializer)
+            .map_err(de::Error::custom)
+    }
+}
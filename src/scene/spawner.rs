@@ -0,0 +1,125 @@
+use crate::{
+    asset::{AssetServer, Assets, Handle, LoadState},
+    ecs::{Command, Commands, Entity, EntityCommands, Res, ResMut, Resource, World},
+};
+
+use super::{DynamicScene, Prefab, registry::AppSceneRegistry};
+
+// Scenes and prefabs are spawned once their asset is loaded, the ones that failed to load are
+// dropped
+#[derive(Resource, Default)]
+pub struct SceneSpawner {
+    pending: Vec<Handle<DynamicScene>>,
+    pending_prefabs: Vec<(Handle<Prefab>, Entity)>,
+}
+
+impl SceneSpawner {
+    pub fn spawn(&mut self, scene: Handle<DynamicScene>) {
+        self.pending.push(scene);
+    }
+
+    // The prefab is written to the root entity, which has to be spawned already
+    pub fn spawn_prefab(&mut self, prefab: Handle<Prefab>, root: Entity) {
+        self.pending_prefabs.push((prefab, root));
+    }
+
+    #[inline]
+    pub fn pending_len(&self) -> usize {
+        self.pending.len() + self.pending_prefabs.len()
+    }
+}
+
+pub fn scene_spawner_system(
+    mut commands: Commands,
+    scenes: Res<Assets<DynamicScene>>,
+    prefabs: Res<Assets<Prefab>>,
+    asset_server: Res<AssetServer>,
+    mut spawner: ResMut<SceneSpawner>,
+) {
+    if spawner.pending_len() == 0 {
+        return;
+    }
+
+    spawner.pending_prefabs.retain(|(handle, root)| {
+        if prefabs.get(handle).is_some() {
+            commands.add(SpawnPrefab {
+                prefab: handle.clone(),
+                root: *root,
+            });
+            return false;
+        }
+        match asset_server.get_load_state(handle) {
+            LoadState::Failed => {
+                log::error!("Failed to spawn prefab: asset failed to load");
+                false
+            }
+            _ => true,
+        }
+    });
+
+    spawner.pending.retain(|handle| match scenes.get(handle) {
+        Some(scene) => {
+            commands.add(WriteScene {
+                scene: scene.clone(),
+            });
+            false
+        }
+        None => match asset_server.get_load_state(handle) {
+            LoadState::Failed => {
+                log::error!("Failed to spawn scene: asset failed to load");
+                false
+            }
+            _ => true,
+        },
+    });
+}
+
+pub struct WriteScene {
+    pub scene: DynamicScene,
+}
+
+impl Command for WriteScene {
+    fn write(self: Box<Self>, world: &mut World) {
+        let registry = world.resource::<AppSceneRegistry>().clone();
+        if let Err(error) = self.scene.write_to_world(world, &registry.read()) {
+            log::error!("Failed to spawn scene: {}", error);
+        }
+    }
+}
+
+impl<'w, 's> Commands<'w, 's> {
+    // The root is spawned right away, the prefab is written to it once loaded
+    pub fn spawn_prefab(&mut self, prefab: &Handle<Prefab>) -> EntityCommands<'w, 's, '_> {
+        let root = self.spawn().id();
+        self.add(SpawnPrefab {
+            prefab: prefab.clone(),
+            root,
+        });
+        self.entity(root)
+    }
+}
+
+pub struct SpawnPrefab {
+    pub prefab: Handle<Prefab>,
+    pub root: Entity,
+}
+
+impl Command for SpawnPrefab {
+    fn write(self: Box<Self>, world: &mut World) {
+        // The root may have been despawned while the prefab was loading
+        if !world.has_entity(self.root) {
+            return;
+        }
+        let clones = match world.resource::<Assets<Prefab>>().get(&self.prefab) {
+            Some(prefab) => prefab.world().collect_clones(prefab.root(), true),
+            None => {
+                world
+                    .get_resource_mut::<SceneSpawner>()
+                    .expect("Scene plugin not initialized")
+                    .spawn_prefab(self.prefab, self.root);
+                return;
+            }
+        };
+        world.write_clones(clones, self.root, false);
+    }
+}
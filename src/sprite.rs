@@ -18,7 +18,7 @@ pub use texture_atlas::*;
 pub use texture_atlas_builder::*;
 
 use crate::{
-    app::{App, RenderStage},
+    app::{App, Plugin, RenderStage},
     asset::{Assets, HandleId},
     pipeline::Transparent2d,
     render::render_resource::{Shader, SpecializedPipelines},
@@ -33,6 +33,12 @@ pub mod prelude {
 #[derive(Default)]
 pub struct SpritePlugin;
 
+impl Plugin for SpritePlugin {
+    fn build(&self, app: &mut App, render_app: &mut App) {
+        sprite_plugin(app, render_app);
+    }
+}
+
 // TODO Store these as a resource? Use some non random id?
 pub const SPRITE_SHADER_HANDLE: u64 = 2; // TODO Create HandleUntyped once TypeId::of is const
 pub const SPRITE_COLORED_SHADER_HANDLE: u64 = 3; // TODO Create HandleUntyped once TypeId::of is const
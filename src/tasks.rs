@@ -3,7 +3,7 @@ mod task_pool;
 mod usages;
 
 pub use task::Task;
-pub use task_pool::{TaskPool, TaskPoolBuilder};
-pub use usages::IoTaskPool;
+pub use task_pool::{Scope, TaskPool, TaskPoolBuilder};
+pub use usages::{ComputeTaskPool, IoTaskPool};
 
 pub mod prelude {}
@@ -1,10 +1,13 @@
 use std::{
+    collections::VecDeque,
     future::Future,
+    mem,
+    panic::{self, AssertUnwindSafe},
     sync::Arc,
     thread::{self, JoinHandle, available_parallelism},
 };
 
-use futures_lite::future;
+use futures_lite::{FutureExt, future};
 
 use super::Task;
 
@@ -134,6 +137,110 @@ impl TaskPool {
     {
         Task::new(self.executor.spawn(future))
     }
+
+    /// Spawns futures that may borrow from the caller's stack and blocks until all of them
+    /// complete. The calling thread helps driving the pool while it waits. If `f` or any of the
+    /// futures panics, the panic is resumed after all futures completed.
+    pub fn scope<'scope, F, T>(&self, f: F) -> Vec<T>
+    where
+        F: FnOnce(&mut Scope<'scope, T>) + 'scope + Send,
+        T: Send + 'static,
+    {
+        LOCAL_EXECUTOR.with(|local_executor| {
+            // SAFETY: This function blocks until all spawned futures complete, so nothing borrowed
+            // for 'scope outlives the call even though the executors require 'scope references.
+            let executor: &async_executor::Executor = &self.executor;
+            let executor: &'scope async_executor::Executor = unsafe { mem::transmute(executor) };
+            let scope_local_executor: &'scope async_executor::LocalExecutor =
+                unsafe { mem::transmute(local_executor) };
+            let mut scope = Scope {
+                executor,
+                local_executor: scope_local_executor,
+                spawned: Vec::new(),
+            };
+
+            let scope_result = panic::catch_unwind(AssertUnwindSafe(|| f(&mut scope)));
+
+            // Dropping a task doesn't stop it if it's already running on another thread, so the
+            // guard waits for the remaining tasks if this thread unwinds while joining them
+            let mut guard = ScopeGuard {
+                executor,
+                local_executor: scope_local_executor,
+                spawned: mem::take(&mut scope.spawned).into(),
+            };
+            let mut results = Vec::with_capacity(guard.spawned.len());
+            if !guard.spawned.is_empty() {
+                future::block_on(guard.join(|result| results.push(result)));
+            }
+
+            if let Err(payload) = scope_result {
+                panic::resume_unwind(payload);
+            }
+            results
+                .into_iter()
+                .map(|result| result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+                .collect()
+        })
+    }
+}
+
+struct ScopeGuard<'scope, T> {
+    executor: &'scope async_executor::Executor<'scope>,
+    local_executor: &'scope async_executor::LocalExecutor<'scope>,
+    spawned: VecDeque<async_executor::Task<thread::Result<T>>>,
+}
+
+impl<'scope, T> ScopeGuard<'scope, T> {
+    // Drives both executors from this thread as well, otherwise local tasks never run and a scope
+    // started from a pool thread deadlocks a single threaded pool. The thread sleeps until one of
+    // the tasks wakes it.
+    async fn join(&mut self, mut on_result: impl FnMut(thread::Result<T>)) {
+        let (executor, local_executor) = (self.executor, self.local_executor);
+        let spawned = &mut self.spawned;
+        let fut = async move {
+            // A task is only removed once it completed, a task that is still running stays in the
+            // guard if this future is dropped
+            while let Some(task) = spawned.front_mut() {
+                let result = task.await;
+                spawned.pop_front();
+                on_result(result);
+            }
+        };
+        local_executor.run(executor.run(fut)).await
+    }
+}
+
+impl<'scope, T> Drop for ScopeGuard<'scope, T> {
+    fn drop(&mut self) {
+        if !self.spawned.is_empty() {
+            future::block_on(self.join(|_| ()));
+        }
+    }
+}
+
+thread_local! {
+    static LOCAL_EXECUTOR: async_executor::LocalExecutor<'static> =
+        const { async_executor::LocalExecutor::new() };
+}
+
+pub struct Scope<'scope, T> {
+    executor: &'scope async_executor::Executor<'scope>,
+    local_executor: &'scope async_executor::LocalExecutor<'scope>,
+    spawned: Vec<async_executor::Task<thread::Result<T>>>,
+}
+
+impl<'scope, T: Send + 'scope> Scope<'scope, T> {
+    pub fn spawn<Fut: Future<Output = T> + 'scope + Send>(&mut self, f: Fut) {
+        let task = self.executor.spawn(AssertUnwindSafe(f).catch_unwind());
+        self.spawned.push(task);
+    }
+
+    pub fn spawn_local<Fut: Future<Output = T> + 'scope>(&mut self, f: Fut) {
+        let task = self
+            .local_executor
+            .spawn(AssertUnwindSafe(f).catch_unwind());
+        self.spawned.push(task);
+    }
 }
 
 impl Default for TaskPool {
@@ -141,3 +248,59 @@ impl Default for TaskPool {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use super::{TaskPool, TaskPoolBuilder};
+
+    #[test]
+    fn single_local_task() {
+        let pool = TaskPool::new();
+        let value = 5;
+        let results = pool.scope(|scope| {
+            scope.spawn_local(async { value * 2 });
+        });
+        assert_eq!(results, vec![10]);
+    }
+
+    #[test]
+    fn nested_scope_on_single_thread() {
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        let inner_pool = pool.clone();
+        let results = pool.scope(|scope| {
+            scope.spawn(async move {
+                inner_pool
+                    .scope(|scope| {
+                        scope.spawn(async { 1 });
+                    })
+                    .into_iter()
+                    .sum::<i32>()
+            });
+        });
+        assert_eq!(results, vec![1]);
+    }
+
+    #[test]
+    fn panic_waits_for_other_tasks() {
+        let pool = TaskPoolBuilder::new().num_threads(2).build();
+        let finished = AtomicBool::new(false);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.spawn(async {
+                    thread::sleep(Duration::from_millis(50));
+                    finished.store(true, Ordering::Relaxed);
+                });
+                scope.spawn(async { panic!("task failed") });
+            })
+        }));
+        assert!(result.is_err());
+        assert!(finished.load(Ordering::Relaxed));
+    }
+}
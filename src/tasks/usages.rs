@@ -14,3 +14,14 @@ impl Deref for IoTaskPool {
         &self.0
     }
 }
+
+#[derive(Clone, Debug, Resource)]
+pub struct ComputeTaskPool(pub TaskPool);
+
+impl Deref for ComputeTaskPool {
+    type Target = TaskPool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
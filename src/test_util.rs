@@ -0,0 +1,29 @@
+use crate::ecs::{IntoSystem, ResMut, Resource, System, World};
+
+// Records in which order systems, plugins, hooks or observers ran
+#[derive(Resource)]
+pub(crate) struct TestLog<T>(pub Vec<T>);
+
+pub(crate) type Log = TestLog<&'static str>;
+
+impl<T> Default for TestLog<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: Send + Sync + 'static> TestLog<T> {
+    pub fn push(world: &mut World, entry: T) {
+        world.resource_mut::<Self>().0.push(entry);
+    }
+
+    // Entries logged since the last call
+    pub fn take(world: &mut World) -> Vec<T> {
+        std::mem::take(&mut world.resource_mut::<Self>().0)
+    }
+}
+
+// System that only logs the entry
+pub(crate) fn log(entry: &'static str) -> impl System<In = (), Out = ()> {
+    (move |mut log: ResMut<Log>| log.0.push(entry)).system()
+}
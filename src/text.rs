@@ -21,7 +21,7 @@ pub use render::*;
 pub use text::*;
 
 use crate::{
-    app::{App, MainStage, RenderStage},
+    app::{App, MainStage, Plugin, RenderStage},
     ecs::{Entity, Resource},
 };
 
@@ -32,6 +32,15 @@ pub mod prelude {
 #[derive(Default, Resource, Deref, DerefMut)]
 pub struct DefaultTextPipeline(TextPipeline<Entity>);
 
+#[derive(Default)]
+pub struct TextPlugin;
+
+impl Plugin for TextPlugin {
+    fn build(&self, app: &mut App, render_app: &mut App) {
+        text_plugin(app, render_app);
+    }
+}
+
 pub fn text_plugin(app: &mut App, render_app: &mut App) {
     app.add_asset::<Font>()
         .add_asset::<FontAtlasSet>()
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ab_glyph::{FontArc, FontVec, InvalidFont, OutlinedGlyph};
 use wgpu::{Extent3d, TextureDimension, TextureFormat};
 
@@ -6,13 +8,22 @@ use crate::render::texture::Image;
 #[derive(Debug)]
 pub struct Font {
     pub font: FontArc,
+    // Named font sizes, such as "title" or "body", configured by the loader settings
+    pub size_presets: HashMap<String, f32>,
 }
 
 impl Font {
     pub fn try_from_bytes(font_data: Vec<u8>) -> Result<Self, InvalidFont> {
         let font = FontVec::try_from_vec(font_data)?;
         let font = FontArc::new(font);
-        Ok(Font { font })
+        Ok(Font {
+            font,
+            size_presets: HashMap::new(),
+        })
+    }
+
+    pub fn size_preset(&self, name: &str) -> Option<f32> {
+        self.size_presets.get(name).copied()
     }
 
     pub fn get_outlined_glyph_texture(outlined_glyph: OutlinedGlyph) -> Image {
@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
@@ -10,6 +13,13 @@ use super::Font;
 #[derive(Default)]
 pub struct FontLoader;
 
+// For example in a `font.ttf.meta` file: `(size_presets: {"title": 48.0, "body": 16.0})`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontLoaderSettings {
+    pub size_presets: HashMap<String, f32>,
+}
+
 impl AssetLoader for FontLoader {
     fn load<'a>(
         &'a self,
@@ -17,7 +27,9 @@ impl AssetLoader for FontLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
-            let font = Font::try_from_bytes(bytes.into())?;
+            let settings = load_context.settings::<FontLoaderSettings>()?;
+            let mut font = Font::try_from_bytes(bytes.into())?;
+            font.size_presets = settings.size_presets;
             load_context.set_default_asset(LoadedAsset::new(font));
             Ok(())
         })
@@ -1,17 +1,30 @@
+mod fixed_time;
 mod stopwatch;
 mod time;
 mod timer;
 
+pub use fixed_time::{FixedTime, FixedTimeAlpha};
 pub use stopwatch::Stopwatch;
 pub use time::Time;
-pub use timer::Timer;
+pub use timer::{Timer, on_timer};
 
-use crate::app::App;
+use crate::app::{App, Plugin};
 
 pub mod prelude {
-    pub use crate::timing::{Stopwatch, Time, Timer};
+    pub use crate::timing::{FixedTime, FixedTimeAlpha, Stopwatch, Time, Timer, on_timer};
+}
+
+#[derive(Default)]
+pub struct TimingPlugin;
+
+impl Plugin for TimingPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        timing_plugin(app);
+    }
 }
 
 pub fn timing_plugin(app: &mut App) {
-    app.init_resource::<Time>();
+    app.init_resource::<Time>()
+        .init_resource::<FixedTime>()
+        .init_resource::<FixedTimeAlpha>();
 }
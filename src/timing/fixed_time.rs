@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use crate::ecs::Resource;
+
+// Drives MainStage::FixedUpdate, which runs zero or more times per frame so that every run
+// advances the simulation by exactly one step
+#[derive(Debug, Clone, Resource)]
+pub struct FixedTime {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+    elapsed: Duration,
+    frame_steps: u32,
+    dropped_steps: u64,
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::from_hz(60.0)
+    }
+}
+
+impl FixedTime {
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "Fixed time step must not be zero");
+        Self {
+            step,
+            max_steps: 5,
+            accumulator: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_steps: 0,
+            dropped_steps: 0,
+        }
+    }
+
+    #[inline]
+    pub fn from_seconds(step: f32) -> Self {
+        Self::new(Duration::from_secs_f32(step))
+    }
+
+    #[inline]
+    pub fn from_hz(hz: f64) -> Self {
+        Self::new(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    // Maximum number of steps run in a single frame. Time accumulated beyond that is dropped, so
+    // that a slow frame does not cause even slower frames afterwards.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.set_max_steps(max_steps);
+        self
+    }
+
+    #[inline]
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    #[inline]
+    pub fn step_seconds(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    pub fn set_step(&mut self, step: Duration) {
+        assert!(!step.is_zero(), "Fixed time step must not be zero");
+        self.step = step;
+    }
+
+    #[inline]
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        assert!(
+            max_steps > 0,
+            "At least one fixed step per frame is required"
+        );
+        self.max_steps = max_steps;
+    }
+
+    #[inline]
+    pub fn accumulated(&self) -> Duration {
+        self.accumulator
+    }
+
+    // Simulated time, the sum of all steps run so far
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+
+    #[inline]
+    pub fn dropped_steps(&self) -> u64 {
+        self.dropped_steps
+    }
+
+    // Fraction of a step accumulated but not yet simulated, used to interpolate rendered state
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.step.as_secs_f64()).min(1.0) as f32
+    }
+
+    pub(crate) fn accumulate(&mut self, delta: Duration) {
+        self.accumulator += delta;
+        self.frame_steps = 0;
+    }
+
+    // Returns true if another step should run in this frame
+    pub(crate) fn expend(&mut self) -> bool {
+        if self.accumulator < self.step {
+            return false;
+        }
+
+        if self.frame_steps >= self.max_steps {
+            let step = self.step.as_nanos();
+            let accumulated = self.accumulator.as_nanos();
+            self.dropped_steps += (accumulated / step) as u64;
+            self.accumulator = Duration::from_nanos((accumulated % step) as u64);
+            return false;
+        }
+
+        self.accumulator -= self.step;
+        self.elapsed += self.step;
+        self.frame_steps += 1;
+        true
+    }
+}
+
+// Interpolation factor between the last two fixed steps, updated every frame after the fixed
+// update stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Resource)]
+pub struct FixedTimeAlpha(pub f32);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::FixedTime;
+
+    fn run_frame(time: &mut FixedTime, delta_ms: u64) -> u32 {
+        time.accumulate(Duration::from_millis(delta_ms));
+        let mut steps = 0;
+        while time.expend() {
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn steps_do_not_depend_on_frame_rate() {
+        let mut fast = FixedTime::new(Duration::from_millis(10));
+        let mut slow = FixedTime::new(Duration::from_millis(10));
+
+        let fast_steps = (0..12).map(|_| run_frame(&mut fast, 5)).sum::<u32>();
+        let slow_steps = (0..2).map(|_| run_frame(&mut slow, 30)).sum::<u32>();
+        assert_eq!(fast_steps, 6);
+        assert_eq!(slow_steps, 6);
+        assert_eq!(fast.elapsed(), slow.elapsed());
+
+        assert_eq!(run_frame(&mut fast, 4), 0);
+        assert!((fast.alpha() - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn catch_up_is_limited() {
+        let mut time = FixedTime::new(Duration::from_millis(10)).with_max_steps(3);
+        assert_eq!(run_frame(&mut time, 75), 3);
+        assert_eq!(time.dropped_steps(), 4);
+        assert_eq!(time.accumulated(), Duration::from_millis(5));
+        assert_eq!(run_frame(&mut time, 5), 1);
+    }
+}
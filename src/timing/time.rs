@@ -34,9 +34,7 @@ impl Time {
 
     pub(crate) fn update_with_instant(&mut self, instant: Instant) {
         if let Some(last_update) = self.last_update {
-            self.delta = instant - last_update;
-            self.delta_seconds_f64 = self.delta.as_secs_f64();
-            self.delta_seconds = self.delta.as_secs_f32();
+            self.set_delta(instant - last_update);
         }
 
         let duration_since_startup = instant - self.startup;
@@ -44,6 +42,13 @@ impl Time {
         self.last_update = Some(instant);
     }
 
+    // Also used to expose the fixed step while the fixed update stage runs
+    pub(crate) fn set_delta(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.delta_seconds_f64 = delta.as_secs_f64();
+        self.delta_seconds = delta.as_secs_f32();
+    }
+
     #[inline]
     pub fn delta(&self) -> Duration {
         self.delta
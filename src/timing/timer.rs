@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use super::stopwatch::Stopwatch;
+use crate::ecs::{IntoSystem, Res, System};
+
+use super::{stopwatch::Stopwatch, time::Time};
 
 #[derive(Clone, Debug, Default)]
 pub struct Timer {
@@ -143,3 +145,9 @@ impl Timer {
         self.times_finished
     }
 }
+
+// Run condition which holds once every `duration` of game time
+pub fn on_timer(duration: Duration) -> impl System<In = (), Out = bool> + Clone {
+    let mut timer = Timer::new(duration, true);
+    (move |time: Res<Time>| timer.tick(time.delta()).just_finished()).system()
+}
@@ -11,7 +11,7 @@ pub use parent::Parent;
 pub use transform::Transform;
 
 use crate::{
-    app::{App, MainStage},
+    app::{App, MainStage, Plugin},
     ecs::Bundle,
 };
 use transform_propagate_system::transform_propagate_system;
@@ -51,6 +51,17 @@ impl From<Transform> for TransformBundle {
     }
 }
 
+#[derive(Default)]
+pub struct TransformPlugin;
+
+impl Plugin for TransformPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        transform_plugin(app);
+    }
+}
+
 pub fn transform_plugin(app: &mut App) {
-    app.add_system_to_stage(MainStage::TransformUpdate, transform_propagate_system);
+    app.register_cloneable::<Transform>()
+        .register_cloneable::<GlobalTransform>()
+        .add_system_to_stage(MainStage::TransformUpdate, transform_propagate_system);
 }
@@ -1,8 +1,13 @@
 use std::{ops::Deref, slice};
 
-use crate::ecs::{Component, Entity};
+use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, Clone, Debug, Default, Component)]
+use crate::{
+    ecs::{Component, Entity, EntityMap, MapEntities, MapEntitiesError},
+    reflect::Reflect,
+};
+
+#[derive(Eq, PartialEq, Clone, Debug, Default, Component, Reflect, Serialize, Deserialize)]
 pub struct Children(pub(crate) Vec<Entity>);
 
 impl Children {
@@ -13,6 +18,15 @@ impl Children {
     }
 }
 
+impl MapEntities for Children {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for entity in self.0.iter_mut() {
+            *entity = entity_map.get(*entity)?;
+        }
+        Ok(())
+    }
+}
+
 impl Deref for Children {
     type Target = [Entity];
 
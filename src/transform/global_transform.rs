@@ -4,13 +4,14 @@ use cgm::{ElementWise, Zero};
 
 use crate::{
     ecs::Component,
+    reflect::Reflect,
     transform::Transform,
     ty::{Mat3, Mat4, Rad, Vec3},
 };
 
 use super::transform::IDENTITY_SCALE;
 
-#[derive(Debug, PartialEq, Clone, Copy, Component)]
+#[derive(Debug, PartialEq, Clone, Copy, Component, Reflect)]
 pub struct GlobalTransform {
     pub translation: Vec3,
     pub rotation: Rad,
@@ -1,6 +1,11 @@
-use crate::ecs::{Component, Entity};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+use crate::{
+    ecs::{Component, Entity, EntityMap, MapEntities, MapEntitiesError},
+    reflect::Reflect,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component, Reflect, Serialize, Deserialize)]
 pub struct Parent(pub(crate) Entity);
 
 impl Parent {
@@ -9,3 +14,10 @@ impl Parent {
         self.0
     }
 }
+
+impl MapEntities for Parent {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.0 = entity_map.get(self.0)?;
+        Ok(())
+    }
+}
@@ -2,13 +2,14 @@ use std::ops::Mul;
 
 use crate::{
     ecs::Component,
+    reflect::Reflect,
     ty::{Mat3, Mat4, Rad, Vec3},
 };
 use cgm::{ElementWise, Zero};
 
 use super::global_transform::GlobalTransform;
 
-#[derive(Debug, PartialEq, Clone, Copy, Component)]
+#[derive(Debug, PartialEq, Clone, Copy, Component, Reflect)]
 pub struct Transform {
     pub translation: Vec3,
     pub rotation: Rad,
@@ -17,7 +17,7 @@ pub use ui_node::*;
 use update::update_clipping_system;
 
 use crate::{
-    app::{App, MainStage},
+    app::{App, MainStage, Plugin},
     ecs::Resource,
     render::cameras::camera_type_plugin,
 };
@@ -47,6 +47,15 @@ impl Default for UiScale {
     }
 }
 
+#[derive(Default)]
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App, render_app: &mut App) {
+        ui_plugin(app, render_app);
+    }
+}
+
 pub fn ui_plugin(app: &mut App, render_app: &mut App) {
     app.world.spawn().insert_bundle(UiCameraBundle::default()); // TODO Allow to customize the camera
     camera_type_plugin::<CameraUi>(app, render_app);
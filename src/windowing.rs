@@ -10,15 +10,27 @@ pub use size::{FullScreen, LogicalSize, PhysicalSize, WindowSize};
 pub use window::{PresentMode, Window, WindowDescriptor, WindowId};
 pub use windows::Windows;
 
-use crate::app::App;
+use crate::app::{App, Plugin};
 
 pub mod prelude {
     pub use crate::windowing::{LogicalSize, PhysicalSize, Window, WindowDescriptor, Windows};
 }
 
+#[derive(Default)]
+pub struct WindowingPlugin;
+
+impl Plugin for WindowingPlugin {
+    fn build(&self, app: &mut App, _render_app: &mut App) {
+        windowing_plugin(app);
+    }
+}
+
 pub fn windowing_plugin(app: &mut App) {
-    app.init_resource::<Windows>()
-        .add_event::<WindowCreated>()
+    // Windows may already contain the primary window created before the plugins are built
+    if app.get_resource::<Windows>().is_none() {
+        app.init_resource::<Windows>();
+    }
+    app.add_event::<WindowCreated>()
         .add_event::<ReceivedCharacter>()
         .add_event::<WindowCloseRequested>()
         .add_event::<WindowResized>()